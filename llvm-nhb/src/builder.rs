@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use crate::llvm_c::{LLVMBuildBr, LLVMBuildRetVoid};
+use crate::llvm_c::{LLVMBuildBr, LLVMBuildRetVoid, LLVMBuildUnreachable};
 use crate::value::Value;
 
 use super::{basic_block::BasicBlock, context::Context, module::Module, traits::WrappedReference};
@@ -29,10 +29,32 @@ impl<'c, 'm> Builder<'c, 'm> {
 	pub fn build_branch(&self, dest: &BasicBlock<'c, 'm>) -> Value<'c, 'm> {
 		unsafe { Value::from_ref(LLVMBuildBr(self.builder_ref, dest.get_ref())) }
 	}
+
+	/// Terminates the current basic block with an instruction that control can never reach.
+	pub fn build_unreachable(&self) -> Value<'c, 'm> {
+		unsafe { Value::from_ref(LLVMBuildUnreachable(self.builder_ref)) }
+	}
 }
 
 impl<'c, 'm> Drop for Builder<'c, 'm> {
 	fn drop(&mut self) {
 		unsafe { LLVMDisposeBuilder(self.builder_ref) };
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::context::Context;
+
+	#[test]
+	fn build_unreachable() {
+		let context = Context::new();
+		let module = context.new_module("test");
+		let function = module.add_function(context.void_type().function_type(&[], false), "diverges");
+		let basic_block = function.append_basic_block(&context, "entry");
+		let builder = context.new_builder();
+		builder.position_at_end(&basic_block);
+		builder.build_unreachable();
+		assert!(module.print_to_string().contains("entry:\n  unreachable\n"));
+	}
+}
@@ -1,12 +1,12 @@
-use std::ffi::CString;
+use std::{ffi::CString, mem::transmute};
 
-use crate::llvm_c::{LLVMBool, LLVMConstStringInContext};
+use crate::llvm_c::{LLVMBool, LLVMConstStringInContext, LLVMMDNodeInContext, LLVMMDStringInContext};
 use crate::value::Value;
 
 use super::{builder::Builder, types::Type, module::Module, traits::WrappedReference};
 use super::llvm_c::{LLVMContextCreate, LLVMContextDispose, LLVMContextRef, LLVMCreateBuilderInContext, LLVMInt128TypeInContext};
 use super::llvm_c::{LLVMInt16TypeInContext, LLVMInt1TypeInContext, LLVMInt32TypeInContext, LLVMInt64TypeInContext, LLVMInt8TypeInContext};
-use super::llvm_c::{LLVMModuleCreateWithNameInContext, LLVMVoidTypeInContext};
+use super::llvm_c::{LLVMModuleCreateWithNameInContext, LLVMVoidTypeInContext, LLVMFloatTypeInContext, LLVMDoubleTypeInContext};
 
 #[allow(non_upper_case_globals)]
 static mut context_exists_in_this_thread: bool = false;
@@ -86,6 +86,16 @@ impl Context {
 		unsafe { Type::from_ref(LLVMInt128TypeInContext(self.context_ref)) }
 	}
 
+	#[inline]
+	pub fn float_32_type<'a>(&'a self) -> Type<'a> {
+		unsafe { Type::from_ref(LLVMFloatTypeInContext(self.context_ref)) }
+	}
+
+	#[inline]
+	pub fn float_64_type<'a>(&'a self) -> Type<'a> {
+		unsafe { Type::from_ref(LLVMDoubleTypeInContext(self.context_ref)) }
+	}
+
 	#[inline]
 	pub fn new_builder<'a>(&'a self) -> Builder<'a, 'a> {
 		unsafe { Builder::from_ref(LLVMCreateBuilderInContext(self.context_ref)) }
@@ -96,6 +106,16 @@ impl Context {
 			self.context_ref, string.as_ptr() as *const i8, string.len().try_into().unwrap(), !do_null_terminate as LLVMBool,
 		)) }
 	}
+
+	/// Create a metadata string, such as the `!"..."` operands of `llvm.ident`.
+	pub fn metadata_string<'a>(&'a self, string: &str) -> Value<'a, 'a> {
+		unsafe { Value::from_ref(LLVMMDStringInContext(self.context_ref, string.as_ptr() as *const i8, string.len().try_into().unwrap())) }
+	}
+
+	/// Create a metadata node containing each of the given values.
+	pub fn metadata_node<'a>(&'a self, values: &[Value<'a, 'a>]) -> Value<'a, 'a> {
+		unsafe { Value::from_ref(LLVMMDNodeInContext(self.context_ref, transmute(values.as_ptr()), values.len().try_into().unwrap())) }
+	}
 }
 
 impl Drop for Context {
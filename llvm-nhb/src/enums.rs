@@ -1,4 +1,5 @@
 #[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Linkage {
 	External = 0,
 	Internal = 8,
@@ -6,7 +7,10 @@ pub enum Linkage {
 }
 
 #[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CallingConvention {
+	C = 0,
+	X86StdCall = 64,
 	Win64 = 79,
 }
 
@@ -23,11 +27,32 @@ pub enum CodegenOptLevel {
 #[repr(C)]
 pub enum RealocMode {
 	Default = 0,
+	Static = 1,
+	PositionIndependent = 2,
 }
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub enum CodeModel {
 	Default = 0,
+	Tiny = 2,
+	Small = 3,
+	Kernel = 4,
+	Medium = 5,
+	Large = 6,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FastMathFlags {
+	AllowReassociation = 1,
+	NoNaNs = 2,
+	NoInfinities = 4,
+	NoSignedZeros = 8,
+	AllowReciprocal = 16,
+	AllowContract = 32,
+	ApproximateFunctions = 64,
+	Fast = 127,
 }
 
 #[repr(C)]
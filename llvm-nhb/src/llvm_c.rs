@@ -16,6 +16,7 @@ pub type LLVMRelocMode = c_int;
 pub type LLVMCodeModel = c_int;
 pub type LLVMLinkage = c_int;
 pub type LLVMCodeGenFileType = c_int;
+pub type LLVMFastMathFlags = c_uint;
 pub type LLVMIntPredicate = c_int;
 
 #[link(name = "C:/Program Files/LLVM/lib/LLVM-C")]
@@ -30,8 +31,16 @@ unsafe extern "C" {
 	pub unsafe fn LLVMModuleCreateWithNameInContext(ModuleID: *const c_char, C: LLVMContextRef) -> LLVMModuleRef;
 	pub unsafe fn LLVMDisposeModule(M: LLVMModuleRef) -> c_void;
 	pub unsafe fn LLVMDumpModule(M: LLVMModuleRef) -> c_void;
+	pub unsafe fn LLVMPrintModuleToString(M: LLVMModuleRef) -> *mut c_char;
 	pub unsafe fn LLVMSetTarget(M: LLVMModuleRef, Triple: *const c_char) -> c_void;
+	pub unsafe fn LLVMSetFastMathFlags(FPMathInst: LLVMValueRef, FMF: LLVMFastMathFlags) -> c_void;
+	pub unsafe fn LLVMSetSourceFileName(M: LLVMModuleRef, Name: *const c_char, Len: usize) -> c_void;
+	pub unsafe fn LLVMAddNamedMetadataOperand(M: LLVMModuleRef, Name: *const c_char, Val: LLVMValueRef) -> c_void;
 	pub unsafe fn LLVMAddFunction(M: LLVMModuleRef, Name: *const c_char, FunctionTy: LLVMTypeRef) -> LLVMValueRef;
+	pub unsafe fn LLVMGetNamedFunction(M: LLVMModuleRef, Name: *const c_char) -> LLVMValueRef;
+	pub unsafe fn LLVMGetNamedGlobal(M: LLVMModuleRef, Name: *const c_char) -> LLVMValueRef;
+	pub unsafe fn LLVMGetFirstFunction(M: LLVMModuleRef) -> LLVMValueRef;
+	pub unsafe fn LLVMGetNextFunction(Fn: LLVMValueRef) -> LLVMValueRef;
 	// Core/Types
 	pub unsafe fn LLVMGetTypeKind(Ty: LLVMTypeRef) -> LLVMTypeKind;
 	// Core/Types/Integer Types
@@ -41,12 +50,16 @@ unsafe extern "C" {
 	pub unsafe fn LLVMInt32TypeInContext(C: LLVMContextRef) -> LLVMTypeRef;
 	pub unsafe fn LLVMInt64TypeInContext(C: LLVMContextRef) -> LLVMTypeRef;
 	pub unsafe fn LLVMInt128TypeInContext(C: LLVMContextRef) -> LLVMTypeRef;
+	// Core/Types/Floating Point Types
+	pub unsafe fn LLVMFloatTypeInContext(C: LLVMContextRef) -> LLVMTypeRef;
+	pub unsafe fn LLVMDoubleTypeInContext(C: LLVMContextRef) -> LLVMTypeRef;
 	// Core/Types/Other Types
 	pub unsafe fn LLVMVoidTypeInContext(C: LLVMContextRef) -> LLVMTypeRef;
 	// Core/Types/Function Types
 	pub unsafe fn LLVMFunctionType(ReturnType: LLVMTypeRef, ParamTypes: *const LLVMTypeRef, ParamCount: c_uint, IsVarArg: LLVMBool) -> LLVMTypeRef;
 	pub unsafe fn LLVMCountParamTypes(FunctionTy: LLVMTypeRef) -> c_uint;
 	pub unsafe fn LLVMGetReturnType(FunctionTy: LLVMTypeRef) -> LLVMTypeRef;
+	pub unsafe fn LLVMGetIntTypeWidth(IntegerTy: LLVMTypeRef) -> c_uint;
 	pub unsafe fn LLVMIsFunctionVarArg(FunctionTy: LLVMTypeRef) -> LLVMBool;
 	pub unsafe fn LLVMGetParamTypes(FunctionTy: LLVMTypeRef, Dest: *mut LLVMTypeRef) -> c_void;
 	// Core/Types/Sequential Types
@@ -57,13 +70,20 @@ unsafe extern "C" {
 	pub unsafe fn LLVMGetUndef(Ty: LLVMTypeRef) -> LLVMValueRef;
 	// Core/Values/Constants/Global Values
 	pub unsafe fn LLVMSetLinkage(Global: LLVMValueRef, Linkage: LLVMLinkage) -> c_void;
+	pub unsafe fn LLVMGetLinkage(Global: LLVMValueRef) -> LLVMLinkage;
+	pub unsafe fn LLVMSetSection(Global: LLVMValueRef, Section: *const c_char) -> c_void;
 	// Core/Values/Constants/Global Variables
 	pub unsafe fn LLVMAddGlobal(M: LLVMModuleRef, Ty: LLVMTypeRef, Name: *const c_char) -> LLVMValueRef;
+	pub unsafe fn LLVMGetFirstGlobal(M: LLVMModuleRef) -> LLVMValueRef;
+	pub unsafe fn LLVMGetNextGlobal(GlobalVar: LLVMValueRef) -> LLVMValueRef;
 	pub unsafe fn LLVMSetInitializer(GlobalVar: LLVMValueRef, ConstantVal: LLVMValueRef) -> c_void;
 	pub unsafe fn LLVMGetInitializer(GlobalVar: LLVMValueRef) -> LLVMValueRef;
 	pub unsafe fn LLVMSetGlobalConstant(GlobalVar: LLVMValueRef, IsConstant: LLVMBool) -> c_void;
 	// Core/Values/Constants/Composite Constants
 	pub unsafe fn LLVMConstStringInContext(C: LLVMContextRef, Str: *const c_char, Length: c_uint, DontNullTerminate: LLVMBool) -> LLVMValueRef;
+	// Core/Metadata
+	pub unsafe fn LLVMMDStringInContext(C: LLVMContextRef, Str: *const c_char, SLen: c_uint) -> LLVMValueRef;
+	pub unsafe fn LLVMMDNodeInContext(C: LLVMContextRef, Vals: *const LLVMValueRef, Count: c_uint) -> LLVMValueRef;
 	// Core/Values/Constants/Function values
 	pub unsafe fn LLVMSetFunctionCallConv(Fn: LLVMValueRef, CC: c_uint) -> c_void;
 	// Core/Values/Constants/Function values/Function Parameters
@@ -71,12 +91,19 @@ unsafe extern "C" {
 	pub unsafe fn LLVMCountParams(Fn: LLVMValueRef) -> c_uint;
 	// Core/Values/Constants/Scalar constants
 	pub unsafe fn LLVMConstInt(IntTy: LLVMTypeRef, N: c_ulonglong, SignExtend: LLVMBool) -> LLVMValueRef;
+	// Core/Values/Constants/Constant expressions
+	pub unsafe fn LLVMConstPtrToInt(ConstantVal: LLVMValueRef, ToType: LLVMTypeRef) -> LLVMValueRef;
 	// Core/Values/General APIs
 	pub unsafe fn LLVMGetValueKind(Val: LLVMValueRef) -> LLVMValueKind;
 	pub unsafe fn LLVMTypeOf(Val: LLVMValueRef) -> LLVMTypeRef;
+	pub unsafe fn LLVMGetValueName2(Val: LLVMValueRef, Length: *mut usize) -> *const c_char;
+	pub unsafe fn LLVMIsACallInst(Val: LLVMValueRef) -> LLVMValueRef;
 	// Core/Basic Block
 	pub unsafe fn LLVMAppendBasicBlockInContext(C: LLVMContextRef, Fn: LLVMValueRef, Name: *const c_char) -> LLVMBasicBlockRef;
 	pub unsafe fn LLVMInsertBasicBlockInContext(C: LLVMContextRef, BB: LLVMBasicBlockRef, Name: *const c_char) -> LLVMBasicBlockRef;
+	// Core/Instructions/Call Sites and Invocations
+	pub unsafe fn LLVMSetTailCall(CallInst: LLVMValueRef, IsTailCall: LLVMBool) -> c_void;
+	pub unsafe fn LLVMSetInstructionCallConv(Instr: LLVMValueRef, CC: c_uint) -> c_void;
 	// Instruction Builders
 	pub unsafe fn LLVMCreateBuilderInContext(C: LLVMContextRef) -> LLVMBuilderRef;
 	pub unsafe fn LLVMDisposeBuilder(Builder: LLVMBuilderRef) -> c_void;
@@ -84,10 +111,16 @@ unsafe extern "C" {
 	pub unsafe fn LLVMBuildPtrToInt(B: LLVMBuilderRef, Val: LLVMValueRef, DestTy: LLVMTypeRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildIntToPtr(B: LLVMBuilderRef, Val: LLVMValueRef, DestTy: LLVMTypeRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildZExt(B: LLVMBuilderRef, Val: LLVMValueRef, DestTy: LLVMTypeRef, Name: *const c_char) -> LLVMValueRef;
+	pub unsafe fn LLVMBuildBitCast(B: LLVMBuilderRef, Val: LLVMValueRef, DestTy: LLVMTypeRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildSExt(B: LLVMBuilderRef, Val: LLVMValueRef, DestTy: LLVMTypeRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildTrunc(B: LLVMBuilderRef, Val: LLVMValueRef, DestTy: LLVMTypeRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildCall2(B: LLVMBuilderRef, Ty: LLVMTypeRef, Fn: LLVMValueRef, Args: *const LLVMValueRef, NumArgs: c_uint, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildAdd(B: LLVMBuilderRef, LHS: LLVMValueRef, RHS: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
+	pub unsafe fn LLVMBuildFAdd(B: LLVMBuilderRef, LHS: LLVMValueRef, RHS: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
+	pub unsafe fn LLVMBuildFSub(B: LLVMBuilderRef, LHS: LLVMValueRef, RHS: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
+	pub unsafe fn LLVMBuildFMul(B: LLVMBuilderRef, LHS: LLVMValueRef, RHS: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
+	pub unsafe fn LLVMBuildFDiv(B: LLVMBuilderRef, LHS: LLVMValueRef, RHS: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
+	pub unsafe fn LLVMBuildFRem(B: LLVMBuilderRef, LHS: LLVMValueRef, RHS: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildSub(B: LLVMBuilderRef, LHS: LLVMValueRef, RHS: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildMul(B: LLVMBuilderRef, LHS: LLVMValueRef, RHS: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildUDiv(B: LLVMBuilderRef, LHS: LLVMValueRef, RHS: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
@@ -99,8 +132,12 @@ unsafe extern "C" {
 	pub unsafe fn LLVMBuildXor(B: LLVMBuilderRef, LHS: LLVMValueRef, RHS: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildRet(B: LLVMBuilderRef, V: LLVMValueRef) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildRetVoid(B: LLVMBuilderRef) -> LLVMValueRef;
+	pub unsafe fn LLVMBuildUnreachable(B: LLVMBuilderRef) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildAlloca(B: LLVMBuilderRef, Ty: LLVMTypeRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildStore(B: LLVMBuilderRef, Val: LLVMValueRef, Ptr: LLVMValueRef) -> LLVMValueRef;
+	pub unsafe fn LLVMBuildMemCpy(
+		B: LLVMBuilderRef, Dst: LLVMValueRef, DstAlign: c_uint, Src: LLVMValueRef, SrcAlign: c_uint, Size: LLVMValueRef
+	) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildLoad2(B: LLVMBuilderRef, Ty: LLVMTypeRef, PointerVal: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildNeg(B: LLVMBuilderRef, V: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
 	pub unsafe fn LLVMBuildNot(B: LLVMBuilderRef, V: LLVMValueRef, Name: *const c_char) -> LLVMValueRef;
@@ -127,6 +164,7 @@ unsafe extern "C" {
 		T: LLVMTargetMachineRef, M: LLVMModuleRef, Filename: *const c_char, codegen: LLVMCodeGenFileType, ErrorMessage: *mut *mut c_char
 	) -> LLVMBool;
 	pub unsafe fn LLVMSizeOfTypeInBits(TD: LLVMTargetDataRef, Ty: LLVMTypeRef) -> c_ulonglong;
+	pub unsafe fn LLVMCopyStringRepOfTargetData(TD: LLVMTargetDataRef) -> *mut c_char;
 }
 
 #[allow(non_upper_case_globals)]
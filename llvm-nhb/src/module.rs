@@ -1,8 +1,9 @@
 use std::{ffi::{c_char, c_int, CStr, CString}, marker::PhantomData, ptr::null_mut};
 
 use super::{context::Context, enums::CodegenFileType, target_data::TargetData, target_machine::TargetMachine, traits::WrappedReference, types::Type, value::Value};
-use super::llvm_c::{LLVMAddFunction, LLVMAddGlobal, LLVMDisposeMessage, LLVMDisposeModule, LLVMDumpModule};
-use super::llvm_c::{LLVMModuleRef, LLVMSetModuleDataLayout, LLVMSetTarget, LLVMTargetMachineEmitToFile, LLVMTypeKind};
+use super::llvm_c::{LLVMAddFunction, LLVMAddGlobal, LLVMAddNamedMetadataOperand, LLVMDisposeMessage, LLVMDisposeModule, LLVMDumpModule, LLVMGetNamedFunction, LLVMGetNamedGlobal, LLVMPrintModuleToString};
+use super::llvm_c::{LLVMGetFirstFunction, LLVMGetFirstGlobal, LLVMGetNextFunction, LLVMGetNextGlobal};
+use super::llvm_c::{LLVMModuleRef, LLVMValueRef, LLVMSetModuleDataLayout, LLVMSetSourceFileName, LLVMSetTarget, LLVMTargetMachineEmitToFile, LLVMTypeKind};
 
 #[repr(transparent)]
 pub struct Module<'c> {
@@ -20,6 +21,14 @@ impl<'c> Module<'c> {
 		unsafe { LLVMDumpModule(self.module_ref) };
 	}
 
+	/// Get the textual LLVM IR of the module.
+	pub fn print_to_string(&self) -> String {
+		let string = unsafe { LLVMPrintModuleToString(self.module_ref) };
+		let result = unsafe { CStr::from_ptr(string) }.to_string_lossy().into_owned();
+		unsafe { LLVMDisposeMessage(string) };
+		result
+	}
+
 	pub fn add_global<'m>(&'m self, global_type: Type<'c>, name: &str) -> Value<'c, 'm> {
 		match global_type {
 			invalid if !invalid.is_normal() => panic!("Invalid global type {invalid:?}"),
@@ -38,6 +47,44 @@ impl<'c> Module<'c> {
 		unsafe { Value::from_ref(LLVMAddFunction(self.module_ref, name.as_ptr(), function_type.get_ref())) }
 	}
 
+	/// Get a function that has already been added to the module by its name.
+	pub fn get_function<'m>(&'m self, name: &str) -> Option<Value<'c, 'm>> {
+		let name = CString::new(name).unwrap();
+		let function_ref = unsafe { LLVMGetNamedFunction(self.module_ref, name.as_ptr()) };
+		match function_ref.is_null() {
+			true => None,
+			false => Some(unsafe { Value::from_ref(function_ref) }),
+		}
+	}
+
+	/// Get a global variable that has already been added to the module by its name.
+	pub fn get_global<'m>(&'m self, name: &str) -> Option<Value<'c, 'm>> {
+		let name = CString::new(name).unwrap();
+		let global_ref = unsafe { LLVMGetNamedGlobal(self.module_ref, name.as_ptr()) };
+		match global_ref.is_null() {
+			true => None,
+			false => Some(unsafe { Value::from_ref(global_ref) }),
+		}
+	}
+
+	/// Iterate over each function in the module in the order they where added.
+	pub fn functions<'m>(&'m self) -> ModuleValues<'c, 'm> {
+		ModuleValues {
+			next_ref: unsafe { LLVMGetFirstFunction(self.module_ref) },
+			get_next: LLVMGetNextFunction,
+			phantom_data: PhantomData,
+		}
+	}
+
+	/// Iterate over each global variable in the module in the order they where added.
+	pub fn globals<'m>(&'m self) -> ModuleValues<'c, 'm> {
+		ModuleValues {
+			next_ref: unsafe { LLVMGetFirstGlobal(self.module_ref) },
+			get_next: LLVMGetNextGlobal,
+			phantom_data: PhantomData,
+		}
+	}
+
 	pub fn set_data_layout(&self, data_layout: &TargetData) {
 		unsafe { LLVMSetModuleDataLayout(self.module_ref, data_layout.get_ref()) };
 	}
@@ -47,6 +94,17 @@ impl<'c> Module<'c> {
 		unsafe { LLVMSetTarget(self.module_ref, target_triple.as_ptr()) };
 	}
 
+	/// Set the source filename that is recorded in the module and the emitted object file, this defaults to the module name.
+	pub fn set_source_file_name(&self, source_file_name: &str) {
+		unsafe { LLVMSetSourceFileName(self.module_ref, source_file_name.as_ptr() as *const c_char, source_file_name.len()) };
+	}
+
+	/// Append a metadata node to the named metadata `name` of the module, creating it if it does not exist.
+	pub fn add_named_metadata_operand(&self, name: &str, value: Value) {
+		let name = CString::new(name).unwrap();
+		unsafe { LLVMAddNamedMetadataOperand(self.module_ref, name.as_ptr(), value.get_ref()) };
+	}
+
 	pub fn emit_to_file(&self, target_machine: &TargetMachine, filepath: &str, codegen_type: CodegenFileType) -> Result<(), String> {
 		let mut error: *mut c_char = null_mut();
 		let filepath = CString::new(filepath).unwrap();
@@ -65,6 +123,26 @@ impl<'c> Module<'c> {
 	}
 }
 
+/// An iterator over the functions or global variables of a module.
+pub struct ModuleValues<'c, 'm> {
+	next_ref: LLVMValueRef,
+	get_next: unsafe extern "C" fn(LLVMValueRef) -> LLVMValueRef,
+	phantom_data: PhantomData<&'m Module<'c>>,
+}
+
+impl<'c, 'm> Iterator for ModuleValues<'c, 'm> {
+	type Item = Value<'c, 'm>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.next_ref.is_null() {
+			return None;
+		}
+		let value = unsafe { Value::from_ref(self.next_ref) };
+		self.next_ref = unsafe { (self.get_next)(self.next_ref) };
+		Some(value)
+	}
+}
+
 impl<'c> Drop for Module<'c> {
 	#[inline]
 	fn drop(&mut self) {
@@ -72,4 +150,33 @@ impl<'c> Drop for Module<'c> {
 			LLVMDisposeModule(self.module_ref);
 		}
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::context::Context;
+
+	#[test]
+	fn functions_in_order() {
+		let context = Context::new();
+		let module = context.new_module("test");
+		let function_type = context.void_type().function_type(&[], false);
+		for name in ["first", "second", "third"] {
+			module.add_function(function_type, name);
+		}
+		let names: Vec<String> = module.functions().map(|function| function.get_name()).collect();
+		assert_eq!(names, ["first", "second", "third"]);
+	}
+
+	#[test]
+	fn globals_in_order() {
+		let context = Context::new();
+		let module = context.new_module("test");
+		for name in ["first", "second"] {
+			module.add_global(context.int_64_type(), name);
+		}
+		let names: Vec<String> = module.globals().map(|global| global.get_name()).collect();
+		assert_eq!(names, ["first", "second"]);
+		assert_eq!(module.functions().count(), 0);
+	}
+}
@@ -1,6 +1,6 @@
-use std::marker::PhantomData;
+use std::{ffi::CStr, marker::PhantomData};
 
-use super::{context::Context, llvm_c::{LLVMIntPtrTypeInContext, LLVMTargetDataRef}, traits::WrappedReference, types::Type};
+use super::{context::Context, llvm_c::{LLVMCopyStringRepOfTargetData, LLVMDisposeMessage, LLVMIntPtrTypeInContext, LLVMTargetDataRef}, traits::WrappedReference, types::Type};
 
 #[repr(transparent)]
 pub struct TargetData<'a> {
@@ -13,6 +13,14 @@ impl<'a> TargetData<'a> {
 	pub fn int_ptr_type(&self, context: &'a Context) -> Type<'a> {
 		unsafe { Type::from_ref(LLVMIntPtrTypeInContext(context.get_ref(), self.target_data_ref)) }
 	}
+
+	/// Get the data layout string of the target data, such as `e-m:e-p270:32:32-i64:64-n8:16:32:64-S128`.
+	pub fn string_rep(&self) -> String {
+		let string_rep = unsafe { LLVMCopyStringRepOfTargetData(self.target_data_ref) };
+		let result = unsafe { CStr::from_ptr(string_rep) }.to_string_lossy().into_owned();
+		unsafe { LLVMDisposeMessage(string_rep) };
+		result
+	}
 }
 
 unsafe impl<'a> WrappedReference for TargetData<'a> {
@@ -3,7 +3,7 @@ use std::{ffi::{c_uint, CString}, fmt::Debug, iter::repeat, marker::PhantomData,
 use crate::llvm_c::LLVMArrayType2;
 
 use super::{builder::Builder, context::Context, target_data::TargetData, traits::WrappedReference, value::Value};
-use super::llvm_c::{LLVMBool, LLVMBuildAlloca, LLVMConstInt, LLVMCountParamTypes, LLVMFunctionType, LLVMGetParamTypes, LLVMGetReturnType};
+use super::llvm_c::{LLVMBool, LLVMBuildAlloca, LLVMConstInt, LLVMCountParamTypes, LLVMFunctionType, LLVMGetIntTypeWidth, LLVMGetParamTypes, LLVMGetReturnType};
 use super::llvm_c::{LLVMGetTypeKind, LLVMGetUndef, LLVMIsFunctionVarArg, LLVMPointerType, LLVMSizeOfTypeInBits, LLVMTypeKind, LLVMTypeRef};
 
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
@@ -102,6 +102,12 @@ impl<'a> Type<'a> {
 		self.type_kind() == LLVMTypeKind::LLVMVoidTypeKind
 	}
 
+	/// Returns if this is an array or struct type, values of these types should be copied in memory rather than loaded and stored.
+	#[inline]
+	pub fn is_aggregate(self) -> bool {
+		matches!(self.type_kind(), LLVMTypeKind::LLVMArrayTypeKind | LLVMTypeKind::LLVMStructTypeKind)
+	}
+
 	#[inline]
 	pub(crate) fn type_kind(self) -> LLVMTypeKind {
 		unsafe { LLVMGetTypeKind(self.get_ref()) }
@@ -121,6 +127,13 @@ impl<'a> Type<'a> {
 		}
 	}
 
+	pub fn int_width(self) -> u32 {
+		match self.type_kind() {
+			LLVMTypeKind::LLVMIntegerTypeKind => unsafe { LLVMGetIntTypeWidth(self.get_ref()) },
+			other => panic!("Type is not an int type: {:?}", other),
+		}
+	}
+
 	pub fn is_variadic(self) -> bool {
 		match self.type_kind() {
 			LLVMTypeKind::LLVMFunctionTypeKind => unsafe { LLVMIsFunctionVarArg(self.get_ref()) != 0 },
@@ -1,13 +1,13 @@
 use core::panic;
-use std::{ffi::{c_int, c_uint, CString}, fmt::{Debug, Formatter, Write}, marker::PhantomData, mem::transmute};
+use std::{cmp::Ordering, ffi::{c_int, c_uint, CString}, fmt::{Debug, Formatter, Write}, marker::PhantomData, mem::transmute};
 
-use crate::{enums::Comparison, llvm_c::{LLVMBool, LLVMBuildAnd, LLVMBuildCondBr, LLVMBuildGEP2, LLVMBuildICmp, LLVMBuildNot, LLVMBuildOr, LLVMBuildXor, LLVMSetGlobalConstant}};
+use crate::{enums::{Comparison, FastMathFlags}, llvm_c::{LLVMBool, LLVMFastMathFlags, LLVMSetFastMathFlags, LLVMBuildFAdd, LLVMBuildFSub, LLVMBuildFMul, LLVMBuildFDiv, LLVMBuildFRem, LLVMBuildAnd, LLVMBuildCondBr, LLVMBuildGEP2, LLVMBuildICmp, LLVMBuildNot, LLVMBuildOr, LLVMBuildXor, LLVMSetGlobalConstant}};
 
 use super::{basic_block::BasicBlock, builder::Builder, context::Context, enums::{CallingConvention, Linkage}, module::Module, traits::WrappedReference, types::Type};
 use super::llvm_c::{LLVMAppendBasicBlockInContext, LLVMBuildAdd, LLVMBuildCall2, LLVMBuildIntToPtr, LLVMBuildLoad2, LLVMBuildMul, LLVMBuildNeg, LLVMSetLinkage};
 use super::llvm_c::{LLVMBuildPtrToInt, LLVMBuildRet, LLVMBuildSDiv, LLVMBuildSExt, LLVMBuildSRem, LLVMBuildStore, LLVMBuildSub, LLVMBuildTrunc, LLVMSetInitializer};
-use super::llvm_c::{LLVMBuildUDiv, LLVMBuildURem, LLVMBuildZExt, LLVMCountParams, LLVMGetParam, LLVMGetValueKind, LLVMTypeOf, LLVMSetFunctionCallConv};
-use super::llvm_c::{LLVMTypeKind, LLVMLinkage, LLVMValueKind, LLVMValueRef};
+use super::llvm_c::{LLVMBuildUDiv, LLVMBuildURem, LLVMBuildZExt, LLVMBuildBitCast, LLVMCountParams, LLVMGetParam, LLVMGetValueKind, LLVMTypeOf, LLVMSetFunctionCallConv};
+use super::llvm_c::{LLVMTypeKind, LLVMLinkage, LLVMValueKind, LLVMValueRef, LLVMSetSection, LLVMSetTailCall, LLVMGetLinkage, LLVMGetValueName2, LLVMSetInstructionCallConv, LLVMBuildMemCpy, LLVMConstPtrToInt, LLVMIsACallInst};
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -27,6 +27,12 @@ impl<'c, 'm> Value<'c, 'm> where Value<'c, 'm>: Sized {
 		unsafe { LLVMGetValueKind(self.value_ref) }
 	}
 
+	/// Returns if this value is a call instruction.
+	#[inline]
+	pub fn is_call_instruction(&self) -> bool {
+		unsafe { !LLVMIsACallInst(self.value_ref).is_null() }
+	}
+
 	#[inline]
 	pub fn get_type(&self) -> Type<'c> {
 		unsafe { Type::from_ref(LLVMTypeOf(self.value_ref)) }
@@ -65,6 +71,19 @@ impl<'c, 'm> Value<'c, 'm> where Value<'c, 'm>: Sized {
 		unsafe { Self::from_ref(LLVMBuildPtrToInt(builder.get_ref(), self.value_ref, dest_type.get_ref(), name.as_ptr())) }
 	}
 
+	/// Get a constant expression that converts this constant pointer to an int, such as the address of a global, that can be used as the initializer of a global.
+	pub fn const_ptr_to_int(&self, dest_type: Type<'c>) -> Self {
+		let input_type_kind = self.get_type().type_kind();
+		if !matches!(input_type_kind, LLVMTypeKind::LLVMFunctionTypeKind | LLVMTypeKind::LLVMPointerTypeKind) {
+			panic!("Invalid input type kind {:?}", input_type_kind);
+		}
+		let dest_type_kind = dest_type.type_kind();
+		if !matches!(dest_type_kind, LLVMTypeKind::LLVMIntegerTypeKind) {
+			panic!("Invalid dest type kind {:?}", dest_type_kind);
+		}
+		unsafe { Self::from_ref(LLVMConstPtrToInt(self.value_ref, dest_type.get_ref())) }
+	}
+
 	pub fn build_int_to_ptr(&self, builder: &Builder<'c, 'm>, dest_type: Type<'c>, name: &str) -> Self {
 		let input_type_kind = self.get_type().type_kind();
 		if !matches!(input_type_kind, LLVMTypeKind::LLVMIntegerTypeKind) {
@@ -91,6 +110,41 @@ impl<'c, 'm> Value<'c, 'm> where Value<'c, 'm>: Sized {
 		unsafe { Self::from_ref(LLVMBuildZExt(builder.get_ref(), self.value_ref, dest_type.get_ref(), name.as_ptr())) }
 	}
 
+	/// Convert an int value to another int type, truncating if the type is narrower or extending by `is_signed` if it is wider.
+	pub fn build_int_cast(&self, builder: &Builder<'c, 'm>, dest_type: Type<'c>, is_signed: bool, name: &str) -> Self {
+		let input_type = self.get_type();
+		let input_type_kind = input_type.type_kind();
+		if !matches!(input_type_kind, LLVMTypeKind::LLVMIntegerTypeKind) {
+			panic!("Invalid input type kind {:?}", input_type_kind);
+		}
+		let dest_type_kind = dest_type.type_kind();
+		if !matches!(dest_type_kind, LLVMTypeKind::LLVMIntegerTypeKind) {
+			panic!("Invalid dest type kind {:?}", dest_type_kind);
+		}
+		match input_type.int_width().cmp(&dest_type.int_width()) {
+			Ordering::Greater => self.build_truncate(builder, dest_type, name),
+			Ordering::Equal => self.clone(),
+			Ordering::Less => match is_signed {
+				false => self.build_zero_extend(builder, dest_type, name),
+				true => self.build_sign_extend(builder, dest_type, name),
+			}
+		}
+	}
+
+	/// Reinterpret the bits of an int or float value as another int or float type of the same width, such as getting the bit pattern of a float.
+	pub fn build_bit_cast(&self, builder: &Builder<'c, 'm>, dest_type: Type<'c>, name: &str) -> Self {
+		let input_type_kind = self.get_type().type_kind();
+		if !matches!(input_type_kind, LLVMTypeKind::LLVMIntegerTypeKind | LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid input type kind {:?}", input_type_kind);
+		}
+		let dest_type_kind = dest_type.type_kind();
+		if !matches!(dest_type_kind, LLVMTypeKind::LLVMIntegerTypeKind | LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid dest type kind {:?}", dest_type_kind);
+		}
+		let name = CString::new(name).unwrap();
+		unsafe { Self::from_ref(LLVMBuildBitCast(builder.get_ref(), self.value_ref, dest_type.get_ref(), name.as_ptr())) }
+	}
+
 	pub fn build_sign_extend(&self, builder: &Builder<'c, 'm>, dest_type: Type<'c>, name: &str) -> Self {
 		let input_type_kind = self.get_type().type_kind();
 		if !matches!(input_type_kind, LLVMTypeKind::LLVMIntegerTypeKind) {
@@ -135,6 +189,102 @@ impl<'c, 'm> Value<'c, 'm> where Value<'c, 'm>: Sized {
 		unsafe { Self::from_ref(LLVMBuildAdd(builder.get_ref(), self.value_ref, rhs.value_ref, name.as_ptr())) }
 	}
 
+	pub fn build_float_add(&self, rhs: &Self, builder: &Builder<'c, 'm>, name: &str) -> Self {
+		let input_type = self.get_type();
+		let input_type_kind = input_type.type_kind();
+		if !matches!(input_type_kind, LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid LHS input type kind {:?}", input_type_kind);
+		}
+		let rhs_type = rhs.get_type();
+		let rhs_type_kind = rhs_type.type_kind();
+		if !matches!(rhs_type_kind, LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid RHS input type kind {:?}", rhs_type_kind);
+		}
+		if input_type != rhs_type {
+			panic!("Type mismatch");
+		}
+		let name = CString::new(name).unwrap();
+		unsafe { Self::from_ref(LLVMBuildFAdd(builder.get_ref(), self.value_ref, rhs.value_ref, name.as_ptr())) }
+	}
+
+	pub fn build_float_sub(&self, rhs: &Self, builder: &Builder<'c, 'm>, name: &str) -> Self {
+		let input_type = self.get_type();
+		let input_type_kind = input_type.type_kind();
+		if !matches!(input_type_kind, LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid LHS input type kind {:?}", input_type_kind);
+		}
+		let rhs_type = rhs.get_type();
+		let rhs_type_kind = rhs_type.type_kind();
+		if !matches!(rhs_type_kind, LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid RHS input type kind {:?}", rhs_type_kind);
+		}
+		if input_type != rhs_type {
+			panic!("Type mismatch");
+		}
+		let name = CString::new(name).unwrap();
+		unsafe { Self::from_ref(LLVMBuildFSub(builder.get_ref(), self.value_ref, rhs.value_ref, name.as_ptr())) }
+	}
+
+	pub fn build_float_mult(&self, rhs: &Self, builder: &Builder<'c, 'm>, name: &str) -> Self {
+		let input_type = self.get_type();
+		let input_type_kind = input_type.type_kind();
+		if !matches!(input_type_kind, LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid LHS input type kind {:?}", input_type_kind);
+		}
+		let rhs_type = rhs.get_type();
+		let rhs_type_kind = rhs_type.type_kind();
+		if !matches!(rhs_type_kind, LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid RHS input type kind {:?}", rhs_type_kind);
+		}
+		if input_type != rhs_type {
+			panic!("Type mismatch");
+		}
+		let name = CString::new(name).unwrap();
+		unsafe { Self::from_ref(LLVMBuildFMul(builder.get_ref(), self.value_ref, rhs.value_ref, name.as_ptr())) }
+	}
+
+	pub fn build_float_div(&self, rhs: &Self, builder: &Builder<'c, 'm>, name: &str) -> Self {
+		let input_type = self.get_type();
+		let input_type_kind = input_type.type_kind();
+		if !matches!(input_type_kind, LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid LHS input type kind {:?}", input_type_kind);
+		}
+		let rhs_type = rhs.get_type();
+		let rhs_type_kind = rhs_type.type_kind();
+		if !matches!(rhs_type_kind, LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid RHS input type kind {:?}", rhs_type_kind);
+		}
+		if input_type != rhs_type {
+			panic!("Type mismatch");
+		}
+		let name = CString::new(name).unwrap();
+		unsafe { Self::from_ref(LLVMBuildFDiv(builder.get_ref(), self.value_ref, rhs.value_ref, name.as_ptr())) }
+	}
+
+	pub fn build_float_truncated_modulo(&self, rhs: &Self, builder: &Builder<'c, 'm>, name: &str) -> Self {
+		let input_type = self.get_type();
+		let input_type_kind = input_type.type_kind();
+		if !matches!(input_type_kind, LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid LHS input type kind {:?}", input_type_kind);
+		}
+		let rhs_type = rhs.get_type();
+		let rhs_type_kind = rhs_type.type_kind();
+		if !matches!(rhs_type_kind, LLVMTypeKind::LLVMFloatTypeKind | LLVMTypeKind::LLVMDoubleTypeKind) {
+			panic!("Invalid RHS input type kind {:?}", rhs_type_kind);
+		}
+		if input_type != rhs_type {
+			panic!("Type mismatch");
+		}
+		let name = CString::new(name).unwrap();
+		unsafe { Self::from_ref(LLVMBuildFRem(builder.get_ref(), self.value_ref, rhs.value_ref, name.as_ptr())) }
+	}
+
+	/// Set fast-math flags on a float instruction, allowing LLVM to optimize it in ways that do not follow IEEE 754.
+	pub fn set_fast_math_flags(&self, flags: &[FastMathFlags]) {
+		let flags = flags.iter().fold(0, |all_flags, flag| all_flags | *flag as LLVMFastMathFlags);
+		unsafe { LLVMSetFastMathFlags(self.value_ref, flags) };
+	}
+
 	pub fn build_sub(&self, rhs: &Self, builder: &Builder<'c, 'm>, name: &str) -> Self {
 		let input_type = self.get_type();
 		let input_type_kind = input_type.type_kind();
@@ -366,6 +516,15 @@ impl<'c, 'm> Value<'c, 'm> where Value<'c, 'm>: Sized {
 		}
 	}
 
+	/// Build a call to a function that uses a calling convention other than the default, the convention of the call must match the callee.
+	pub fn build_call_with_calling_convention(
+		&self, arguments: &[Self], function_type: Type<'c>, calling_convention: CallingConvention, builder: &Builder<'c, 'm>, name: &str
+	) -> Self {
+		let call = self.build_call(arguments, function_type, builder, name);
+		unsafe { LLVMSetInstructionCallConv(call.value_ref, calling_convention as c_uint) };
+		call
+	}
+
 	pub fn build_conditional_branch(&self, then_dest: &BasicBlock<'c, 'm>, else_dest: &BasicBlock<'c, 'm>, context: &'c Context, builder: &Builder<'c, 'm>) -> Value<'c, 'm> {
 		if self.get_type() != context.int_1_type() {
 			panic!("Condition type should be i1, is {self:?}")
@@ -431,6 +590,19 @@ impl<'c, 'm> Value<'c, 'm> where Value<'c, 'm>: Sized {
 		unsafe { Self::from_ref(LLVMBuildStore(builder.get_ref(), value_to_store.value_ref, self.value_ref)) }
 	}
 
+	/// Copies `size` bytes from the `source` pointer to the `self` pointer with a call to `llvm.memcpy`, both pointers are assumed to have an alignment of 1.
+	pub fn build_memcpy(&self, source: &Self, size: &Self, builder: &Builder<'c, 'm>) -> Self {
+		for pointer in [self, source] {
+			if pointer.get_type().type_kind() != LLVMTypeKind::LLVMPointerTypeKind {
+				panic!("Memcpy with non-pointer operand {pointer:?}");
+			}
+		}
+		if size.get_type().type_kind() != LLVMTypeKind::LLVMIntegerTypeKind {
+			panic!("Memcpy with non-integer size {size:?}");
+		}
+		unsafe { Self::from_ref(LLVMBuildMemCpy(builder.get_ref(), self.value_ref, 1, source.value_ref, 1, size.value_ref)) }
+	}
+
 	pub fn build_return(&self, builder: &Builder) ->Self {
 		let self_type = self.get_type();
 		if !self_type.is_normal() {
@@ -457,6 +629,36 @@ impl<'c, 'm> Value<'c, 'm> where Value<'c, 'm>: Sized {
 		unsafe { LLVMSetLinkage(self.value_ref, linkage as LLVMLinkage) };
 	}
 
+	/// Get the linkage of a global variable or function, `None` if it is a linkage that is not in `Linkage`.
+	pub fn get_linkage(&self) -> Option<Linkage> {
+		match (self.value_kind(), self.get_type().type_kind()) {
+			(LLVMValueKind::LLVMGlobalVariableValueKind | LLVMValueKind::LLVMFunctionValueKind, LLVMTypeKind::LLVMPointerTypeKind) => {}
+			_ => panic!("Invalid input value {self:?}, should be global variable/function")
+		}
+		match unsafe { LLVMGetLinkage(self.value_ref) } {
+			0 => Some(Linkage::External),
+			8 => Some(Linkage::Internal),
+			10 => Some(Linkage::DLLImport),
+			_ => None,
+		}
+	}
+
+	/// Get the name of the value, empty if it does not have one.
+	pub fn get_name(&self) -> String {
+		let mut length = 0;
+		let name = unsafe { LLVMGetValueName2(self.value_ref, &mut length) };
+		String::from_utf8_lossy(unsafe { std::slice::from_raw_parts(name as *const u8, length) }).into_owned()
+	}
+
+	pub fn set_section(&self, section: &str) {
+		match (self.value_kind(), self.get_type().type_kind()) {
+			(LLVMValueKind::LLVMGlobalVariableValueKind | LLVMValueKind::LLVMFunctionValueKind, LLVMTypeKind::LLVMPointerTypeKind) => {}
+			_ => panic!("Invalid input value {self:?}, should be global variable/function")
+		}
+		let section = CString::new(section).unwrap();
+		unsafe { LLVMSetSection(self.value_ref, section.as_ptr()) };
+	}
+
 	pub fn set_calling_convention(&self, calling_convention: CallingConvention) {
 		match (self.value_kind(), self.get_type().type_kind()) {
 			(LLVMValueKind::LLVMFunctionValueKind, LLVMTypeKind::LLVMPointerTypeKind) => {}
@@ -464,6 +666,14 @@ impl<'c, 'm> Value<'c, 'm> where Value<'c, 'm>: Sized {
 		}
 		unsafe { LLVMSetFunctionCallConv(self.value_ref, calling_convention as c_uint) };
 	}
+
+	/// Marks a call instruction as a tail call, the callee must not access any allocas of the caller.
+	pub fn set_tail_call(&self, is_tail_call: bool) {
+		if !self.is_call_instruction() {
+			panic!("Invalid input value {self:?}, should be call instruction");
+		}
+		unsafe { LLVMSetTailCall(self.value_ref, is_tail_call as LLVMBool) };
+	}
 }
 
 impl<'c, 'm> Debug for Value<'c, 'm> {
@@ -472,4 +682,93 @@ impl<'c, 'm> Debug for Value<'c, 'm> {
 		f.write_char('/')?;
 		self.value_kind().fmt(f)
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{context::Context, enums::CallingConvention};
+
+	#[test]
+	fn build_memcpy() {
+		let context = Context::new();
+		let module = context.new_module("test");
+		let function = module.add_function(context.void_type().function_type(&[], false), "copy_array");
+		let builder = context.new_builder();
+		builder.position_at_end(&function.append_basic_block(&context, "entry"));
+		let array_type = context.int_64_type().array_type(4);
+		assert!(array_type.is_aggregate());
+		assert!(!context.int_64_type().is_aggregate());
+		let source = array_type.build_alloca(&builder, "source");
+		let destination = array_type.build_alloca(&builder, "destination");
+		destination.build_memcpy(&source, &context.int_64_type().const_int(32, false), &builder);
+		builder.build_return_void();
+		let llvm_ir = module.print_to_string();
+		assert!(llvm_ir.contains("call void @llvm.memcpy.p0"), "{llvm_ir}");
+		assert!(llvm_ir.contains("%destination") && llvm_ir.contains("%source") && llvm_ir.contains("i64 32, i1 false)"), "{llvm_ir}");
+	}
+
+	#[test]
+	fn build_call_with_calling_convention() {
+		let context = Context::new();
+		let module = context.new_module("test");
+		let function_type = context.void_type().function_type(&[], false);
+		let callee = module.add_function(function_type, "callee");
+		let caller = module.add_function(function_type, "caller");
+		let builder = context.new_builder();
+		builder.position_at_end(&caller.append_basic_block(&context, "entry"));
+		callee.build_call_with_calling_convention(&[], function_type, CallingConvention::Win64, &builder, "");
+		builder.build_return_void();
+		assert!(module.print_to_string().contains("  call win64cc void @callee()\n"));
+	}
+
+	#[test]
+	fn get_name() {
+		let context = Context::new();
+		let module = context.new_module("test");
+		let function = module.add_function(context.void_type().function_type(&[context.int_64_type()], false), "función");
+		assert_eq!(function.get_name(), "función");
+		assert_eq!(function.get_parameter(0).get_name(), "");
+	}
+
+	#[test]
+	fn build_bit_cast() {
+		let context = Context::new();
+		let module = context.new_module("test");
+		let builder = context.new_builder();
+		// Get the bit pattern of a float
+		let float_to_bits = module.add_function(context.int_32_type().function_type(&[context.float_32_type()], false), "float_to_bits");
+		builder.position_at_end(&float_to_bits.append_basic_block(&context, "entry"));
+		float_to_bits.get_parameter(0).build_bit_cast(&builder, context.int_32_type(), "bits").build_return(&builder);
+		// And back again
+		let bits_to_float = module.add_function(context.float_64_type().function_type(&[context.int_64_type()], false), "bits_to_float");
+		builder.position_at_end(&bits_to_float.append_basic_block(&context, "entry"));
+		bits_to_float.get_parameter(0).build_bit_cast(&builder, context.float_64_type(), "float").build_return(&builder);
+		let llvm_ir = module.print_to_string();
+		assert!(llvm_ir.contains("%bits = bitcast float %0 to i32"), "{llvm_ir}");
+		assert!(llvm_ir.contains("%float = bitcast i64 %0 to double"), "{llvm_ir}");
+	}
+
+	#[test]
+	fn build_int_cast() {
+		let context = Context::new();
+		let module = context.new_module("test");
+		let builder = context.new_builder();
+		let function_type = context.void_type().function_type(&[context.int_8_type(), context.int_64_type()], false);
+		let function = module.add_function(function_type, "casts");
+		builder.position_at_end(&function.append_basic_block(&context, "entry"));
+		let narrow = function.get_parameter(0);
+		let wide = function.get_parameter(1);
+		narrow.build_int_cast(&builder, context.int_64_type(), true, "widen_signed");
+		narrow.build_int_cast(&builder, context.int_64_type(), false, "widen_unsigned");
+		wide.build_int_cast(&builder, context.int_8_type(), true, "truncate");
+		let same = wide.build_int_cast(&builder, context.int_64_type(), true, "same");
+		builder.build_return_void();
+		let llvm_ir = module.print_to_string();
+		assert!(llvm_ir.contains("%widen_signed = sext i8 %0 to i64"), "{llvm_ir}");
+		assert!(llvm_ir.contains("%widen_unsigned = zext i8 %0 to i64"), "{llvm_ir}");
+		assert!(llvm_ir.contains("%truncate = trunc i64 %1 to i8"), "{llvm_ir}");
+		// Casting to the same width builds nothing and returns the input value
+		assert!(!llvm_ir.contains("%same"), "{llvm_ir}");
+		assert_eq!(same.value_ref, wide.value_ref);
+	}
+}
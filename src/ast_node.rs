@@ -1,9 +1,9 @@
-use std::{cmp::Ordering, collections::{HashMap, HashSet}, hash::{DefaultHasher, Hash, Hasher}, iter::{repeat, repeat_n}, mem::{swap, take}, num::NonZeroUsize, path::PathBuf};
+use std::{collections::{HashMap, HashSet}, mem::{replace, take}, num::NonZeroUsize, path::PathBuf};
 
 use strum_macros::EnumDiscriminants;
 
-use crate::{built_value::{BuiltLValue, BuiltRValue}, compile::relative_filepath_to_absolute, error::Error, file_build_data::FileBuildData, function_building_data::{BlockLevel, FunctionBuildData}, token::Keyword, MainData};
-use llvm_nhb::{builder::Builder, enums::{CallingConvention, Comparison, Linkage}, module::Module, types::Type, value::Value};
+use crate::{built_value::{BuiltLValue, BuiltRValue}, compile::relative_filepath_to_absolute, compiler_arguments::{LanguageVersion, TrapMode}, error::Error, file_build_data::{FileBuildData, MangledSymbol}, function_building_data::{BlockLevel, FunctionBuildData}, position::{Span, GENERATED_POSITION}, token::Keyword, MainData, ParameterDefaults};
+use llvm_nhb::{builder::Builder, enums::{CallingConvention, Comparison, FastMathFlags, Linkage}, module::Module, types::Type, value::Value};
 
 #[derive(Debug, Clone)]
 pub enum Operation {
@@ -64,6 +64,41 @@ pub enum Operation {
 	ArithmeticRightBitShift,
 }
 
+impl Operation {
+	/// Get how many operands this operation takes.
+	pub const fn operand_count(&self) -> usize {
+		match self {
+			Self::Read | Self::IntegerNegate | Self::FloatNegate | Self::Dereference | Self::TakeReference | Self::BitwiseNot | Self::LogicalNot |
+			Self::PrefixIntegerIncrement | Self::SuffixIntegerIncrement | Self::PrefixIntegerDecrement | Self::SuffixIntegerDecrement => 1,
+			Self::NotShortCircuitTernary | Self::ShortCircuitTernary => 3,
+			_ => 2,
+		}
+	}
+
+	/// Get the name of the function that `--operator-overloading` desugars this operation to, such as `__add` for `a + b`.
+	pub const fn overload_function_name(&self) -> Option<&'static str> {
+		Some(match self {
+			Self::IntegerAdd => "__add",
+			Self::IntegerSubtract => "__sub",
+			Self::IntegerMultiply => "__mul",
+			Self::SignedDivide | Self::UnsignedDivide => "__div",
+			Self::SignedTruncatedModulo | Self::UnsignedModulo => "__mod",
+			Self::BitwiseAnd => "__and",
+			Self::BitwiseOr => "__or",
+			Self::BitwiseXor => "__xor",
+			Self::LogicalLeftBitShift => "__shl",
+			Self::LogicalRightBitShift | Self::ArithmeticRightBitShift => "__shr",
+			Self::IntegerEqualTo => "__eq",
+			Self::IntegerNotEqualTo => "__ne",
+			Self::SignedLessThan | Self::UnsignedLessThan => "__lt",
+			Self::SignedLessThanOrEqualTo | Self::UnsignedLessThanOrEqualTo => "__le",
+			Self::SignedGreaterThan | Self::UnsignedGreaterThan => "__gt",
+			Self::SignedGreaterThanOrEqualTo | Self::UnsignedGreaterThanOrEqualTo => "__ge",
+			_ => return None,
+		})
+	}
+}
+
 #[derive(Debug, Clone)]
 pub enum Operator {
 	Assignment,
@@ -72,6 +107,16 @@ pub enum Operator {
 	LValueAssignment,
 }
 
+impl Operator {
+	/// Get how many operands this operator takes, augmented operators take the l-value and the r-value.
+	pub const fn operand_count(&self) -> usize {
+		match self {
+			Self::Normal(operation) => operation.operand_count(),
+			Self::Assignment | Self::Augmented(..) | Self::LValueAssignment => 2,
+		}
+	}
+}
+
 #[derive(Debug, EnumDiscriminants, Clone)]
 pub enum AstNodeVariant {
 	/// A constant.
@@ -90,6 +135,12 @@ pub enum AstNodeVariant {
 	FunctionDefinition(Box<[AstNode]>, Box<AstNode>),
 	/// A string literal.
 	String(Box<str>),
+	/// An expression and the name of the member being accessed on it, such as `a.b`.
+	MemberAccess(Box<AstNode>, Box<str>),
+	/// A chain of comparisons such as `a < b <= c` and its operands, each operand is evaluated once and compared with the operands either side of it.
+	ComparisonChain(Box<[Operation]>, Box<[AstNode]>),
+	/// A range of ints from the start up to but not including the end, such as `0..5`.
+	Range(Box<AstNode>, Box<AstNode>),
 }
 
 #[derive(Debug, Clone)]
@@ -102,6 +153,16 @@ pub struct AstNode {
 }
 
 impl AstNode {
+	/// Creates a placeholder node that is not from a source file, used when moving nodes out of the tree.
+	pub const fn generated() -> Self {
+		Self { variant: AstNodeVariant::Constant(0), start: GENERATED_POSITION, end: GENERATED_POSITION }
+	}
+
+	/// The start and end of this node in the source file.
+	pub const fn span(&self) -> Span {
+		(self.start, self.end)
+	}
+
 	pub fn print_tree(&self, level: usize) {
 		for _ in 0..level {
 			print!("-");
@@ -116,6 +177,9 @@ impl AstNode {
 			AstNodeVariant::String(string_value) => print!(", string_value: {string_value:?}"),
 			AstNodeVariant::Operator(operator, _) => print!(", operator: {operator:?}"),
 			AstNodeVariant::Keyword(keyword, _, _) => print!(", keyword: {keyword:?}"),
+			AstNodeVariant::MemberAccess(_, member_name) => print!(", member_name: {member_name}"),
+			AstNodeVariant::ComparisonChain(operations, _) => print!(", operations: {operations:?}"),
+			AstNodeVariant::Range(..) => {},
 		}
 		println!(" {}", '}');
 		match &self.variant {
@@ -151,6 +215,14 @@ impl AstNode {
 			AstNodeVariant::Operator(_, operands) => for operand in operands {
 				operand.print_tree(level + 1);
 			}
+			AstNodeVariant::MemberAccess(accessed, _) => accessed.print_tree(level + 1),
+			AstNodeVariant::ComparisonChain(_, operands) => for operand in operands {
+				operand.print_tree(level + 1);
+			}
+			AstNodeVariant::Range(range_start, range_end) => {
+				range_start.print_tree(level + 1);
+				range_end.print_tree(level + 1);
+			}
 			AstNodeVariant::Constant(..) => {}
 			AstNodeVariant::Identifier(..) => {}
 			AstNodeVariant::String(..) => {}
@@ -158,21 +230,14 @@ impl AstNode {
 	}
 
 	/// Removes global assignments nodes and puts them into a `(name, node)` hash map, replacing them with an identifier node.
-	pub fn separate_globals(&mut self, global_list: &mut HashMap<Box<str>, (Self, bool)>, will_be_discarded: bool, can_be_exported: bool) -> Result<bool, (Error, (NonZeroUsize, NonZeroUsize))> {
-		let start = self.start;
+	pub fn separate_globals(&mut self, global_list: &mut HashMap<Box<str>, (Self, bool)>, will_be_discarded: bool, can_be_exported: bool) -> Result<bool, (Error, Span)> {
+		let span = self.span();
 		match &mut self.variant {
 			AstNodeVariant::Operator(operator, operands) => match operator {
 				Operator::Assignment => {
 					// Separate operands
-					let dummy_number = NonZeroUsize::new(1).unwrap();
-					let mut identifier_node = AstNode {
-						start: (dummy_number, dummy_number), end: (dummy_number, dummy_number), variant: AstNodeVariant::Constant(0)
-					};
-					let mut operand_node = AstNode {
-						start: (dummy_number, dummy_number), end: (dummy_number, dummy_number), variant: AstNodeVariant::Constant(0)
-					};
-					swap(&mut operands[0], &mut identifier_node);
-					swap(&mut operands[1], &mut operand_node);
+					let mut identifier_node = replace(&mut operands[0], AstNode::generated());
+					let mut operand_node = replace(&mut operands[1], AstNode::generated());
 					let is_exported = identifier_node.separate_globals(global_list, false, true)?;
 					operand_node.separate_globals(global_list, false, false)?;
 					// Get name to assign to
@@ -183,14 +248,14 @@ impl AstNode {
 					} = &identifier_node;
 					let name = match variant {
 						AstNodeVariant::Identifier(name) => name.clone(),
-						_ => return Err((Error::GlobalAssignmentToNonIdentifier, start)),
+						_ => return Err((Error::GlobalAssignmentToNonIdentifier, span)),
 					};
 					// Pop out global assignment into global variable list
 					match global_list.insert(name, (operand_node, is_exported)) {
 						Some(..) => return Err((Error::GlobalVariableConflict(match variant {
 							AstNodeVariant::Identifier(name) => name.clone().into(),
-							_ => return Err((Error::GlobalAssignmentToNonIdentifier, start)),
-						}), start)),
+							_ => return Err((Error::GlobalAssignmentToNonIdentifier, span)),
+						}), span)),
 						None => {}
 					};
 					// Replace node with the identifier node
@@ -199,46 +264,51 @@ impl AstNode {
 				Operator::Normal(..) => for operand in operands {
 					operand.separate_globals(global_list, will_be_discarded, false)?;
 				}
-				Operator::Augmented(..) => return Err((Error::GlobalAugmentedOperator, start)),
-				Operator::LValueAssignment => return Err((Error::GlobalLValueAssignment, start)),
+				Operator::Augmented(..) => return Err((Error::GlobalAugmentedOperator, span)),
+				Operator::LValueAssignment => return Err((Error::GlobalLValueAssignment, span)),
 			}
 			AstNodeVariant::Constant(..) => {}
 			AstNodeVariant::FunctionCall(..) => if will_be_discarded {
-				return Err((Error::DiscardedGlobalFunctionCall, start));
+				return Err((Error::DiscardedGlobalFunctionCall, span));
 			}
 			AstNodeVariant::Block(children, is_result_undefined) => {
 				if *is_result_undefined && children.is_empty() {
 					return Ok(false);
 				}
 				if children.len() != 1 || (*is_result_undefined && children.len() != 0) {
-					return Err((Error::FeatureNotYetImplemented("Global blocks".into()), start));
+					return Err((Error::FeatureNotYetImplemented("Global blocks".into()), span));
 				}
-				let dummy_number = NonZeroUsize::new(1).unwrap();
-				let mut child = AstNode { start: (dummy_number, dummy_number), end: (dummy_number, dummy_number), variant: AstNodeVariant::Constant(0) };
-				swap(&mut children[0], &mut child);
+				let mut child = replace(&mut children[0], AstNode::generated());
 				child.separate_globals(global_list, will_be_discarded, false)?;
 				*self = child;
 			}
 			AstNodeVariant::FunctionDefinition(..) => {}
 			AstNodeVariant::Identifier(..) => {}
 			AstNodeVariant::String(..) => {}
+			AstNodeVariant::MemberAccess(accessed, _) => {
+				accessed.separate_globals(global_list, will_be_discarded, false)?;
+			}
+			AstNodeVariant::ComparisonChain(_, operands) => for operand in operands {
+				operand.separate_globals(global_list, will_be_discarded, false)?;
+			}
+			AstNodeVariant::Range(range_start, range_end) => {
+				range_start.separate_globals(global_list, will_be_discarded, false)?;
+				range_end.separate_globals(global_list, will_be_discarded, false)?;
+			}
 			AstNodeVariant::Keyword(keyword, arguments, child) => match keyword {
 				Keyword::Export => {
 					if !arguments.is_empty() {
-						return Err((Error::InvalidBuiltInFunctionArgumentCount, start));
+						return Err((Error::InvalidBuiltInFunctionArgumentCount, span));
 					}
 					let child = match child {
 						Some(child) => child,
-						None => return Err((Error::InvalidBuiltInFunctionArgumentCount, start)),
+						None => return Err((Error::InvalidBuiltInFunctionArgumentCount, span)),
 					};
 					if !can_be_exported {
-						return Err((Error::InvalidExport, start));
+						return Err((Error::InvalidExport, span));
 					}
 					child.separate_globals(global_list, will_be_discarded, false)?;
-					let dummy_number = NonZeroUsize::new(1).unwrap();
-					let mut child_taken = AstNode { start: (dummy_number, dummy_number), end: (dummy_number, dummy_number), variant: AstNodeVariant::Constant(0) };
-					swap(&mut **child, &mut child_taken);
-					*self = child_taken;
+					*self = replace(&mut **child, AstNode::generated());
 					return Ok(true);
 				}
 				_ => {
@@ -258,22 +328,24 @@ impl AstNode {
 	///
 	/// Appends imported filepaths that need to be compiled before this global variable to `import_dependencies`.
 	///
-	/// Appends the name of global variables that need to be compiled before this global variable to `variable_dependencies`.
+	/// Appends the name of global variables that need to be compiled before this global variable to `variable_dependencies` with the span of their first use.
 	pub fn get_variable_dependencies(
 		&self,
 		main_data: &MainData,
 		filepath: &PathBuf,
-		variable_dependencies: &mut HashSet<Box<str>>,
+		variable_dependencies: &mut HashMap<Box<str>, Span>,
 		import_dependencies: &mut HashSet<PathBuf>,
 		local_variables: &mut Vec<HashSet<Box<str>>>,
 		is_l_value: bool,
-	) -> Result<(), (Error, (NonZeroUsize, NonZeroUsize))> {
+	) -> Result<(), (Error, Span)> {
 		// Unpack
 		let AstNode {
 			variant,
 			start,
-			end: _,
+			end,
 		} = self;
+		let span = (*start, *end);
+		main_data.check_deadline(span)?;
 		// Search depends on type of node
 		match variant {
 			// For a block we search each sub-expression in the block
@@ -286,7 +358,7 @@ impl AstNode {
 						}
 						local_variables.pop();
 					}
-					true => return Err((Error::FeatureNotYetImplemented("L-value blocks".into()), *start)),
+					true => return Err((Error::FeatureNotYetImplemented("L-value blocks".into()), span)),
 				};
 			}
 			// Constants can't have dependencies
@@ -294,10 +366,18 @@ impl AstNode {
 			// For a function call we search the expression yeilding the function pointer and the function argument expressions
 			AstNodeVariant::FunctionCall(function, arguments) => {
 				if is_l_value {
-					return Err((Error::LValueFunctionCall, *start));
+					return Err((Error::LValueFunctionCall, span));
+				}
+				// Built-in functions that are not shadowed by a local variable are not global variables so are not dependencies
+				let is_builtin = match &function.variant {
+					AstNodeVariant::Identifier(name) => main_data.builtins.contains_key(&**name) &&
+						!local_variables.iter().any(|local_variable_level| local_variable_level.contains(name)),
+					_ => false,
+				};
+				if !is_builtin {
+					function
+						.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, &mut local_variables.clone(), false)?;
 				}
-				function
-					.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, &mut local_variables.clone(), false)?;
 				for argument in arguments {
 					argument.get_variable_dependencies(
 						main_data, filepath, variable_dependencies, import_dependencies, local_variables, false
@@ -318,9 +398,94 @@ impl AstNode {
 						)?;
 					}
 					Keyword::Export => unreachable!(),
+					Keyword::Section => {
+						if arguments.len() != 1 {
+							return Err((Error::InvalidBuiltInFunctionArgumentCount, span));
+						}
+						if !matches!(arguments[0].variant, AstNodeVariant::String(..)) {
+							return Err((Error::ConstValueRequired, arguments[0].span()));
+						}
+						match child {
+							Some(child) => child.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, is_l_value)?,
+							None => return Err((Error::MetadataItemWithoutChildNode, span)),
+						}
+					}
 					Keyword::Loop => child.as_ref().unwrap().get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?,
-					Keyword::Break | Keyword::Continue => if !arguments.is_empty() {
-						return Err((Error::FeatureNotYetImplemented("Arguments for @break and @continue".into()), *start));
+					Keyword::Assert | Keyword::RuntimeAssert | Keyword::SizeOf => {
+						if arguments.len() != 1 {
+							return Err((Error::InvalidBuiltInFunctionArgumentCount, span));
+						}
+						if let Some(child) = child {
+							return Err((Error::ShouldNotHaveChild, child.span()));
+						}
+						arguments[0].get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?;
+					}
+					Keyword::Const => {
+						if !arguments.is_empty() {
+							return Err((Error::InvalidBuiltInFunctionArgumentCount, span));
+						}
+						match child {
+							Some(child) => child.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?,
+							None => return Err((Error::MetadataItemWithoutChildNode, span)),
+						}
+					}
+					// A @break with arguments exits a labeled block, the first argument is the label and the second is an optional result value
+					Keyword::Break => match arguments.len() {
+						0 => {}
+						1 | 2 => {
+							if !matches!(arguments[0].variant, AstNodeVariant::Identifier(..)) {
+								return Err((Error::ExpectedIdentifier, arguments[0].span()));
+							}
+							if let Some(value) = arguments.get(1) {
+								value.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?;
+							}
+						}
+						_ => return Err((Error::InvalidBuiltInFunctionArgumentCount, span)),
+					}
+					Keyword::Continue => if !arguments.is_empty() {
+						return Err((Error::FeatureNotYetImplemented("Arguments for @continue".into()), span));
+					}
+					Keyword::DoWhile => {
+						if arguments.len() != 1 {
+							return Err((Error::InvalidBuiltInFunctionArgumentCount, span));
+						}
+						arguments[0].get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?;
+						match child {
+							Some(child) => child.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?,
+							None => return Err((Error::MetadataItemWithoutChildNode, span)),
+						}
+					}
+					// The range is evaluated outside of the loop, the induction variable is local to the body
+					Keyword::For => {
+						if arguments.len() != 2 {
+							return Err((Error::InvalidBuiltInFunctionArgumentCount, span));
+						}
+						let induction_variable = match &arguments[0].variant {
+							AstNodeVariant::Identifier(name) => name.clone(),
+							_ => return Err((Error::ExpectedIdentifier, arguments[0].span())),
+						};
+						if !matches!(arguments[1].variant, AstNodeVariant::Range(..)) {
+							return Err((Error::ExpectedRange, arguments[1].span()));
+						}
+						arguments[1].get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?;
+						local_variables.push(HashSet::from([induction_variable]));
+						match child {
+							Some(child) => child.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?,
+							None => return Err((Error::MetadataItemWithoutChildNode, span)),
+						}
+						local_variables.pop();
+					}
+					Keyword::Label => {
+						if arguments.len() != 1 {
+							return Err((Error::InvalidBuiltInFunctionArgumentCount, span));
+						}
+						if !matches!(arguments[0].variant, AstNodeVariant::Identifier(..)) {
+							return Err((Error::ExpectedIdentifier, arguments[0].span()));
+						}
+						match child {
+							Some(child) => child.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?,
+							None => return Err((Error::MetadataItemWithoutChildNode, span)),
+						}
 					}
 					Keyword::Import => {
 						for argument in arguments {
@@ -330,26 +495,32 @@ impl AstNode {
 						let import_name = match &import_name_node.variant {
 							AstNodeVariant::Identifier(import_name) => &**import_name,
 							AstNodeVariant::String(import_name) => &**import_name,
-							_ => return Err((Error::ExpectedIdentifier, import_name_node.start)),
+							_ => return Err((Error::ExpectedIdentifier, import_name_node.span())),
 						};
 						let absolute_filepath = relative_filepath_to_absolute(main_data, filepath, import_name)
-							.map_err(|error| (error, import_name_node.start))?;
+							.map_err(|error| (error, import_name_node.span()))?;
 						import_dependencies.insert(absolute_filepath);
 					}
 				}
 			}
 			AstNodeVariant::FunctionDefinition(parameters, body) => {
 				if is_l_value {
-					return Err((Error::LValueFunctionDefinition, *start));
+					return Err((Error::LValueFunctionDefinition, span));
 				}
 				let mut local_variables_top = HashSet::new();
+				let mut has_default = false;
 				for parameter in parameters {
-					match &parameter.variant {
-						AstNodeVariant::Identifier(name) => {
-							local_variables_top.insert(name.clone());
+					let (name, default) = parameter.parameter_name_and_default()?;
+					// Once a parameter has a default then so must all parameters after it
+					match default {
+						Some(default) => {
+							default.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?;
+							has_default = true;
 						}
-						_ => return Err((Error::ExpectedIdentifier, parameter.start)),
+						None if has_default => return Err((Error::ParameterWithoutDefault, parameter.span())),
+						None => {}
 					}
+					local_variables_top.insert(name.into());
 				}
 				let mut local_variables = vec![local_variables_top];
 				body.get_variable_dependencies(
@@ -364,7 +535,7 @@ impl AstNode {
 							break 'a;
 						}
 					}
-					variable_dependencies.insert(name.clone());
+					variable_dependencies.entry(name.clone()).and_modify(|first_use| *first_use = (*first_use).min(span)).or_insert(span);
 				}
 				// An identifier being used as an l-value should be added to the local variable list
 				// so that it is not added to the global variable list if used later
@@ -377,6 +548,9 @@ impl AstNode {
 					local_variables.last_mut().unwrap().insert(name.clone());
 				}
 			}
+			// Operators with the wrong amount of operands would cause a panic when their operands are indexed
+			AstNodeVariant::Operator(operator, operands) if operands.len() != operator.operand_count() =>
+				return Err((Error::MalformedOperatorArity(operator.operand_count(), operands.len()), span)),
 			AstNodeVariant::Operator(operator, operands) => match operator {
 				// For an assignment, we search the the l-value and r-value
 				Operator::Assignment => {
@@ -405,7 +579,7 @@ impl AstNode {
 					Operation::Dereference | Operation::IntegerNegate | Operation::FloatNegate | Operation::Read | Operation::TakeReference |
 					Operation::BitwiseNot | Operation::LogicalNot | Operation::PrefixIntegerDecrement | Operation::PrefixIntegerIncrement | Operation::SuffixIntegerDecrement |
 					Operation::SuffixIntegerIncrement
-						=> return Err((Error::FeatureNotYetImplemented("Augmented unary operators".into()), *start)),
+						=> return Err((Error::FeatureNotYetImplemented("Augmented unary operators".into()), span)),
 					Operation::ShortCircuitTernary | Operation::NotShortCircuitTernary => unreachable!(),
 				}
 				// For normal operators we search the operands
@@ -446,6 +620,24 @@ impl AstNode {
 			}
 			// Strings, just like constants, can't have dependencies
 			AstNodeVariant::String(..) => {}
+			// The member name is not a variable so only the accessed expression is searched
+			AstNodeVariant::MemberAccess(accessed, _) =>
+				accessed.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?,
+			AstNodeVariant::ComparisonChain(_, operands) => {
+				if main_data.language_version < LanguageVersion::V2 {
+					return Err((Error::FeatureNotInLanguageVersion("Comparison chains", LanguageVersion::V2), span));
+				}
+				for operand in operands {
+					operand.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?;
+				}
+			}
+			AstNodeVariant::Range(range_start, range_end) => {
+				if main_data.language_version < LanguageVersion::V2 {
+					return Err((Error::FeatureNotInLanguageVersion("Ranges", LanguageVersion::V2), span));
+				}
+				range_start.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?;
+				range_end.get_variable_dependencies(main_data, filepath, variable_dependencies, import_dependencies, local_variables, false)?;
+			}
 		}
 		Ok(())
 	}
@@ -458,21 +650,21 @@ impl AstNode {
 		llvm_builder: &'a Builder,
 		name: &str,
 		is_entry_point: bool,
-	) -> Result<Value<'a, 'a>, (Error, (NonZeroUsize, NonZeroUsize))> {
+	) -> Result<Value<'a, 'a>, (Error, Span)> {
 		// Unpack node
 		let Self {
 			start,
-			end: _,
+			end,
 			variant,
 		} = self;
+		let span = (*start, *end);
 		match variant {
 			AstNodeVariant::FunctionDefinition(parameters, _) => {
 				// Create function parameter type
-				if parameters.len() > u16::MAX as usize {
-					return Err((Error::TooManyFunctionParameters, *start));
+				if parameters.len() > main_data.max_function_parameters as usize {
+					return Err((Error::TooManyFunctionParameters, span));
 				}
-				let parameter_types: Box<[Type]> = repeat(main_data.int_type).take(parameters.len()).collect();
-				let function_type = main_data.int_type.function_type(&*parameter_types, false);
+				let function_type = file_build_data.int_function_type(main_data.int_type, parameters.len(), false);
 				// Build function value
 				let function = llvm_module.add_function(function_type, &*name);
 				function.set_linkage(match is_entry_point {
@@ -485,6 +677,12 @@ impl AstNode {
 			AstNodeVariant::Keyword(keyword, _, child_node) => match keyword {
 				Keyword::EntryPoint =>
 					child_node.as_ref().unwrap().build_function_signature(main_data, file_build_data, llvm_module, llvm_builder, name, true),
+				Keyword::Section => {
+					let function = child_node.as_ref().unwrap()
+						.build_function_signature(main_data, file_build_data, llvm_module, llvm_builder, name, is_entry_point)?;
+					function.set_section(self.section_name().unwrap());
+					Ok(function)
+				}
 				_ => unreachable!(),
 			}
 			_ => unreachable!(),
@@ -500,19 +698,22 @@ impl AstNode {
 		llvm_builder: &'a Builder,
 		name: &str,
 		is_entry_point: bool,
-	) -> Result<Value<'a, 'a>, (Error, (NonZeroUsize, NonZeroUsize))> {
+	) -> Result<Value<'a, 'a>, (Error, Span)> {
 		// Unpack function definition node
 		let Self {
 			start,
-			end: _,
+			end,
 			variant,
 		} = self;
+		let span = (*start, *end);
 		// If we have a metadata node, then build the child node
 		let (parameters, function_body) = match variant {
 			AstNodeVariant::FunctionDefinition(function_parameters, function_body) => (function_parameters, function_body),
 			AstNodeVariant::Keyword(keyword, _, child) => match keyword {
 				Keyword::EntryPoint =>
 					return child.as_ref().unwrap().build_function_definition(main_data, file_build_data, llvm_module, llvm_builder, name, true),
+				Keyword::Section =>
+					return child.as_ref().unwrap().build_function_definition(main_data, file_build_data, llvm_module, llvm_builder, name, is_entry_point),
 				_ => unreachable!(),
 			}
 			_ => unreachable!(),
@@ -521,11 +722,10 @@ impl AstNode {
 			Some(function) => function.clone(),
 			None => {
 				// Create function parameter type
-				if parameters.len() > u16::MAX as usize {
-					return Err((Error::TooManyFunctionParameters, *start));
+				if parameters.len() > main_data.max_function_parameters as usize {
+					return Err((Error::TooManyFunctionParameters, span));
 				}
-				let parameter_types: Box<[Type]> = repeat(main_data.int_type).take(parameters.len()).collect();
-				let function_type = main_data.int_type.function_type(&*parameter_types, false);
+				let function_type = file_build_data.int_function_type(main_data.int_type, parameters.len(), false);
 				// Build function value
 				let function = llvm_module.add_function(function_type, &*name);
 				function.set_linkage(Linkage::Internal);
@@ -545,7 +745,8 @@ impl AstNode {
 			basic_blocks: vec![body_basic_block.clone()],
 			allocas_in_use: HashSet::new(),
 			array_allocas_in_use: HashMap::new(),
-			is_loop: false,
+			loop_blocks: None,
+			label: None,
 		}];
 		let mut function_info = FunctionBuildData {
 			function: function.clone(),
@@ -557,24 +758,44 @@ impl AstNode {
 		// Build function parameters
 		for (parameter_index, parameter) in parameters.iter().enumerate() {
 			// Get parameter name
-			let parameter_name = match &parameter.variant {
-				AstNodeVariant::Identifier(name) => name,
-				_ => return Err((Error::ExpectedIdentifier, parameter.start)),
-			};
+			let (parameter_name, _) = parameter.parameter_name_and_default()?;
 			// Add parameter to local scope
 			let parameter_value = function.get_parameter(parameter_index);
 			let parameter_variable = function_info.get_alloca(main_data, llvm_builder, parameter_name);
 			parameter_variable.build_store(&parameter_value, llvm_builder);
-			function_info.block_stack.last_mut().unwrap().local_variables.insert(parameter_name.clone(), BuiltLValue::AllocaVariable(parameter_variable));
+			function_info.block_stack.last_mut().unwrap().local_variables.insert(parameter_name.into(), BuiltLValue::AllocaVariable(parameter_variable));
+		}
+		function_info.dump_scopes(main_data, &format!("Enter function {name}"));
+		// A function that yields a value on some paths but not others would return an undefined value on those paths
+		if !function_body.is_void() {
+			if let Some(void_path_span) = function_body.void_path_span() {
+				return Err((Error::MissingReturn, void_path_span));
+			}
+		}
+		// The stack of the function is freed when it returns so returning an address on it gives a dangling pointer
+		if let Some(stack_address_span) = function_body.returned_stack_address_span(&file_build_data.global_names) {
+			main_data.report_warning(Error::ReturnedStackAddress, file_build_data.filepath, stack_address_span)?;
 		}
 		// Build function body
 		let function_body_built = function_body.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(&mut function_info))?;
+		// Mark a call in tail position as a tail call so that LLVM can turn recursion into a loop,
+		// this is only done if the stack of this function can't be accessed by the callee
+		if let (true, false, BuiltRValue::Value(function_call)) =
+			(function_body.is_tail_call(main_data, &function_info), function_body.may_expose_stack_address(), &function_body_built) {
+			function_call.set_tail_call(true);
+		}
 		// Build branch from entry block to first body block
 		llvm_builder.position_at_end(&entry_basic_block);
 		llvm_builder.build_branch(&body_basic_block);
 		// Build return
 		llvm_builder.position_at_end(function_info.block_stack.last().unwrap().last_block());
-		BuiltRValue::Value(function_body_built.get_value(main_data, llvm_builder).build_return(llvm_builder));
+		let mut return_value = function_body_built.get_value(main_data, llvm_builder);
+		// The value returned by the entry point is the exit code of the process, which is a 32-bit C `int` on every supported platform
+		if is_entry_point {
+			let exit_code = return_value.build_truncate(llvm_builder, main_data.llvm_context.int_32_type(), "exit_code_temp");
+			return_value = exit_code.build_sign_extend(llvm_builder, main_data.int_type, "exit_code_extend_temp");
+		}
+		BuiltRValue::Value(return_value.build_return(llvm_builder));
 		// Return
 		//if is_entry_point {
 		//	function.set_linkage(Linkage::External);
@@ -582,7 +803,7 @@ impl AstNode {
 		let result = function.build_ptr_to_int(llvm_builder, main_data.int_type, "fn_ptr_to_int");
 		if is_entry_point {
 			if file_build_data.entrypoint.is_some() {
-				return Err((Error::MultipleEntryPoints, *start));
+				return Err((Error::MultipleEntryPoints, span));
 			}
 			file_build_data.entrypoint = Some((result.clone(), name.into()));
 		}
@@ -597,18 +818,28 @@ impl AstNode {
 		llvm_module: &'a Module,
 		llvm_builder: &'a Builder<'a, 'a>,
 		function_build_data: Option<&mut FunctionBuildData<'a, 'b>>,
-	) -> Result<BuiltRValue<'a>, (Error, (NonZeroUsize, NonZeroUsize))> {
+	) -> Result<BuiltRValue<'a>, (Error, Span)> {
 		// Unpack
 		let Self {
 			start,
-			end: _,
+			end,
 			variant,
 		} = self;
+		let span = (*start, *end);
+		main_data.check_deadline(span)?;
+		let temp_name = |name: &str| main_data.temp_name(name, *start);
 		// Use the `build_function_definition()` method to build the node if it is a function.
 		if self.is_function() {
+			// Unnamed functions are only called through a local variable or address so their defaults could never be filled in
+			if let AstNodeVariant::FunctionDefinition(parameters, _) = variant {
+				if let Some(default) = parameters.iter().find_map(|parameter| parameter.parameter_name_and_default().ok()?.1) {
+					return Err((Error::DefaultParameterInLocalFunction, default.span()));
+				}
+			}
 			// Build function
+			let name = file_build_data.mangle_name(MangledSymbol::UnnamedFunction);
 			let out = self.build_function_definition(
-				main_data, file_build_data, llvm_module, llvm_builder, "__bcz__unnamedFunction", false/*, false*/
+				main_data, file_build_data, llvm_module, llvm_builder, &name, false/*, false*/
 			)?;
 			// The function will have positioned the builder pos to one of it's basic blocks, so re-position it back
 			if let Some(function_info) = function_build_data {
@@ -624,16 +855,48 @@ impl AstNode {
 			// For an identifier, we load the value stored in the variable it represents
 			AstNodeVariant::Identifier(name) => get_variable_by_name(main_data, file_build_data, llvm_builder, function_build_data, &*name),
 			AstNodeVariant::Operator(operator, operands) => {
+				if operands.len() != operator.operand_count() {
+					return Err((Error::MalformedOperatorArity(operator.operand_count(), operands.len()), span));
+				}
 				let function_build_data = match function_build_data {
 					Some(function_build_data) => function_build_data,
-					None => return Err((Error::GlobalOperatorNotConstEvaluated, *start))
+					// Taking a reference to a global in global scope yields a constant address that can be the initializer of another global
+					None => return match (operator, &operands[0].variant) {
+						(Operator::Normal(Operation::TakeReference), AstNodeVariant::Identifier(name)) => {
+							let global = match file_build_data.built_global_function_signatures.get(name) {
+								Some(function) => function.clone(),
+								None => llvm_module.get_global(name).ok_or((Error::GlobalOperatorNotConstEvaluated, span))?,
+							};
+							Ok(BuiltRValue::Value(global.const_ptr_to_int(main_data.int_type)))
+						}
+						_ => Err((Error::GlobalOperatorNotConstEvaluated, span)),
+					},
+				};
+				// Void values can not be used as operands, the l-value of an assignment is not read so it is skipped
+				// and the branches of a ternary are its result rather than operands so they may be void such as in `condition ? {} : @break`
+				let read_operands = match operator {
+					Operator::Normal(Operation::NotShortCircuitTernary | Operation::ShortCircuitTernary) => &operands[..1],
+					Operator::Normal(_) => &operands[..],
+					Operator::Assignment | Operator::Augmented(_) | Operator::LValueAssignment => &operands[1..],
 				};
+				if let Some(void_operand) = read_operands.iter().find(|operand| operand.is_void()) {
+					return Err((Error::UseOfVoidValue, void_operand.span()));
+				}
 				match operator {
 					// For an assignment, we build the l and r-values and then build a store instruction
 					Operator::Assignment => {
 						let r_value = operands[1].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?;
+						// An array assigned to a new local variable is named by the variable so that assigning the variable to another array variable copies its entries
+						if let (BuiltRValue::Array(array, array_type), AstNodeVariant::Identifier(name)) = (&r_value, &operands[0].variant) {
+							if function_build_data.get_local_variable(name).is_none() && !file_build_data.global_names.contains(name) {
+								function_build_data.block_stack.last_mut().unwrap().local_variables
+									.insert(name.clone(), BuiltLValue::ArrayVariable(array.clone(), *array_type));
+								function_build_data.dump_scopes(main_data, &format!("Create array variable {name}"));
+								return Ok(r_value);
+							}
+						}
 						let l_value = operands[0].build_l_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?;
-						l_value.set_value(main_data, llvm_builder, &r_value.get_value(main_data, llvm_builder));
+						l_value.set_value(main_data, llvm_builder, &r_value).map_err(|error| (error, span))?;
 						return Ok(r_value);
 					}
 					// For a normal operator, we build the operands then build the operator instruction
@@ -645,73 +908,88 @@ impl AstNode {
 						Operation::IntegerEqualTo | Operation::IntegerNotEqualTo | Operation::UnsignedLessThanOrEqualTo |
 						Operation::UnsignedGreaterThan | Operation::UnsignedGreaterThanOrEqualTo | Operation::UnsignedLessThan |
 						Operation::SignedLessThanOrEqualTo | Operation::SignedGreaterThan | Operation::SignedGreaterThanOrEqualTo | Operation::SignedLessThan => {
-							let left_value = operands[0].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
-								.get_value(main_data, llvm_builder);
-							let right_value = operands[1].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
-								.get_value(main_data, llvm_builder);
+							let left_r_value = operands[0].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?;
+							let right_r_value = operands[1].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?;
+							// Bitwise operations on a float work on its bit pattern, the float is cast to an int and the result is cast back to a float
+							let float_type = match (operation, &left_r_value, &right_r_value) {
+								(Operation::BitwiseAnd | Operation::BitwiseOr | Operation::BitwiseXor, BuiltRValue::Float(float), _) |
+								(Operation::BitwiseAnd | Operation::BitwiseOr | Operation::BitwiseXor, _, BuiltRValue::Float(float)) => Some(float.get_type()),
+								_ => None,
+							};
+							let left_value = left_r_value.get_value(main_data, llvm_builder);
+							let right_value = right_r_value.get_value(main_data, llvm_builder);
+							// Branch to the runtime failure if the divisor is zero
+							if main_data.checked_division && matches!(
+								operation, Operation::UnsignedDivide | Operation::UnsignedModulo | Operation::SignedDivide | Operation::SignedTruncatedModulo
+							) {
+								let is_zero = right_value
+									.build_compare(&main_data.int_type.const_int(0, false), Comparison::Equal, llvm_builder, &temp_name("divisor_is_zero_temp"));
+								let failed_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "division_by_zero");
+								let passed_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "divisor_not_zero");
+								is_zero.build_conditional_branch(&failed_basic_block, &passed_basic_block, main_data.llvm_context, llvm_builder);
+								llvm_builder.position_at_end(&failed_basic_block);
+								build_runtime_failure(main_data, llvm_module, llvm_builder);
+								llvm_builder.position_at_end(&passed_basic_block);
+								function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(passed_basic_block);
+							}
 							let result = match operation {
-								Operation::IntegerAdd => left_value.build_add(&right_value, llvm_builder, "add_temp"),
-								Operation::IntegerSubtract => left_value.build_sub(&right_value, llvm_builder, "sub_temp"),
-								Operation::IntegerMultiply => left_value.build_mult(&right_value, llvm_builder, "mult_temp"),
-								Operation::UnsignedDivide => left_value.build_unsigned_div(&right_value, llvm_builder, "udiv_temp"),
-								Operation::UnsignedModulo => left_value.build_unsigned_modulo(&right_value, llvm_builder, "umod_temp"),
-								Operation::SignedDivide => left_value.build_signed_div(&right_value, llvm_builder, "sdiv_temp"),
-								Operation::SignedTruncatedModulo => left_value.build_signed_truncated_modulo(&right_value, llvm_builder, "stmod_temp"),
-								Operation::BitwiseAnd => left_value.build_bitwise_and(&right_value, llvm_builder, "band_temp"),
+								Operation::IntegerAdd => left_value.build_add(&right_value, llvm_builder, &temp_name("add_temp")),
+								Operation::IntegerSubtract => left_value.build_sub(&right_value, llvm_builder, &temp_name("sub_temp")),
+								Operation::IntegerMultiply => left_value.build_mult(&right_value, llvm_builder, &temp_name("mult_temp")),
+								Operation::UnsignedDivide => left_value.build_unsigned_div(&right_value, llvm_builder, &temp_name("udiv_temp")),
+								Operation::UnsignedModulo => left_value.build_unsigned_modulo(&right_value, llvm_builder, &temp_name("umod_temp")),
+								Operation::SignedDivide => left_value.build_signed_div(&right_value, llvm_builder, &temp_name("sdiv_temp")),
+								Operation::SignedTruncatedModulo => left_value.build_signed_truncated_modulo(&right_value, llvm_builder, &temp_name("stmod_temp")),
+								Operation::BitwiseAnd => left_value.build_bitwise_and(&right_value, llvm_builder, &temp_name("band_temp")),
 								Operation::BitwiseOr | Operation::LogicalNotShortCircuitOr =>
-									left_value.build_bitwise_or(&right_value, llvm_builder, "bor_temp"),
-								Operation::BitwiseXor => left_value.build_bitwise_xor(&right_value, llvm_builder, "bxor_temp"),
+									left_value.build_bitwise_or(&right_value, llvm_builder, &temp_name("bor_temp")),
+								Operation::BitwiseXor => left_value.build_bitwise_xor(&right_value, llvm_builder, &temp_name("bxor_temp")),
 								Operation::LogicalNotShortCircuitAnd => {
-									let zero_const = main_data.int_type.const_int(0, false);
-									let left_value_bool =
-										left_value.build_compare(&zero_const, Comparison::NotEqual, llvm_builder, "itbneq_temp");
-									let right_value_bool =
-										right_value.build_compare(&zero_const, Comparison::NotEqual, llvm_builder, "itbneq_temp");
-									left_value_bool.build_bitwise_and(&right_value_bool, llvm_builder, "band_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp")
+									let left_value_bool = build_int_to_bool(main_data, llvm_builder, &left_value);
+									let right_value_bool = build_int_to_bool(main_data, llvm_builder, &right_value);
+									build_bool_to_int(main_data, llvm_builder, &left_value_bool.build_bitwise_and(&right_value_bool, llvm_builder, &temp_name("band_temp")))
 								}
 								Operation::LogicalXor => {
-									let zero_const = main_data.int_type.const_int(0, false);
-									let left_value_bool =
-										left_value.build_compare(&zero_const, Comparison::NotEqual, llvm_builder, "itbneq_temp");
-									let right_value_bool =
-										right_value.build_compare(&zero_const, Comparison::NotEqual, llvm_builder, "itbneq_temp");
-									left_value_bool.build_bitwise_xor(&right_value_bool, llvm_builder, "bxor_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp")
+									let left_value_bool = build_int_to_bool(main_data, llvm_builder, &left_value);
+									let right_value_bool = build_int_to_bool(main_data, llvm_builder, &right_value);
+									build_bool_to_int(main_data, llvm_builder, &left_value_bool.build_bitwise_xor(&right_value_bool, llvm_builder, &temp_name("bxor_temp")))
 								}
 								Operation::IntegerEqualTo =>
-									left_value.build_compare(&right_value, Comparison::Equal, llvm_builder, "eq_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp"),
+									left_value.build_compare(&right_value, Comparison::Equal, llvm_builder, &temp_name("eq_temp"))
+										.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")),
 								Operation::IntegerNotEqualTo =>
-									left_value.build_compare(&right_value, Comparison::NotEqual, llvm_builder, "neq_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp"),
+									left_value.build_compare(&right_value, Comparison::NotEqual, llvm_builder, &temp_name("neq_temp"))
+										.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")),
 								Operation::UnsignedLessThan =>
-									left_value.build_compare(&right_value, Comparison::UnsignedLessThan, llvm_builder, "ult_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp"),
+									left_value.build_compare(&right_value, Comparison::UnsignedLessThan, llvm_builder, &temp_name("ult_temp"))
+										.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")),
 								Operation::UnsignedLessThanOrEqualTo =>
-									left_value.build_compare(&right_value, Comparison::UnsignedLessThanOrEqualTo, llvm_builder, "ulteq_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp"),
+									left_value.build_compare(&right_value, Comparison::UnsignedLessThanOrEqualTo, llvm_builder, &temp_name("ulteq_temp"))
+										.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")),
 								Operation::UnsignedGreaterThan =>
-									left_value.build_compare(&right_value, Comparison::UnsignedGreaterThan, llvm_builder, "ugt_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp"),
+									left_value.build_compare(&right_value, Comparison::UnsignedGreaterThan, llvm_builder, &temp_name("ugt_temp"))
+										.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")),
 								Operation::UnsignedGreaterThanOrEqualTo =>
-									left_value.build_compare(&right_value, Comparison::UnsignedGreaterThanOrEqualTo, llvm_builder, "ugteq_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp"),
+									left_value.build_compare(&right_value, Comparison::UnsignedGreaterThanOrEqualTo, llvm_builder, &temp_name("ugteq_temp"))
+										.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")),
 								Operation::SignedLessThan =>
-									left_value.build_compare(&right_value, Comparison::SignedLessThan, llvm_builder, "slt_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp"),
+									left_value.build_compare(&right_value, Comparison::SignedLessThan, llvm_builder, &temp_name("slt_temp"))
+										.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")),
 								Operation::SignedLessThanOrEqualTo =>
-									left_value.build_compare(&right_value, Comparison::SignedLessThanOrEqualTo, llvm_builder, "slteq_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp"),
+									left_value.build_compare(&right_value, Comparison::SignedLessThanOrEqualTo, llvm_builder, &temp_name("slteq_temp"))
+										.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")),
 								Operation::SignedGreaterThan =>
-									left_value.build_compare(&right_value, Comparison::SignedGreaterThan, llvm_builder, "sgt_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp"),
+									left_value.build_compare(&right_value, Comparison::SignedGreaterThan, llvm_builder, &temp_name("sgt_temp"))
+										.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")),
 								Operation::SignedGreaterThanOrEqualTo =>
-									left_value.build_compare(&right_value, Comparison::SignedGreaterThanOrEqualTo, llvm_builder, "sgteq_temp")
-										.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp"),
+									left_value.build_compare(&right_value, Comparison::SignedGreaterThanOrEqualTo, llvm_builder, &temp_name("sgteq_temp"))
+										.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")),
 								_ => unreachable!(),
 							};
-							BuiltRValue::Value(result)
+							match float_type {
+								Some(float_type) => BuiltRValue::Float(result.build_bit_cast(llvm_builder, float_type, &temp_name("int_to_float_temp"))),
+								None => BuiltRValue::Value(result),
+							}
 						}
 						Operation::LogicalShortCircuitAnd | Operation::LogicalShortCircuitOr => {
 							// Get the left value
@@ -720,9 +998,9 @@ impl AstNode {
 							// Get if we should skip
 							let skip_condition = match operation {
 								Operation::LogicalShortCircuitAnd
-									=> left_value.build_compare(&main_data.int_type.const_int(0, false), Comparison::Equal, llvm_builder, "should_skip_temp"),
+									=> left_value.build_compare(&main_data.int_type.const_int(0, false), Comparison::Equal, llvm_builder, &temp_name("should_skip_temp")),
 								Operation::LogicalShortCircuitOr
-									=> left_value.build_compare(&main_data.int_type.const_int(0, false), Comparison::NotEqual, llvm_builder, "should_skip_temp"),
+									=> left_value.build_compare(&main_data.int_type.const_int(0, false), Comparison::NotEqual, llvm_builder, &temp_name("should_skip_temp")),
 								_ => unreachable!()
 							};
 							// Get the alloca to write the result to
@@ -755,8 +1033,8 @@ impl AstNode {
 						Operation::NotShortCircuitTernary => {
 							// Build operands
 							let condition = operands[0].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
-								.get_value(main_data, llvm_builder)
-								.build_compare(&main_data.int_type.const_int(0, false), Comparison::NotEqual, llvm_builder, "int_to_bool_temp");
+								.get_value(main_data, llvm_builder);
+							let condition = build_int_to_bool(main_data, llvm_builder, &condition);
 							let then_case = operands[1].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
 								.get_value(main_data, llvm_builder);
 							let else_case = operands[2].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
@@ -791,8 +1069,8 @@ impl AstNode {
 						Operation::ShortCircuitTernary => {
 							// Build the condition to an i1
 							let condition = operands[0].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
-								.get_value(main_data, llvm_builder)
-								.build_compare(&main_data.int_type.const_int(0, false), Comparison::NotEqual, llvm_builder, "int_to_bool_temp");
+								.get_value(main_data, llvm_builder);
+							let condition = build_int_to_bool(main_data, llvm_builder, &condition);
 							// Build the basic blocks for the then and else cases and an end basic block to jump to when they have been executed
 							let then_basic_block = function_build_data.function.append_basic_block(&main_data.llvm_context, "ternary_then");
 							let else_basic_block = function_build_data.function.append_basic_block(&main_data.llvm_context, "ternary_else");
@@ -824,34 +1102,75 @@ impl AstNode {
 							function_build_data.surrender_alloca(result_alloca);
 							BuiltRValue::Value(result)
 						}
-						Operation::IntegerNegate | Operation::Dereference | Operation::BitwiseNot => {
+						// Float operations reinterpret the int operands as floats of the same width
+						Operation::FloatAdd | Operation::FloatSubtract | Operation::FloatMultiply | Operation::FloatDivide | Operation::FloatTruncatedModulo => {
+							let float_type = match main_data.int_bit_width {
+								32 => main_data.llvm_context.float_32_type(),
+								64 => main_data.llvm_context.float_64_type(),
+								_ => return Err((Error::FeatureNotYetImplemented("Float operations for this int width".into()), span)),
+							};
+							let left_value = operands[0].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
+								.get_float_value(main_data, llvm_builder, float_type);
+							let right_value = operands[1].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
+								.get_float_value(main_data, llvm_builder, float_type);
+							let result = match operation {
+								Operation::FloatAdd => left_value.build_float_add(&right_value, llvm_builder, &temp_name("fadd_temp")),
+								Operation::FloatSubtract => left_value.build_float_sub(&right_value, llvm_builder, &temp_name("fsub_temp")),
+								Operation::FloatMultiply => left_value.build_float_mult(&right_value, llvm_builder, &temp_name("fmult_temp")),
+								Operation::FloatDivide => left_value.build_float_div(&right_value, llvm_builder, &temp_name("fdiv_temp")),
+								Operation::FloatTruncatedModulo => left_value.build_float_truncated_modulo(&right_value, llvm_builder, &temp_name("fmod_temp")),
+								_ => unreachable!(),
+							};
+							if main_data.fast_math {
+								result.set_fast_math_flags(&[FastMathFlags::Fast]);
+							}
+							// The result stays a float so that it is only cast back to an int when it is used as one
+							BuiltRValue::Float(result)
+						}
+						Operation::IntegerNegate | Operation::Dereference | Operation::BitwiseNot | Operation::LogicalNot => {
 							let operand = operands[0].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
 								.get_value(main_data, llvm_builder);
 							let result = match operation {
-								Operation::IntegerNegate => operand.build_negate(llvm_builder, "neg_temp"),
+								Operation::IntegerNegate => operand.build_negate(llvm_builder, &temp_name("neg_temp")),
 								Operation::Dereference =>
-									operand.build_int_to_ptr(llvm_builder, main_data.int_type.pointer_to(), "int_to_ptr_for_deref_temp")
-										.build_load(main_data.int_type, llvm_builder, "load_for_deref_temp"),
-								Operation::BitwiseNot => operand.build_bitwise_not(llvm_builder, "bnot_temp"),
+									operand.build_int_to_ptr(llvm_builder, main_data.int_type.pointer_to(), &temp_name("int_to_ptr_for_deref_temp"))
+										.build_load(main_data.int_type, llvm_builder, &temp_name("load_for_deref_temp")),
+								Operation::BitwiseNot => operand.build_bitwise_not(llvm_builder, &temp_name("bnot_temp")),
+								Operation::LogicalNot => build_bool_to_int(
+									main_data, llvm_builder, &operand.build_compare(&main_data.int_type.const_int(0, false), Comparison::Equal, llvm_builder, &temp_name("lnot_temp"))
+								),
 								_ => unreachable!()
 							};
 							BuiltRValue::Value(result)
 						}
 						Operation::TakeReference | Operation::Read => {
+							// Taking a reference to a global function that is not shadowed by a local variable yields the address of the function
+							if let (Operation::TakeReference, AstNodeVariant::Identifier(name)) = (operation, &operands[0].variant) {
+								let is_local_variable = function_build_data.block_stack.iter().any(|block_level| block_level.local_variables.contains_key(name));
+								if let (false, Some(function)) = (is_local_variable, file_build_data.built_global_function_signatures.get(name)) {
+									return Ok(BuiltRValue::Value(function.build_ptr_to_int(llvm_builder, main_data.int_type, &temp_name("fn_ptr_to_int_temp"))));
+								}
+								// Other globals yield the address of the global they are stored in, imported constants are not stored in this module
+								if !is_local_variable && file_build_data.global_names.contains(name) {
+									let global = llvm_module.get_global(name)
+										.ok_or_else(|| (Error::FeatureNotYetImplemented("Taking a reference to an imported global".into()), span))?;
+									return Ok(BuiltRValue::Value(global.build_ptr_to_int(llvm_builder, main_data.int_type, &temp_name("ptr_to_int_for_take_ref_temp"))));
+								}
+							}
 							let value = operands[0].build_l_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?;
 							match operation {
 								Operation::TakeReference => BuiltRValue::Value(value
 									.get_pointer(main_data, llvm_builder)
-									.build_ptr_to_int(llvm_builder, main_data.int_type, "ptr_to_int_for_take_ref_temp")),
+									.build_ptr_to_int(llvm_builder, main_data.int_type, &temp_name("ptr_to_int_for_take_ref_temp"))),
 								Operation::Read => BuiltRValue::Value(value.get_value(main_data, llvm_builder)),
 								_ => unreachable!(),
 							}
 						}
-						_ => return Err((Error::FeatureNotYetImplemented("This operator".into()), *start)),
+						_ => return Err((Error::FeatureNotYetImplemented("This operator".into()), span)),
 					}
 					// TODO
-					Operator::Augmented(..) => return Err((Error::FeatureNotYetImplemented("Augmented assignments".into()), self.start)),
-					Operator::LValueAssignment => return Err((Error::FeatureNotYetImplemented("L-value assignments".into()), self.start)),
+					Operator::Augmented(..) => return Err((Error::FeatureNotYetImplemented("Augmented assignments".into()), self.span())),
+					Operator::LValueAssignment => return Err((Error::FeatureNotYetImplemented("L-value assignments".into()), self.span())),
 				}
 			}
 			// We built function definitions at the start of this function
@@ -864,7 +1183,7 @@ impl AstNode {
 				}
 				let function_build_data = match function_build_data {
 					Some(function_build_data) => function_build_data,
-					None => return Err((Error::FeatureNotYetImplemented("Blocks in global scope".into()), self.start)),
+					None => return Err((Error::FeatureNotYetImplemented("Blocks in global scope".into()), self.span())),
 				};
 				// Create the first inner basic block for the BCZ block, then branch from the current basic block to it, then re-position the builder to the new basic block
 				let inner_basic_block = function_build_data.function.append_basic_block(&main_data.llvm_context, "block_start");
@@ -878,8 +1197,10 @@ impl AstNode {
 					local_variables: HashMap::new(),
 					allocas_in_use: HashSet::new(),
 					array_allocas_in_use: HashMap::new(),
-					is_loop: false,
+					loop_blocks: None,
+					label: None,
 				});
+				function_build_data.dump_scopes(main_data, "Enter block");
 				// Build each expression
 				let mut last_built_expression = None;
 				for expression in block_expressions {
@@ -903,6 +1224,7 @@ impl AstNode {
 					}
 				}
 				// Pop the block level we pushed
+				function_build_data.dump_scopes(main_data, "Exit block");
 				function_build_data.block_stack.pop();
 				// Branch to the basic block that was created before to branch to after the BCZ block was built and position the builder to it
 				llvm_builder.build_branch(function_build_data.block_stack.last().unwrap().last_block());
@@ -917,28 +1239,74 @@ impl AstNode {
 			AstNodeVariant::FunctionCall(function_to_call, arguments) => {
 				let function_build_data = match function_build_data {
 					Some(function_build_data) => function_build_data,
-					None => return Err((Error::FeatureNotYetImplemented("Global function calls".into()), self.start))
+					None => return Err((Error::FeatureNotYetImplemented("Global function calls".into()), self.span()))
+				};
+				// Calls to a built-in function that is not shadowed by a local variable are built by the built-in function
+				let builtin = match &function_to_call.variant {
+					AstNodeVariant::Identifier(name)
+					if !function_build_data.block_stack.iter().any(|block_level| block_level.local_variables.contains_key(name)) =>
+						main_data.builtins.get(&**name),
+					_ => None,
 				};
-				if arguments.len() > u16::MAX as usize {
-					return Err((Error::TooManyFunctionArguments, self.start))
+				if let Some(builtin) = builtin {
+					if arguments.len() != builtin.parameter_count {
+						return Err((Error::InvalidBuiltInFunctionArgumentCount, self.span()));
+					}
+					let mut arguments_built = Vec::with_capacity(arguments.len());
+					for argument in arguments {
+						arguments_built.push(argument.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?.get_value(main_data, llvm_builder));
+					}
+					return Ok(BuiltRValue::Value((builtin.lower)(main_data, llvm_module, llvm_builder, &arguments_built)));
 				}
+				// Calls to a global function that is not shadowed by a local variable can be built directly to the function
+				let direct_function = match &function_to_call.variant {
+					AstNodeVariant::Identifier(name)
+					if !function_build_data.block_stack.iter().any(|block_level| block_level.local_variables.contains_key(name)) =>
+						file_build_data.built_global_function_signatures.get(name).cloned(),
+					_ => None,
+				};
 				// Build function body and arguments
-				let function_pointer_built = function_to_call
-					.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?;
+				let function_pointer_built = match direct_function {
+					Some(_) => None,
+					None => Some(function_to_call.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?),
+				};
 				let mut arguments_built = Vec::with_capacity(arguments.len());
 				for argument in arguments {
 					arguments_built.push(argument.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?.get_value(main_data, llvm_builder));
 				}
+				// Fill in missing trailing arguments with the defaults of the global or imported function being called
+				if let AstNodeVariant::Identifier(name) = &function_to_call.variant {
+					let is_local_variable = function_build_data.block_stack.iter().any(|block_level| block_level.local_variables.contains_key(name));
+					if let (false, Some(defaults)) = (is_local_variable, file_build_data.default_arguments.get(name)) {
+						for default in defaults.iter().skip(arguments.len()) {
+							match default {
+								Some(default) => arguments_built.push(main_data.int_type.const_int(*default as u128, false)),
+								None => return Err((Error::MissingFunctionArguments(defaults.len(), arguments.len()), self.span())),
+							}
+						}
+					}
+				}
+				// The limit is on the arguments passed, including the defaults that were filled in
+				if arguments_built.len() > main_data.max_function_arguments as usize {
+					return Err((Error::TooManyFunctionArguments, self.span()))
+				}
 				// Build types
-				let argument_types: Box<[Type]> = repeat(main_data.int_type).take(arguments.len()).collect();
-				let function_type = main_data.int_type.function_type(&*argument_types, false);
+				let function_type = file_build_data.int_function_type(main_data.int_type, arguments_built.len(), false);
 				let function_pointer_type = function_type.pointer_to();
 				// Build function call
-				let function_pointer = function_pointer_built
-					.get_value(main_data, llvm_builder)
-					.build_int_to_ptr(llvm_builder, function_pointer_type, "int_to_ptr_temp");
+				let function_pointer = match (direct_function, function_pointer_built) {
+					// The function can only be called directly if it has the same amount of parameters as arguments, otherwise it is called through a cast pointer
+					(Some(function), _) if function.count_parameters() == arguments_built.len() => function,
+					(Some(function), _) => function
+						.build_ptr_to_int(llvm_builder, main_data.int_type, &temp_name("fn_ptr_to_int_temp"))
+						.build_int_to_ptr(llvm_builder, function_pointer_type, &temp_name("int_to_ptr_temp")),
+					(None, Some(function_pointer_built)) => function_pointer_built
+						.get_value(main_data, llvm_builder)
+						.build_int_to_ptr(llvm_builder, function_pointer_type, &temp_name("int_to_ptr_temp")),
+					(None, None) => unreachable!(),
+				};
 				let built_function_call = function_pointer
-					.build_call(arguments_built.as_slice(), function_type, llvm_builder, "function_call_temp");
+					.build_call(arguments_built.as_slice(), function_type, llvm_builder, &temp_name("function_call_temp"));
 				BuiltRValue::Value(built_function_call)
 			}
 			// For a built in function, building depends on the function
@@ -947,7 +1315,7 @@ impl AstNode {
 					Keyword::Write => {
 						let function_build_data = match function_build_data {
 							Some(function_build_data) => function_build_data,
-							None => return Err((Error::GlobalOperatorNotConstEvaluated, self.start))
+							None => return Err((Error::GlobalOperatorNotConstEvaluated, self.span()))
 						};
 						// Get arguments
 						let (address_to_write_to, (write_type, is_signed), value_to_write) = match arguments.len() {
@@ -955,29 +1323,21 @@ impl AstNode {
 								(&arguments[0], (main_data.int_type, false), &arguments[1])
 							}
 							3 => (&arguments[0], *(&arguments[1].type_from_width(main_data)?), &arguments[2]),
-							_ => return Err((Error::InvalidBuiltInFunctionArgumentCount, self.start)),
+							_ => return Err((Error::InvalidBuiltInFunctionArgumentCount, self.span())),
 						};
 						if write_type.is_void() {
-							return Err((Error::VoidParameter, self.start))
+							return Err((Error::VoidParameter, self.span()))
 						}
 						let write_type_ptr = write_type.pointer_to();
 						// Build arguments
 						let address_to_write_to_built = address_to_write_to
 							.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
 							.get_value(main_data, llvm_builder)
-							.build_int_to_ptr(llvm_builder, write_type_ptr, "int_to_ptr_temp");
+							.build_int_to_ptr(llvm_builder, write_type_ptr, &temp_name("int_to_ptr_temp"));
 						let value_to_write_built = value_to_write
 							.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
 							.get_value(main_data, llvm_builder);
-						let value_to_write_built_cast = match main_data.int_bit_width
-							.cmp(&(write_type.size_in_bits(&main_data.llvm_data_layout) as u8)) {
-							Ordering::Greater => value_to_write_built.clone().build_truncate(llvm_builder, write_type, "truncate_temp"),
-							Ordering::Equal => value_to_write_built.clone(),
-							Ordering::Less => match is_signed {
-								false => value_to_write_built.clone().build_zero_extend(llvm_builder, write_type, "zero_extend_temp"),
-								true => value_to_write_built.clone().build_sign_extend(llvm_builder, write_type, "sign_extend_temp"),
-							}
-						};
+						let value_to_write_built_cast = value_to_write_built.build_int_cast(llvm_builder, write_type, is_signed, &temp_name("int_cast_temp"));
 						// Build write
 						address_to_write_to_built.build_store(&value_to_write_built_cast, llvm_builder);
 						// Expression yeilds the written value
@@ -986,20 +1346,20 @@ impl AstNode {
 					Keyword::Stack => {
 						let function_build_data = match function_build_data {
 							Some(function_build_data) => function_build_data,
-							None => return Err((Error::GlobalOperatorNotConstEvaluated, self.start))
+							None => return Err((Error::GlobalOperatorNotConstEvaluated, self.span()))
 						};
 						// Get arguments
 						let (count, entry_width) = match arguments.len() {
 							0 => (None, None),
 							1 => (Some(&arguments[0]), None),
 							2 => (Some(&arguments[0]), Some(&arguments[1])),
-							_ => return Err((Error::InvalidBuiltInFunctionArgumentCount, self.start)),
+							_ => return Err((Error::InvalidBuiltInFunctionArgumentCount, self.span())),
 						};
 						// Get entry count
 						let count = match count {
 							Some(count) => match count.variant {
 								AstNodeVariant::Constant(count) => count,
-								_ => return Err((Error::ConstValueRequired, count.start)),
+								_ => return Err((Error::ConstValueRequired, count.span())),
 							}
 							None => 1,
 						};
@@ -1008,54 +1368,102 @@ impl AstNode {
 							Some(entry_width) => {
 								let entry_type = entry_width.type_from_width(main_data)?.0;
 								if entry_type.is_void() {
-									return Err((Error::VoidParameter, self.start));
+									return Err((Error::VoidParameter, self.span()));
 								}
 								entry_type
 							}
 							None => main_data.int_type,
 						};
 						// Get alloca
-						BuiltRValue::Value(function_build_data.get_array_alloca(entry_type, count, llvm_builder, "stack"))
+						BuiltRValue::Array(function_build_data.get_array_alloca(entry_type, count, llvm_builder, "stack"), entry_type.array_type(count as usize))
 					}
 					Keyword::EntryPoint | Keyword::Export => unreachable!(),
+					Keyword::Section => {
+						if function_build_data.is_some() {
+							return Err((Error::FeatureNotYetImplemented("Section in function".into()), self.span()));
+						}
+						child.as_ref().unwrap().build_r_value(main_data, file_build_data, llvm_module, llvm_builder, None)?
+					}
+					// Nodes that were folded are replaced with their value by const evaluation, any left are inside nodes that are not const evaluated
+					Keyword::Const => {
+						let child = child.as_ref().unwrap();
+						if !matches!(child.variant, AstNodeVariant::Constant(..)) {
+							return Err((Error::NotAConstantExpression, child.span()));
+						}
+						child.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, function_build_data)?
+					}
+					// Assertions that were checked are replaced by const evaluation, any left are inside nodes that are not const evaluated
+					Keyword::Assert => match arguments[0].variant {
+						AstNodeVariant::Constant(0) => return Err((Error::StaticAssertionFailed, self.span())),
+						AstNodeVariant::Constant(..) => BuiltRValue::Value(main_data.int_type.const_int(0, false)),
+						_ => return Err((Error::StaticAssertionNotConstant, arguments[0].span())),
+					}
+					// Only reached if the width was not const evaluated
+					Keyword::SizeOf => BuiltRValue::Value(main_data.int_type.const_int(arguments[0].size_of_width(main_data)? as u128, false)),
+					// Branch to the runtime failure if the condition is zero
+					Keyword::RuntimeAssert => {
+						let function_build_data = match function_build_data {
+							Some(function_build_data) => function_build_data,
+							None => return Err((Error::GlobalOperatorNotConstEvaluated, self.span()))
+						};
+						if main_data.no_asserts {
+							return Ok(BuiltRValue::Value(main_data.int_type.const_int(0, false)));
+						}
+						let condition = arguments[0]
+							.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
+							.get_value(main_data, llvm_builder);
+						let condition = build_int_to_bool(main_data, llvm_builder, &condition);
+						let failed_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "assert_failed");
+						let passed_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "assert_passed");
+						condition.build_conditional_branch(&passed_basic_block, &failed_basic_block, main_data.llvm_context, llvm_builder);
+						llvm_builder.position_at_end(&failed_basic_block);
+						build_runtime_failure(main_data, llvm_module, llvm_builder);
+						llvm_builder.position_at_end(&passed_basic_block);
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(passed_basic_block);
+						BuiltRValue::Value(main_data.int_type.const_int(0, false))
+					}
 					Keyword::Link => {
 						if function_build_data.is_some() {
-							return Err((Error::FeatureNotYetImplemented("Link in function".into()), self.start));
+							return Err((Error::FeatureNotYetImplemented("Link in function".into()), self.span()));
 						}
 						if arguments.len() < 3 {
-							return Err((Error::InvalidBuiltInFunctionArgumentCount, self.start));
+							return Err((Error::InvalidBuiltInFunctionArgumentCount, self.span()));
 						}
-						if arguments.len() > u16::MAX as usize {
-							return Err((Error::TooManyFunctionParameters, *start));
+						if arguments.len() - 3 > main_data.max_function_parameters as usize {
+							return Err((Error::TooManyFunctionParameters, span));
 						}
 						// Get wrapped function name
 						let wrapped_function_name = &arguments[0];
 						let wrapped_function_name: &str = match &wrapped_function_name.variant {
 							AstNodeVariant::String(link_function_name) => &**link_function_name,
 							AstNodeVariant::Identifier(link_function_name) => &**link_function_name,
-							_ => return Err((Error::ConstValueRequired, wrapped_function_name.start)),
+							_ => return Err((Error::ConstValueRequired, wrapped_function_name.span())),
 						};
 						// Create wrapped function type
 						let parameter_count = arguments.len() - 3;
 						let mut wrapped_parameter_types = Vec::with_capacity(parameter_count);
 						for parameter in &arguments[3..] {
-							let parameter_type = parameter.type_from_width(main_data)?.0;
+							let parameter_type = parameter.link_type_from_width(main_data)?.0;
 							if parameter_type.is_void() {
-								return Err((Error::VoidParameter, self.start));
+								return Err((Error::VoidParameter, self.span()));
 							}
 							wrapped_parameter_types.push(parameter_type);
 						}
-						let (wrapped_function_return_type, wrapped_function_return_type_is_signed) = arguments[2].type_from_width(main_data)?;
+						let (wrapped_function_return_type, wrapped_function_return_type_is_signed) = arguments[2].link_type_from_width(main_data)?;
 						let wrapped_function_type = wrapped_function_return_type.function_type(&*wrapped_parameter_types, false);
-						// Create wrapped function
+						// Create wrapped function, freestanding programs link to it directly using the C calling convention
+						let (wrapped_function_linkage, wrapped_function_calling_convention) = match main_data.freestanding {
+							true => (Linkage::External, CallingConvention::C),
+							false => (Linkage::DLLImport, CallingConvention::Win64),
+						};
 						let wrapped_function = llvm_module.add_function(wrapped_function_type, &*wrapped_function_name);
-						wrapped_function.set_linkage(Linkage::DLLImport);
-						wrapped_function.set_calling_convention(CallingConvention::Win64);
+						wrapped_function.set_linkage(wrapped_function_linkage);
+						wrapped_function.set_calling_convention(wrapped_function_calling_convention);
 						// Create wrapper function type
-						let wrapper_function_parameter_types: Box<[Type]> = repeat_n(main_data.int_type, parameter_count).collect();
-						let wrapper_function_type = main_data.int_type.function_type(&wrapper_function_parameter_types, false);
+						let wrapper_function_type = file_build_data.int_function_type(main_data.int_type, parameter_count, false);
 						// Create wrapper function
-						let wrapper_function = llvm_module.add_function(wrapper_function_type, &format!("__link__{wrapped_function_name}"));
+						let wrapper_function_name = file_build_data.mangle_name(MangledSymbol::LinkWrapper(wrapped_function_name));
+						let wrapper_function = llvm_module.add_function(wrapper_function_type, &wrapper_function_name);
 						wrapper_function.set_linkage(Linkage::Internal);
 						// Build casts
 						let basic_block = wrapper_function.append_basic_block(&main_data.llvm_context, "entry");
@@ -1064,44 +1472,31 @@ impl AstNode {
 						for (parameter_index, parameter) in (&arguments[3..]).iter().enumerate() {
 							let (parameter_type, is_signed) = parameter.type_from_width(main_data)?;
 							let argument = wrapper_function.get_parameter(parameter_index);
-								let argument_converted = match main_data.int_bit_width
-									.cmp(&(parameter_type.size_in_bits(&main_data.llvm_data_layout) as u8)) {
-								Ordering::Less => match is_signed {
-									false => argument.build_zero_extend(llvm_builder, parameter_type, "z_extend_temp"),
-									true => argument.build_sign_extend(llvm_builder, parameter_type, "s_extend_temp"),
-								}
-								Ordering::Equal => argument,
-								Ordering::Greater => argument.build_truncate(llvm_builder, parameter_type, "truncate_temp"),
-							};
+							let argument_converted = argument.build_int_cast(llvm_builder, parameter_type, is_signed, &temp_name("int_cast_temp"));
 							arguments_converted.push(argument_converted);
 						}
-						let call_result = wrapped_function.build_call(arguments_converted.as_slice(), wrapped_function_type, llvm_builder, "wrapped_function_call_temp");
+						let call_result = wrapped_function.build_call_with_calling_convention(
+							arguments_converted.as_slice(), wrapped_function_type, wrapped_function_calling_convention, llvm_builder, &temp_name("wrapped_function_call_temp")
+						);
 						// Build return
 						if wrapped_function_return_type.is_void() {
 							llvm_builder.build_return_void();
 						}
 						else {
-							let call_result_converted = match main_data.int_bit_width
-								.cmp(&(wrapped_function_return_type.size_in_bits(&main_data.llvm_data_layout) as u8)) {
-								Ordering::Less => call_result.build_truncate(llvm_builder, main_data.int_type, "truncate_temp"),
-								Ordering::Equal => call_result,
-								Ordering::Greater => match wrapped_function_return_type_is_signed {
-									false => call_result.build_zero_extend(llvm_builder, main_data.int_type, "zero_extend_temp"),
-									true => call_result.build_sign_extend(llvm_builder, main_data.int_type, "sign_extend_temp"),
-								}
-							};
+							let call_result_converted = call_result
+								.build_int_cast(llvm_builder, main_data.int_type, wrapped_function_return_type_is_signed, &temp_name("int_cast_temp"));
 							call_result_converted.build_return(llvm_builder);
 						}
 						// Return wrapper function as int
-						BuiltRValue::Value(wrapper_function.build_ptr_to_int(llvm_builder, main_data.int_type, "link_fn_to_int_temp"))
+						BuiltRValue::Value(wrapper_function.build_ptr_to_int(llvm_builder, main_data.int_type, &temp_name("link_fn_to_int_temp")))
 					}
 					Keyword::Loop => {
 						let function_build_data = match function_build_data {
 							Some(function_build_data) => function_build_data,
-							None => return Err((Error::GlobalOperatorNotConstEvaluated, self.start))
+							None => return Err((Error::GlobalOperatorNotConstEvaluated, self.span()))
 						};
 						if !arguments.is_empty() {
-							return Err((Error::FeatureNotYetImplemented("Loop arguments".into()), self.start));
+							return Err((Error::FeatureNotYetImplemented("Loop arguments".into()), self.span()));
 						}
 						// Get the alloca for the loop result
 						let result_alloca = function_build_data.get_alloca(main_data, llvm_builder, "loop_result");
@@ -1110,86 +1505,265 @@ impl AstNode {
 						llvm_builder.build_branch(&inner_basic_block);
 						llvm_builder.position_at_end(&inner_basic_block);
 						// Create a basic block to branch to after we are done with the BCZ block we are building
-						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(function_build_data.function.append_basic_block(&main_data.llvm_context, "loop_end"));
-						// Push a new block level onto the block stack
+						let loop_end_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "loop_end");
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(loop_end_basic_block.clone());
+						// Push a new block level onto the block stack, @continue branches to the start of the loop and @break to the end
 						function_build_data.block_stack.push(BlockLevel {
 							basic_blocks: vec![inner_basic_block.clone()],
 							local_variables: HashMap::new(),
 							allocas_in_use: HashSet::new(),
 							array_allocas_in_use: HashMap::new(),
-							is_loop: true,
+							loop_blocks: Some((inner_basic_block.clone(), loop_end_basic_block)),
+							label: None,
 						});
+						function_build_data.dump_scopes(main_data, "Enter loop");
 						// Build child expression
 						child.as_ref().unwrap().build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?;
 						// Build branch from end of loop to start of loop
 						llvm_builder.build_branch(&function_build_data.block_stack.last().unwrap().basic_blocks[0]);
 						// Pop the scope we pushed
+						function_build_data.dump_scopes(main_data, "Exit loop");
 						function_build_data.block_stack.pop();
 						// Branch to the basic block that was created before to branch to after the BCZ block was built and position the builder to it
 						llvm_builder.position_at_end(function_build_data.block_stack.last().unwrap().last_block());
 						// Return
-						let result = result_alloca.build_load(main_data.int_type, llvm_builder, "loop_result_temp");
+						let result = result_alloca.build_load(main_data.int_type, llvm_builder, &temp_name("loop_result_temp"));
+						function_build_data.surrender_alloca(result_alloca);
+						BuiltRValue::Value(result)
+					}
+					// A loop that runs the child and then repeats if the condition is non-zero, the child always runs at least once
+					Keyword::DoWhile => {
+						let function_build_data = match function_build_data {
+							Some(function_build_data) => function_build_data,
+							None => return Err((Error::GlobalOperatorNotConstEvaluated, self.span()))
+						};
+						let body_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "do_while_body");
+						let condition_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "do_while_condition");
+						let end_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "do_while_end");
+						llvm_builder.build_branch(&body_basic_block);
+						llvm_builder.position_at_end(&body_basic_block);
+						// Push a new block level onto the block stack, @continue branches to the condition and @break to the end
+						function_build_data.block_stack.push(BlockLevel {
+							basic_blocks: vec![body_basic_block.clone()],
+							local_variables: HashMap::new(),
+							allocas_in_use: HashSet::new(),
+							array_allocas_in_use: HashMap::new(),
+							loop_blocks: Some((condition_basic_block.clone(), end_basic_block.clone())),
+							label: None,
+						});
+						function_build_data.dump_scopes(main_data, "Enter do while");
+						// Build child expression then branch to the condition
+						child.as_ref().unwrap().build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?;
+						llvm_builder.build_branch(&condition_basic_block);
+						// Pop the scope we pushed
+						function_build_data.dump_scopes(main_data, "Exit do while");
+						function_build_data.block_stack.pop();
+						// Build the condition and branch back to the body if it is true
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(condition_basic_block.clone());
+						llvm_builder.position_at_end(&condition_basic_block);
+						let condition = arguments[0]
+							.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
+							.get_value(main_data, llvm_builder);
+						let condition = build_int_to_bool(main_data, llvm_builder, &condition);
+						condition.build_conditional_branch(&body_basic_block, &end_basic_block, main_data.llvm_context, llvm_builder);
+						// Continue building after the loop
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(end_basic_block.clone());
+						llvm_builder.position_at_end(&end_basic_block);
+						BuiltRValue::Value(main_data.int_type.undefined())
+					}
+					// A loop over a range, the induction variable counts from the start of the range up to but not including the end
+					Keyword::For => {
+						let function_build_data = match function_build_data {
+							Some(function_build_data) => function_build_data,
+							None => return Err((Error::GlobalOperatorNotConstEvaluated, self.span()))
+						};
+						let induction_variable_name = match &arguments[0].variant {
+							AstNodeVariant::Identifier(name) => name.clone(),
+							_ => return Err((Error::ExpectedIdentifier, arguments[0].span())),
+						};
+						let (range_start, range_end) = match &arguments[1].variant {
+							AstNodeVariant::Range(range_start, range_end) => (range_start, range_end),
+							_ => return Err((Error::ExpectedRange, arguments[1].span())),
+						};
+						// Build the range once before the loop and store the start in the induction variable
+						let range_start_built = range_start
+							.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
+							.get_value(main_data, llvm_builder);
+						let range_end_built = range_end
+							.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
+							.get_value(main_data, llvm_builder);
+						let induction_variable = function_build_data.get_alloca(main_data, llvm_builder, &induction_variable_name);
+						induction_variable.build_store(&range_start_built, llvm_builder);
+						let condition_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "for_condition");
+						let body_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "for_body");
+						let increment_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "for_increment");
+						let end_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "for_end");
+						// Loop while the induction variable is less than the end of the range
+						llvm_builder.build_branch(&condition_basic_block);
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(condition_basic_block.clone());
+						llvm_builder.position_at_end(&condition_basic_block);
+						let induction_variable_value = induction_variable.build_load(main_data.int_type, llvm_builder, &temp_name("for_induction_temp"));
+						let condition = induction_variable_value.build_compare(&range_end_built, Comparison::SignedLessThan, llvm_builder, &temp_name("for_condition_temp"));
+						condition.build_conditional_branch(&body_basic_block, &end_basic_block, main_data.llvm_context, llvm_builder);
+						// Push a new block level onto the block stack with the induction variable in it, @continue branches to the increment and @break to the end
+						llvm_builder.position_at_end(&body_basic_block);
+						function_build_data.block_stack.push(BlockLevel {
+							basic_blocks: vec![body_basic_block.clone()],
+							local_variables: HashMap::from([(induction_variable_name, BuiltLValue::AllocaVariable(induction_variable.clone()))]),
+							allocas_in_use: HashSet::new(),
+							array_allocas_in_use: HashMap::new(),
+							loop_blocks: Some((increment_basic_block.clone(), end_basic_block.clone())),
+							label: None,
+						});
+						function_build_data.dump_scopes(main_data, "Enter for");
+						// Build child expression then branch to the increment
+						child.as_ref().unwrap().build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?;
+						llvm_builder.build_branch(&increment_basic_block);
+						// Pop the scope we pushed
+						function_build_data.dump_scopes(main_data, "Exit for");
+						function_build_data.block_stack.pop();
+						// Increment the induction variable and go back to the condition
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(increment_basic_block.clone());
+						llvm_builder.position_at_end(&increment_basic_block);
+						let induction_variable_value = induction_variable.build_load(main_data.int_type, llvm_builder, &temp_name("for_induction_temp"));
+						let incremented = induction_variable_value.build_add(&main_data.int_type.const_int(1, false), llvm_builder, &temp_name("for_increment_temp"));
+						induction_variable.build_store(&incremented, llvm_builder);
+						llvm_builder.build_branch(&condition_basic_block);
+						// Continue building after the loop
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(end_basic_block.clone());
+						llvm_builder.position_at_end(&end_basic_block);
+						function_build_data.surrender_alloca(induction_variable);
+						BuiltRValue::Value(main_data.int_type.undefined())
+					}
+					Keyword::Label => {
+						let function_build_data = match function_build_data {
+							Some(function_build_data) => function_build_data,
+							None => return Err((Error::GlobalOperatorNotConstEvaluated, self.span()))
+						};
+						let label = match &arguments[0].variant {
+							AstNodeVariant::Identifier(label) => label.clone(),
+							_ => return Err((Error::ExpectedIdentifier, arguments[0].span())),
+						};
+						// Get the alloca for the result, this is the value of the child or the value given to a @break
+						let result_alloca = function_build_data.get_alloca(main_data, llvm_builder, "label_result");
+						// Create the first inner basic block for the BCZ block, then branch from the current basic block to it, then re-position the builder to the new basic block
+						let inner_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "label_start");
+						llvm_builder.build_branch(&inner_basic_block);
+						llvm_builder.position_at_end(&inner_basic_block);
+						// Create a basic block to branch to after we are done with the BCZ block, a @break to this label also branches here
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(function_build_data.function.append_basic_block(main_data.llvm_context, "label_end"));
+						// Push a new block level onto the block stack
+						function_build_data.block_stack.push(BlockLevel {
+							basic_blocks: vec![inner_basic_block],
+							local_variables: HashMap::new(),
+							allocas_in_use: HashSet::new(),
+							array_allocas_in_use: HashMap::new(),
+							loop_blocks: None,
+							label: Some((label, result_alloca.clone())),
+						});
+						function_build_data.dump_scopes(main_data, "Enter label");
+						// Build child expression and store its value as the result
+						let child_result = child.as_ref().unwrap()
+							.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
+							.get_value(main_data, llvm_builder);
+						result_alloca.build_store(&child_result, llvm_builder);
+						// Pop the scope we pushed
+						function_build_data.dump_scopes(main_data, "Exit label");
+						function_build_data.block_stack.pop();
+						// Branch to the basic block that was created before to branch to after the BCZ block was built and position the builder to it
+						llvm_builder.build_branch(function_build_data.block_stack.last().unwrap().last_block());
+						llvm_builder.position_at_end(function_build_data.block_stack.last().unwrap().last_block());
+						// Return
+						let result = result_alloca.build_load(main_data.int_type, llvm_builder, &temp_name("label_result_temp"));
 						function_build_data.surrender_alloca(result_alloca);
 						BuiltRValue::Value(result)
 					}
 					Keyword::Break => {
 						let function_build_data = match function_build_data {
 							Some(function_build_data) => function_build_data,
-							None => return Err((Error::FeatureNotYetImplemented("Blocks in global scope".into()), self.start)),
+							None => return Err((Error::FeatureNotYetImplemented("Blocks in global scope".into()), self.span())),
 						};
-						let mut last_was_loop = false;
-						for block_level in function_build_data.block_stack.iter().rev() {
-							if last_was_loop {
-								llvm_builder.build_branch(&block_level.last_block());
-								let unreachable_basic_block = function_build_data.function.append_basic_block(&main_data.llvm_context, "break_unreachable");
-								llvm_builder.position_at_end(&unreachable_basic_block);
-								function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(unreachable_basic_block);
-								return Ok(BuiltRValue::Value(main_data.int_type.undefined()));
+						// Exit the labeled block with the given label, storing the result value if one was given
+						if let Some(label_argument) = arguments.first() {
+							let label = match &label_argument.variant {
+								AstNodeVariant::Identifier(label) => label,
+								_ => return Err((Error::ExpectedIdentifier, label_argument.span())),
+							};
+							let value = match arguments.get(1) {
+								Some(value) => Some(value
+									.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
+									.get_value(main_data, llvm_builder)),
+								None => None,
+							};
+							let mut last_was_label = false;
+							for block_level in function_build_data.block_stack.iter().rev() {
+								if last_was_label {
+									llvm_builder.build_branch(block_level.last_block());
+									let unreachable_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "break_unreachable");
+									llvm_builder.position_at_end(&unreachable_basic_block);
+									function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(unreachable_basic_block);
+									return Ok(BuiltRValue::Value(main_data.int_type.undefined()));
+								}
+								if let Some((block_label, result_alloca)) = &block_level.label {
+									if block_label == label {
+										if let Some(value) = &value {
+											result_alloca.build_store(value, llvm_builder);
+										}
+										last_was_label = true;
+									}
+								}
 							}
-							last_was_loop = block_level.is_loop;
+							return Err((Error::UndefinedLabel(label.to_string()), label_argument.span()));
 						}
-						return Err((Error::NotUsedInsideLoop, self.start));
+						// Branch to the end of the innermost loop
+						let loop_end_basic_block = match function_build_data.block_stack.iter().rev().find_map(|block_level| block_level.loop_blocks.as_ref()) {
+							Some((_, loop_end_basic_block)) => loop_end_basic_block,
+							None => return Err((Error::NotUsedInsideLoop, self.span())),
+						};
+						llvm_builder.build_branch(loop_end_basic_block);
+						let unreachable_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "break_unreachable");
+						llvm_builder.position_at_end(&unreachable_basic_block);
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(unreachable_basic_block);
+						BuiltRValue::Value(main_data.int_type.undefined())
 					}
 					Keyword::Continue => {
 						let function_build_data = match function_build_data {
 							Some(function_build_data) => function_build_data,
-							None => return Err((Error::FeatureNotYetImplemented("Blocks in global scope".into()), self.start)),
+							None => return Err((Error::FeatureNotYetImplemented("Blocks in global scope".into()), self.span())),
 						};
-						for block_level in function_build_data.block_stack.iter().rev() {
-							if block_level.is_loop {
-								llvm_builder.build_branch(&block_level.basic_blocks[0]);
-								let unreachable_basic_block = function_build_data.function.append_basic_block(&main_data.llvm_context, "continue_unreachable");
-								llvm_builder.position_at_end(&unreachable_basic_block);
-								function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(unreachable_basic_block);
-								return Ok(BuiltRValue::Value(main_data.int_type.undefined()));
-							}
-						}
-						return Err((Error::NotUsedInsideLoop, self.start));
+						// Branch to the start of the innermost loop
+						let loop_start_basic_block = match function_build_data.block_stack.iter().rev().find_map(|block_level| block_level.loop_blocks.as_ref()) {
+							Some((loop_start_basic_block, _)) => loop_start_basic_block,
+							None => return Err((Error::NotUsedInsideLoop, self.span())),
+						};
+						llvm_builder.build_branch(loop_start_basic_block);
+						let unreachable_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "continue_unreachable");
+						llvm_builder.position_at_end(&unreachable_basic_block);
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(unreachable_basic_block);
+						BuiltRValue::Value(main_data.int_type.undefined())
 					}
 					Keyword::Import => {
 						// Get arguments
 						let (filepath, global_variable_name) = match arguments.len() {
 							2 => (&arguments[0], &arguments[1]),
-							_ => return Err((Error::InvalidBuiltInFunctionArgumentCount, self.start)),
+							_ => return Err((Error::InvalidBuiltInFunctionArgumentCount, self.span())),
 						};
 						// Get filepath
 						let filepath = match &filepath.variant {
 							AstNodeVariant::String(filepath) => &**filepath,
 							AstNodeVariant::Identifier(filepath) => &**filepath,
-							_ => return Err((Error::ConstValueRequired, filepath.start)),
+							_ => return Err((Error::ConstValueRequired, filepath.span())),
 						};
 						let filepath_buff = relative_filepath_to_absolute(main_data, file_build_data.filepath, filepath)
-							.map_err(|error| (error, *start))?;
-						let mut hasher = DefaultHasher::new();
-						filepath_buff.hash(&mut hasher);
-						let hash = hasher.finish();
+							.map_err(|error| (error, span))?;
 						// Get global variable name
 						let global_variable_name = match &global_variable_name.variant {
 							AstNodeVariant::String(global_variable_name) => &**global_variable_name,
 							AstNodeVariant::Identifier(global_variable_name) => &**global_variable_name,
-							_ => return Err((Error::ConstValueRequired, global_variable_name.start)),
+							_ => return Err((Error::ConstValueRequired, global_variable_name.span())),
 						};
-						let global = llvm_module.add_global(main_data.int_type, &format!("__export__{hash}__{global_variable_name}"));
+						let global_name = file_build_data.mangle_name(MangledSymbol::Export(&filepath_buff, global_variable_name));
+						let global = llvm_module.add_global(main_data.int_type, &global_name);
 						global.set_linkage(Linkage::External);
 						global.set_is_constant(true);
 						BuiltRValue::ImportedConstant(global)
@@ -1205,6 +1779,58 @@ impl AstNode {
 				string.set_initializer(&main_data.llvm_context.const_string(text, true));
 				BuiltRValue::Value(string.build_ptr_to_int(llvm_builder, main_data.int_type, "str_ptr_to_int"))
 			}
+			AstNodeVariant::MemberAccess(..) => return Err((Error::MemberAccessNotSupported, self.span())),
+			// Ranges are not values, they are only used by @for
+			AstNodeVariant::Range(..) => return Err((Error::FeatureNotYetImplemented("Ranges outside of @for".into()), self.span())),
+			// For a comparison chain, each comparison is built in turn and a false comparison skips the rest of the chain
+			AstNodeVariant::ComparisonChain(operations, operands) => {
+				if let Some(void_operand) = operands.iter().find(|operand| operand.is_void()) {
+					return Err((Error::UseOfVoidValue, void_operand.span()));
+				}
+				let function_build_data = match function_build_data {
+					Some(function_build_data) => function_build_data,
+					None => return Err((Error::GlobalOperatorNotConstEvaluated, self.span())),
+				};
+				// Get the alloca to write the result to
+				let result_alloca = function_build_data.get_alloca(main_data, llvm_builder, "comparison_chain_value");
+				function_build_data.block_stack.last_mut().unwrap().allocas_in_use.insert(result_alloca.clone());
+				let end_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "comparison_chain_end");
+				// Build each comparison, the right operand of each comparison is the left operand of the next
+				let mut left_value = operands[0].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
+					.get_value(main_data, llvm_builder);
+				for (index, operation) in operations.iter().enumerate() {
+					let right_value = operands[index + 1].build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
+						.get_value(main_data, llvm_builder);
+					let comparison = match operation {
+						Operation::UnsignedLessThan => Comparison::UnsignedLessThan,
+						Operation::UnsignedLessThanOrEqualTo => Comparison::UnsignedLessThanOrEqualTo,
+						Operation::UnsignedGreaterThan => Comparison::UnsignedGreaterThan,
+						Operation::UnsignedGreaterThanOrEqualTo => Comparison::UnsignedGreaterThanOrEqualTo,
+						Operation::SignedLessThan => Comparison::SignedLessThan,
+						Operation::SignedLessThanOrEqualTo => Comparison::SignedLessThanOrEqualTo,
+						Operation::SignedGreaterThan => Comparison::SignedGreaterThan,
+						Operation::SignedGreaterThanOrEqualTo => Comparison::SignedGreaterThanOrEqualTo,
+						_ => return Err((Error::FeatureNotYetImplemented("This operator in a comparison chain".into()), self.span())),
+					};
+					let is_true = left_value.build_compare(&right_value, comparison, llvm_builder, &temp_name("chain_compare_temp"));
+					result_alloca.build_store(&is_true.build_zero_extend(llvm_builder, main_data.int_type, &temp_name("bool_to_int_temp")), llvm_builder);
+					if index + 1 < operations.len() {
+						let next_comparison_basic_block = function_build_data.function.append_basic_block(main_data.llvm_context, "next_comparison");
+						is_true.build_conditional_branch(&next_comparison_basic_block, &end_basic_block, main_data.llvm_context, llvm_builder);
+						llvm_builder.position_at_end(&next_comparison_basic_block);
+						function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(next_comparison_basic_block);
+					}
+					left_value = right_value;
+				}
+				llvm_builder.build_branch(&end_basic_block);
+				// Re-position builder at end
+				llvm_builder.position_at_end(&end_basic_block);
+				function_build_data.block_stack.last_mut().unwrap().basic_blocks.push(end_basic_block);
+				// Read result
+				let result = result_alloca.build_load(main_data.int_type, llvm_builder, "read_result");
+				function_build_data.surrender_alloca(result_alloca);
+				BuiltRValue::Value(result)
+			}
 		})
 	}
 
@@ -1216,7 +1842,7 @@ impl AstNode {
 		llvm_module: &'a Module,
 		llvm_builder: &'a Builder<'a, 'a>,
 		function_build_data: Option<&mut FunctionBuildData<'a, 'b>>,
-	) -> Result<BuiltLValue<'a>, (Error, (NonZeroUsize, NonZeroUsize))> {
+	) -> Result<BuiltLValue<'a>, (Error, Span)> {
 		// Unpack
 		let Self {
 			start: _,
@@ -1229,7 +1855,7 @@ impl AstNode {
 			AstNodeVariant::Identifier(name) => {
 				let function_build_data = match function_build_data {
 					Some(function_build_data) => function_build_data,
-					None => return Err((Error::GlobalOperatorNotConstEvaluated, self.start)),
+					None => return Err((Error::GlobalOperatorNotConstEvaluated, self.span())),
 				};
 				// Get local variable if it exists
 				for scope_level in function_build_data.block_stack.iter().rev() {
@@ -1237,46 +1863,58 @@ impl AstNode {
 						return Ok(variable.clone());
 					}
 				}
+				// Globals are constant so they can't be shadowed by a new local variable
+				if file_build_data.global_names.contains(name) {
+					return Err((Error::AssignmentToConstant(name.to_string()), self.span()));
+				}
 				// Get alloca for variable
 				let alloca = function_build_data.get_alloca(main_data, llvm_builder, name);
 				// Insert variable into list
 				function_build_data.block_stack.last_mut().unwrap().local_variables.insert(name.clone(), BuiltLValue::AllocaVariable(alloca.clone()));
+				function_build_data.dump_scopes(main_data, &format!("Create variable {name}"));
 				// Return variable
 				BuiltLValue::AllocaVariable(alloca)
 			}
-			AstNodeVariant::Constant(..) => return Err((Error::InvalidLValue, self.start)),
-			AstNodeVariant::String(..) => return Err((Error::InvalidLValue, self.start)),
-			AstNodeVariant::FunctionCall(..) => return Err((Error::InvalidLValue, self.start)),
-			AstNodeVariant::FunctionDefinition(..) => return Err((Error::InvalidLValue, self.start)),
+			AstNodeVariant::Constant(..) => return Err((Error::InvalidLValue, self.span())),
+			AstNodeVariant::String(..) => return Err((Error::InvalidLValue, self.span())),
+			AstNodeVariant::FunctionCall(..) => return Err((Error::InvalidLValue, self.span())),
+			AstNodeVariant::FunctionDefinition(..) => return Err((Error::InvalidLValue, self.span())),
 			AstNodeVariant::Keyword(keyword, _arguments, _child) => {
 				match keyword {
-					Keyword::Link | Keyword::EntryPoint | Keyword::Import | Keyword::Export => return Err((Error::InvalidLValue, self.start)),
-					Keyword::Write => return Err((Error::FeatureNotYetImplemented("L-value write".into()), self.start)),
-					Keyword::Stack => return Err((Error::FeatureNotYetImplemented("L-value stack".into()), self.start)),
-					Keyword::Loop => return Err((Error::FeatureNotYetImplemented("L-value loop".into()), self.start)),
-					Keyword::Break => return Err((Error::FeatureNotYetImplemented("L-value break".into()), self.start)),
-					Keyword::Continue => return Err((Error::FeatureNotYetImplemented("L-value continue".into()), self.start)),
+					Keyword::Link | Keyword::EntryPoint | Keyword::Import | Keyword::Export | Keyword::Section | Keyword::Const | Keyword::Assert | Keyword::RuntimeAssert |
+					Keyword::SizeOf => return Err((Error::InvalidLValue, self.span())),
+					Keyword::Write => return Err((Error::FeatureNotYetImplemented("L-value write".into()), self.span())),
+					Keyword::Stack => return Err((Error::FeatureNotYetImplemented("L-value stack".into()), self.span())),
+					Keyword::Loop => return Err((Error::FeatureNotYetImplemented("L-value loop".into()), self.span())),
+					Keyword::Break => return Err((Error::FeatureNotYetImplemented("L-value break".into()), self.span())),
+					Keyword::Label => return Err((Error::FeatureNotYetImplemented("L-value label".into()), self.span())),
+					Keyword::DoWhile => return Err((Error::FeatureNotYetImplemented("L-value do while".into()), self.span())),
+					Keyword::For => return Err((Error::FeatureNotYetImplemented("L-value for".into()), self.span())),
+					Keyword::Continue => return Err((Error::FeatureNotYetImplemented("L-value continue".into()), self.span())),
 					Keyword::SystemConstant => unreachable!(),
 				}
 			}
-			AstNodeVariant::Block(..) => return Err((Error::FeatureNotYetImplemented("L-value blocks".into()), self.start)),
+			AstNodeVariant::Block(..) => return Err((Error::FeatureNotYetImplemented("L-value blocks".into()), self.span())),
+			AstNodeVariant::MemberAccess(..) => return Err((Error::MemberAccessNotSupported, self.span())),
+			AstNodeVariant::ComparisonChain(..) => return Err((Error::InvalidLValue, self.span())),
+			AstNodeVariant::Range(..) => return Err((Error::InvalidLValue, self.span())),
 			AstNodeVariant::Operator(operator, operands) => match operator {
-				Operator::Assignment => return Err((Error::FeatureNotYetImplemented("L-value assignments".into()), self.start)),
-				Operator::Augmented(..) => return Err((Error::FeatureNotYetImplemented("L-value agumented assignments".into()), self.start)),
-				Operator::LValueAssignment => return Err((Error::InvalidLValue, self.start)),
+				Operator::Assignment => return Err((Error::FeatureNotYetImplemented("L-value assignments".into()), self.span())),
+				Operator::Augmented(..) => return Err((Error::FeatureNotYetImplemented("L-value agumented assignments".into()), self.span())),
+				Operator::LValueAssignment => return Err((Error::InvalidLValue, self.span())),
 				Operator::Normal(operation) => match operation {
 					Operation::Dereference => {
 						let function_build_data = match function_build_data {
 							Some(function_build_data) => function_build_data,
-							None => return Err((Error::FeatureNotYetImplemented("Blocks in global scope".into()), self.start)),
+							None => return Err((Error::FeatureNotYetImplemented("Blocks in global scope".into()), self.span())),
 						};
 						let pointer =  operands[0]
 							.build_r_value(main_data, file_build_data, llvm_module, llvm_builder, Some(function_build_data))?
 							.get_value(main_data, llvm_builder)
-							.build_int_to_ptr(llvm_builder, main_data.int_type, "int_to_ptr_for_deref");
+							.build_int_to_ptr(llvm_builder, main_data.int_type.pointer_to(), "int_to_ptr_for_deref");
 						BuiltLValue::DereferencedPointer(pointer)
 					}
-					_ => return Err((Error::FeatureNotYetImplemented("L-value operator".into()), self.start)),
+					_ => return Err((Error::FeatureNotYetImplemented("L-value operator".into()), self.span())),
 				}
 			}
 		})
@@ -1286,7 +1924,7 @@ impl AstNode {
 	pub fn build_global_assignment<'a>(
 		&'a self, main_data: &'a MainData, llvm_module: &'a Module<'a>, llvm_builder: &'a Builder<'a, 'a>, file_build_data: &mut FileBuildData<'a, 'a>, name: &str,
 		is_exported: bool,
-	) -> Result<BuiltRValue<'a>, (Error, (NonZeroUsize, NonZeroUsize))> {
+	) -> Result<BuiltRValue<'a>, (Error, Span)> {
 		// Build r-value/function
 		let r_value = if self.is_function() {
 			let function =
@@ -1298,39 +1936,365 @@ impl AstNode {
 			// Assign to global variable
 			match &r_value {
 				BuiltRValue::Value(value) => {
-					let global = llvm_module.add_global(main_data.int_type, name);
+					// Constants can be stored in the narrowest int type that holds them since reads use the constant directly,
+					// globals that have their address taken are kept full width since they can be read through the address
+					let is_referenced = file_build_data.referenced_globals.contains(name);
+					let (global_type, initializer) = match (main_data.narrow_globals, is_referenced, &self.variant) {
+						(true, false, AstNodeVariant::Constant(constant)) => {
+							let global_type = narrowest_int_type(main_data, *constant);
+							(global_type, global_type.const_int(*constant as u128, false))
+						}
+						_ => (main_data.int_type, value.clone()),
+					};
+					let global = llvm_module.add_global(global_type, name);
 					global.set_linkage(Linkage::Internal);
 					global.set_is_constant(true);
-					global.set_initializer(value);
+					global.set_initializer(&initializer);
+					if let Some(section_name) = self.section_name() {
+						global.set_section(section_name);
+					}
 				}
 				BuiltRValue::ImportedConstant(..) => {}
+				BuiltRValue::Array(..) | BuiltRValue::Float(..) => unreachable!(),
 			}
 			r_value
 		};
 		if is_exported {
-			let mut hasher = DefaultHasher::new();
-			file_build_data.filepath.hash(&mut hasher);
-			let hash = hasher.finish();
-			let global = llvm_module.add_global(main_data.int_type, &format!("__export__{hash}__{name}"));
+			let global_name = file_build_data.mangle_name(MangledSymbol::Export(file_build_data.filepath, name));
+			let global = llvm_module.add_global(main_data.int_type, &global_name);
 			match &r_value {
 				BuiltRValue::Value(value) => {
 					global.set_linkage(Linkage::External);
 					global.set_is_constant(true);
 					global.set_initializer(value);
 				}
-				BuiltRValue::ImportedConstant(..) => return Err((Error::FeatureNotYetImplemented("Re-exporting".into()), self.start))
+				BuiltRValue::ImportedConstant(..) => return Err((Error::FeatureNotYetImplemented("Re-exporting".into()), self.span())),
+				BuiltRValue::Array(..) | BuiltRValue::Float(..) => unreachable!(),
 			}
 		}
 		// Return
 		Ok(r_value)
 	}
 
+	/// If this node is an `@import` of a global variable that was const evaluated to a constant, returns the value of the constant.
+	pub fn imported_constant_value(&self, main_data: &MainData, filepath: &PathBuf) -> Option<u64> {
+		main_data.exported_constants.get(&self.imported_global(main_data, filepath)?).copied()
+	}
+
+	/// If this node is an `@import` of a function with default parameter values, returns the default value of each of its parameters.
+	pub fn imported_parameter_defaults(&self, main_data: &MainData, filepath: &PathBuf) -> Option<ParameterDefaults> {
+		main_data.exported_parameter_defaults.get(&self.imported_global(main_data, filepath)?).cloned()
+	}
+
+	/// If this node is an `@import`, returns the absolute filepath of the file it imports from and the name of the global it imports.
+	fn imported_global(&self, main_data: &MainData, filepath: &PathBuf) -> Option<(PathBuf, Box<str>)> {
+		let arguments = match &self.variant {
+			AstNodeVariant::Keyword(Keyword::Import, arguments, None) if arguments.len() == 2 => arguments,
+			_ => return None,
+		};
+		let import_filepath = match &arguments[0].variant {
+			AstNodeVariant::String(import_filepath) | AstNodeVariant::Identifier(import_filepath) => &**import_filepath,
+			_ => return None,
+		};
+		let global_variable_name = match &arguments[1].variant {
+			AstNodeVariant::String(global_variable_name) | AstNodeVariant::Identifier(global_variable_name) => global_variable_name.clone(),
+			_ => return None,
+		};
+		let import_filepath = relative_filepath_to_absolute(main_data, filepath, import_filepath).ok()?;
+		Some((import_filepath, global_variable_name))
+	}
+
+	/// If this node is a `@section` keyword, returns the name of the linker section that the global it wraps should be placed in.
+	fn section_name(&self) -> Option<&str> {
+		match &self.variant {
+			AstNodeVariant::Keyword(Keyword::Section, arguments, _) => match arguments.first().map(|argument| &argument.variant) {
+				Some(AstNodeVariant::String(section_name)) => Some(section_name),
+				_ => None,
+			}
+			_ => None,
+		}
+	}
+
+	/// Returns if the value of this node is the result of a function call, meaning the call is in tail position if this node is a function body.
+	/// Calls to built-in functions that are not shadowed by a local variable are not function calls since they are lowered to other instructions.
+	fn is_tail_call(&self, main_data: &MainData, function_build_data: &FunctionBuildData) -> bool {
+		match &self.variant {
+			AstNodeVariant::FunctionCall(function, _) => match &function.variant {
+				AstNodeVariant::Identifier(name) => !main_data.builtins.contains_key(&**name) ||
+					function_build_data.block_stack.iter().any(|block_level| block_level.local_variables.contains_key(name)),
+				_ => true,
+			}
+			AstNodeVariant::Block(expressions, false) => expressions.last().is_some_and(|expression| expression.is_tail_call(main_data, function_build_data)),
+			_ => false,
+		}
+	}
+
+	/// Returns if this node or any of its children could give out the address of something on the stack, such as with `&` or `@stack`.
+	fn may_expose_stack_address(&self) -> bool {
+		match &self.variant {
+			AstNodeVariant::Operator(Operator::Normal(Operation::TakeReference), _) => true,
+			AstNodeVariant::Keyword(Keyword::Stack, _, _) => true,
+			AstNodeVariant::Operator(_, operands) => operands.iter().any(Self::may_expose_stack_address),
+			AstNodeVariant::Block(expressions, _) => expressions.iter().any(Self::may_expose_stack_address),
+			AstNodeVariant::FunctionCall(function, arguments) =>
+				function.may_expose_stack_address() || arguments.iter().any(Self::may_expose_stack_address),
+			AstNodeVariant::Keyword(_, arguments, child) =>
+				arguments.iter().any(Self::may_expose_stack_address) || child.as_ref().is_some_and(|child| child.may_expose_stack_address()),
+			AstNodeVariant::MemberAccess(accessed, _) => accessed.may_expose_stack_address(),
+			AstNodeVariant::ComparisonChain(_, operands) => operands.iter().any(Self::may_expose_stack_address),
+			AstNodeVariant::Range(range_start, range_end) => range_start.may_expose_stack_address() || range_end.may_expose_stack_address(),
+			AstNodeVariant::FunctionDefinition(..) | AstNodeVariant::Constant(..) | AstNodeVariant::Identifier(..) | AstNodeVariant::String(..) => false,
+		}
+	}
+
+	/// Adds the name of each variable that this node or its children take the address of with `&` to `referenced_names`.
+	pub fn add_referenced_names(&self, referenced_names: &mut HashSet<Box<str>>) {
+		match &self.variant {
+			AstNodeVariant::Operator(Operator::Normal(Operation::TakeReference), operands) => match &operands[0].variant {
+				AstNodeVariant::Identifier(name) => {
+					referenced_names.insert(name.clone());
+				}
+				_ => operands[0].add_referenced_names(referenced_names),
+			}
+			AstNodeVariant::Operator(_, operands) | AstNodeVariant::Block(operands, _) | AstNodeVariant::ComparisonChain(_, operands) =>
+				operands.iter().for_each(|operand| operand.add_referenced_names(referenced_names)),
+			AstNodeVariant::FunctionCall(function, arguments) => {
+				function.add_referenced_names(referenced_names);
+				arguments.iter().for_each(|argument| argument.add_referenced_names(referenced_names));
+			}
+			AstNodeVariant::Keyword(_, arguments, child) => {
+				arguments.iter().for_each(|argument| argument.add_referenced_names(referenced_names));
+				if let Some(child) = child {
+					child.add_referenced_names(referenced_names);
+				}
+			}
+			AstNodeVariant::FunctionDefinition(parameters, body) => {
+				parameters.iter().for_each(|parameter| parameter.add_referenced_names(referenced_names));
+				body.add_referenced_names(referenced_names);
+			}
+			AstNodeVariant::MemberAccess(accessed, _) => accessed.add_referenced_names(referenced_names),
+			AstNodeVariant::Range(range_start, range_end) => {
+				range_start.add_referenced_names(referenced_names);
+				range_end.add_referenced_names(referenced_names);
+			}
+			AstNodeVariant::Constant(..) | AstNodeVariant::Identifier(..) | AstNodeVariant::String(..) => {}
+		}
+	}
+
+	/// Marks each local variable in `local_variables` that is assigned to in this expression as not having a known value.
+	/// Function definitions are not searched since their local variables are separate.
+	fn forget_assigned_variables(&self, local_variables: &mut [HashMap<Box<str>, Option<u64>>]) {
+		match &self.variant {
+			AstNodeVariant::Operator(operator, operands) => {
+				let is_assignment = matches!(
+					operator,
+					Operator::Assignment | Operator::Augmented(..) | Operator::LValueAssignment |
+					Operator::Normal(Operation::PrefixIntegerIncrement | Operation::SuffixIntegerIncrement |
+					Operation::PrefixIntegerDecrement | Operation::SuffixIntegerDecrement)
+				);
+				if let (true, AstNodeVariant::Identifier(name)) = (is_assignment, &operands[0].variant) {
+					if let Some(variable) = local_variables.iter_mut().rev().find_map(|local_variable_level| local_variable_level.get_mut(name)) {
+						*variable = None;
+					}
+				}
+				operands.iter().for_each(|operand| operand.forget_assigned_variables(local_variables));
+			}
+			AstNodeVariant::Block(expressions, _) | AstNodeVariant::ComparisonChain(_, expressions) =>
+				expressions.iter().for_each(|expression| expression.forget_assigned_variables(local_variables)),
+			AstNodeVariant::FunctionCall(function, arguments) => {
+				function.forget_assigned_variables(local_variables);
+				arguments.iter().for_each(|argument| argument.forget_assigned_variables(local_variables));
+			}
+			AstNodeVariant::Keyword(_, arguments, child) => {
+				arguments.iter().for_each(|argument| argument.forget_assigned_variables(local_variables));
+				if let Some(child) = child {
+					child.forget_assigned_variables(local_variables);
+				}
+			}
+			AstNodeVariant::MemberAccess(accessed, _) => accessed.forget_assigned_variables(local_variables),
+			AstNodeVariant::Range(range_start, range_end) => {
+				range_start.forget_assigned_variables(local_variables);
+				range_end.forget_assigned_variables(local_variables);
+			}
+			AstNodeVariant::FunctionDefinition(..) | AstNodeVariant::Constant(..) | AstNodeVariant::Identifier(..) | AstNodeVariant::String(..) => {}
+		}
+	}
+
+	/// Get the span of the part of this expression that yields a stack address, such as `&a`, `@stack(8, 4)` or `a + 8` if `a` is in `stack_variables`.
+	/// Any identifier that is not in `global_names` is a local variable.
+	fn stack_address_span(&self, global_names: &HashSet<Box<str>>, stack_variables: &HashSet<Box<str>>) -> Option<Span> {
+		match &self.variant {
+			AstNodeVariant::Operator(Operator::Normal(Operation::TakeReference), operands) => match &operands[0].variant {
+				AstNodeVariant::Identifier(name) if !global_names.contains(name) => Some(self.span()),
+				_ => None,
+			}
+			AstNodeVariant::Keyword(Keyword::Stack, _, _) => Some(self.span()),
+			AstNodeVariant::Identifier(name) if stack_variables.contains(name) => Some(self.span()),
+			AstNodeVariant::Operator(Operator::Assignment, operands) => operands[1].stack_address_span(global_names, stack_variables),
+			// An offset from a stack address is still a stack address
+			AstNodeVariant::Operator(Operator::Normal(Operation::IntegerAdd), operands) => operands[0].stack_address_span(global_names, stack_variables)
+				.or_else(|| operands[1].stack_address_span(global_names, stack_variables)),
+			AstNodeVariant::Operator(Operator::Normal(Operation::IntegerSubtract), operands) => operands[0].stack_address_span(global_names, stack_variables),
+			AstNodeVariant::Operator(Operator::Normal(Operation::ShortCircuitTernary | Operation::NotShortCircuitTernary), operands) =>
+				operands[1].stack_address_span(global_names, stack_variables).or_else(|| operands[2].stack_address_span(global_names, stack_variables)),
+			AstNodeVariant::Block(expressions, false) => expressions.last()?.stack_address_span(global_names, stack_variables),
+			_ => None,
+		}
+	}
+
+	/// Add the local variables that are assigned a stack address anywhere in this expression to `stack_variables`, nested functions have their own local variables so are skipped.
+	fn collect_stack_variables(&self, global_names: &HashSet<Box<str>>, stack_variables: &mut HashSet<Box<str>>) {
+		if let AstNodeVariant::FunctionDefinition(..) = &self.variant {
+			return;
+		}
+		for child in self.children() {
+			child.collect_stack_variables(global_names, stack_variables);
+		}
+		if let AstNodeVariant::Operator(Operator::Assignment, operands) = &self.variant {
+			if let (AstNodeVariant::Identifier(name), Some(_)) = (&operands[0].variant, operands[1].stack_address_span(global_names, stack_variables)) {
+				stack_variables.insert(name.clone());
+			}
+		}
+	}
+
+	/// Get the span of where this function body returns a stack address, which will be dangling once the function returns.
+	fn returned_stack_address_span(&self, global_names: &HashSet<Box<str>>) -> Option<Span> {
+		let mut stack_variables = HashSet::new();
+		self.collect_stack_variables(global_names, &mut stack_variables);
+		self.stack_address_span(global_names, &stack_variables)
+	}
+
+	/// Gets the name of a function parameter and its default value if it has one, parameters with a default are written as `name = value`.
+	fn parameter_name_and_default(&self) -> Result<(&str, Option<&AstNode>), (Error, Span)> {
+		match &self.variant {
+			AstNodeVariant::Identifier(name) => Ok((name, None)),
+			AstNodeVariant::Operator(Operator::Assignment, operands) => match &operands[0].variant {
+				AstNodeVariant::Identifier(name) => Ok((name, Some(&operands[1]))),
+				_ => Err((Error::ExpectedIdentifier, operands[0].span())),
+			}
+			_ => Err((Error::ExpectedIdentifier, self.span())),
+		}
+	}
+
+	/// Returns the default value of each parameter of a function, `None` for parameters without a default.
+	/// Defaults are const evaluated with the function so a default that is not a constant is an error.
+	pub fn parameter_defaults(&self) -> Result<ParameterDefaults, (Error, Span)> {
+		match &self.variant {
+			AstNodeVariant::FunctionDefinition(parameters, _) => parameters.iter()
+				.map(|parameter| match parameter.parameter_name_and_default()? {
+					(_, None) => Ok(None),
+					(_, Some(AstNode { variant: AstNodeVariant::Constant(value), .. })) => Ok(Some(*value)),
+					(_, Some(default)) => Err((Error::DefaultParameterNotConstant, default.span())),
+				})
+				.collect(),
+			AstNodeVariant::Keyword(Keyword::EntryPoint | Keyword::Section, _, Some(child)) => child.parameter_defaults(),
+			_ => Ok(Box::new([])),
+		}
+	}
+
+	/// Print each metadata keyword in this node and the nodes below it with its position and the kind of node it applies to.
+	pub fn print_metadata(&self) {
+		if let AstNodeVariant::Keyword(keyword, arguments, child) = &self.variant {
+			if keyword.is_metadata() {
+				let target = match child {
+					Some(child) => format!("{:?}", AstNodeVariantDiscriminants::from(&child.variant)),
+					None => format!("{} arguments", arguments.len()),
+				};
+				println!("@{} at {}:{} -> {target}", keyword.get_symbol(), self.start.0, self.start.1);
+			}
+		}
+		for child in self.children() {
+			child.print_metadata();
+		}
+	}
+
+	/// Get the nodes directly below this node in the tree.
+	pub fn children(&self) -> Vec<&AstNode> {
+		match &self.variant {
+			AstNodeVariant::Constant(..) | AstNodeVariant::Identifier(..) | AstNodeVariant::String(..) => Vec::new(),
+			AstNodeVariant::Operator(_, operands) | AstNodeVariant::ComparisonChain(_, operands) => operands.iter().collect(),
+			AstNodeVariant::Block(expressions, _) => expressions.iter().collect(),
+			AstNodeVariant::FunctionCall(function, arguments) => [&**function].into_iter().chain(arguments.iter()).collect(),
+			AstNodeVariant::Keyword(_, arguments, child) => arguments.iter().chain(child.as_deref()).collect(),
+			AstNodeVariant::FunctionDefinition(parameters, body) => parameters.iter().chain([&**body]).collect(),
+			AstNodeVariant::MemberAccess(accessed, _) => vec![&**accessed],
+			AstNodeVariant::Range(range_start, range_end) => vec![&**range_start, &**range_end],
+		}
+	}
+
+	pub fn children_mut(&mut self) -> Vec<&mut AstNode> {
+		match &mut self.variant {
+			AstNodeVariant::Constant(..) | AstNodeVariant::Identifier(..) | AstNodeVariant::String(..) => Vec::new(),
+			AstNodeVariant::Operator(_, operands) | AstNodeVariant::ComparisonChain(_, operands) => operands.iter_mut().collect(),
+			AstNodeVariant::Block(expressions, _) => expressions.iter_mut().collect(),
+			AstNodeVariant::FunctionCall(function, arguments) => [&mut **function].into_iter().chain(arguments.iter_mut()).collect(),
+			AstNodeVariant::Keyword(_, arguments, child) => arguments.iter_mut().chain(child.as_deref_mut()).collect(),
+			AstNodeVariant::FunctionDefinition(parameters, body) => parameters.iter_mut().chain([&mut **body]).collect(),
+			AstNodeVariant::MemberAccess(accessed, _) => vec![&mut **accessed],
+			AstNodeVariant::Range(range_start, range_end) => vec![&mut **range_start, &mut **range_end],
+		}
+	}
+
+	/// Replace binary operators that have an overload function in `overloads` with a call to that function, such as `a + b` with `__add(a, b)`.
+	pub fn desugar_operator_overloads(&mut self, overloads: &HashSet<Box<str>>) {
+		for child in self.children_mut() {
+			child.desugar_operator_overloads(overloads);
+		}
+		let overload_name = match &self.variant {
+			AstNodeVariant::Operator(Operator::Normal(operation), operands) if operands.len() == 2 => match operation.overload_function_name() {
+				Some(overload_name) if overloads.contains(overload_name) => overload_name,
+				_ => return,
+			}
+			_ => return,
+		};
+		let AstNodeVariant::Operator(_, operands) = replace(&mut self.variant, AstNodeVariant::Constant(0)) else {
+			unreachable!()
+		};
+		let function = AstNode { variant: AstNodeVariant::Identifier(overload_name.into()), start: self.start, end: self.end };
+		self.variant = AstNodeVariant::FunctionCall(Box::new(function), operands);
+	}
+
+	/// Returns if the expression is a block that does not yield a value.
+	pub fn is_void(&self) -> bool {
+		match &self.variant {
+			AstNodeVariant::Block(block_expressions, is_result_undefined) => match (is_result_undefined, block_expressions.last()) {
+				(true, _) | (false, None) => true,
+				(false, Some(last_expression)) => last_expression.is_void(),
+			}
+			_ => false,
+		}
+	}
+
+	/// Get the span of a path through the expression that does not yield a value, such as the else case of `a ? { b } : {}`.
+	pub fn void_path_span(&self) -> Option<Span> {
+		if self.is_void() {
+			return Some(self.span());
+		}
+		match &self.variant {
+			AstNodeVariant::Block(block_expressions, _) => block_expressions.last()?.void_path_span(),
+			AstNodeVariant::Operator(Operator::Normal(Operation::ShortCircuitTernary | Operation::NotShortCircuitTernary), operands) =>
+				operands[1].void_path_span().or_else(|| operands[2].void_path_span()),
+			_ => None,
+		}
+	}
+
+	/// Get how many parameters this node has if it is a function definition or a function linked with `@link`.
+	pub fn parameter_count(&self) -> Option<usize> {
+		match &self.variant {
+			AstNodeVariant::FunctionDefinition(parameters, _) => Some(parameters.len()),
+			AstNodeVariant::Keyword(Keyword::EntryPoint | Keyword::Section, _, Some(child)) => child.parameter_count(),
+			// The arguments of `@link` are the library, the function name and the return width, followed by the parameter widths
+			AstNodeVariant::Keyword(Keyword::Link, arguments, _) => Some(arguments.len().saturating_sub(3)),
+			_ => None,
+		}
+	}
+
 	/// Returns if the expression can be built into a function.
 	pub fn is_function(&self) -> bool {
 		match &self.variant {
 			AstNodeVariant::FunctionDefinition(..) => true,
 			AstNodeVariant::Keyword(keyword, _arguments, child) => match keyword {
-				Keyword::EntryPoint => child.as_ref().unwrap().is_function(),
+				Keyword::EntryPoint | Keyword::Section => child.as_ref().unwrap().is_function(),
 				_ => false,
 			}
 			_ => false,
@@ -1338,12 +2302,13 @@ impl AstNode {
 	}
 
 	/// Get a int/void type form a byte width.
-	pub fn type_from_width<'a>(&'a self, main_data: &'a MainData) -> Result<(Type<'a>, bool), (Error, (NonZeroUsize, NonZeroUsize))> {
+	pub fn type_from_width<'a>(&'a self, main_data: &'a MainData) -> Result<(Type<'a>, bool), (Error, Span)> {
 		let Self {
 			start,
-			end: _,
+			end,
 			variant,
 		} = self;
+		let span = (*start, *end);
 		Ok(match variant {
 			AstNodeVariant::Constant(value) => {
 				let is_negative = (main_data.sign_bit_mask & *value) != 0;
@@ -1358,10 +2323,32 @@ impl AstNode {
 					4 => main_data.llvm_context.int_32_type(),
 					8 => main_data.llvm_context.int_64_type(),
 					16 => main_data.llvm_context.int_128_type(),
-					_ => return Err((Error::InvalidTypeWidth, *start)),
+					_ => return Err((Error::InvalidTypeWidth, span)),
 				}, is_negative)
 			}
-			_ => return Err((Error::ConstValueRequired, *start)),
+			_ => return Err((Error::ConstValueRequired, span)),
+		})
+	}
+
+	/// Get the type of a parameter or return value of a linked function from its width, if `--abi-check` is set the type is rejected if the target C ABI has no integer of that width.
+	pub fn link_type_from_width<'a>(&'a self, main_data: &'a MainData) -> Result<(Type<'a>, bool), (Error, Span)> {
+		let (result_type, is_signed) = self.type_from_width(main_data)?;
+		if main_data.abi_check && !result_type.is_void() {
+			// C ABIs only have integers up to twice the pointer width, such as __int128 on 64-bit targets
+			let bit_width = result_type.size_in_bits(main_data.llvm_data_layout) as u32;
+			if bit_width > main_data.int_bit_width as u32 * 2 {
+				return Err((Error::UnsupportedAbiType(bit_width), self.span()));
+			}
+		}
+		Ok((result_type, is_signed))
+	}
+
+	/// Get the size in bytes of the int/void type with the byte width this node const evaluated to, as used by the target data layout.
+	pub fn size_of_width(&self, main_data: &MainData) -> Result<u64, (Error, Span)> {
+		let (width_type, _) = self.type_from_width(main_data)?;
+		Ok(match width_type.is_void() {
+			true => 0,
+			false => (width_type.size_in_bits(main_data.llvm_data_layout) / 8) as u64,
 		})
 	}
 
@@ -1375,13 +2362,15 @@ impl AstNode {
 		is_link_function: bool,
 		is_l_value: bool,
 		is_standard_library: bool,
-	) -> Result<(), (Error, (NonZeroUsize, NonZeroUsize))> {
+	) -> Result<(), (Error, Span)> {
 		// Unpack
 		let Self {
 			start,
 			end,
 			variant,
 		} = self;
+		let span = (*start, *end);
+		main_data.check_deadline(span)?;
 		// Action depends on variant
 		match variant {
 			AstNodeVariant::Operator(operator, operands) => {
@@ -1393,14 +2382,28 @@ impl AstNode {
 						operands[1]
 							.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, is_link_function, false, is_standard_library)?;
 					}
-					Operator::Augmented(..) => return Err((Error::FeatureNotYetImplemented("Augmented assignments".into()), *start)),
-					Operator::LValueAssignment => return Err((Error::FeatureNotYetImplemented("Augmented assignments".into()), *start)),
+					Operator::Augmented(..) => {
+						// The l-value is left as is but the r-value can be folded
+						operands[0]
+							.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, is_link_function, true, is_standard_library)?;
+						operands[1]
+							.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, is_link_function, false, is_standard_library)?;
+						// The value of the variable being assigned to is no longer known
+						if let AstNodeVariant::Identifier(name) = &operands[0].variant {
+							for local_variable_level in local_variables.iter_mut().rev() {
+								if let Some(variable) = local_variable_level.get_mut(name) {
+									*variable = None;
+									break;
+								}
+							}
+						}
+					}
+					Operator::LValueAssignment => return Err((Error::FeatureNotYetImplemented("Augmented assignments".into()), span)),
 					Operator::Normal(operation) => match operation {
 						Operation::BitwiseAnd | Operation::BitwiseOr | Operation::BitwiseXor | Operation::FloatAdd | Operation::FloatDivide |
 						Operation::FloatMultiply | Operation::FloatSubtract | Operation::FloatNegate | Operation::FloatTruncatedModulo |
 						Operation::IntegerAdd | Operation::IntegerMultiply | Operation::IntegerNegate | Operation::IntegerSubtract |
 						Operation::LogicalNotShortCircuitAnd | Operation::LogicalNotShortCircuitOr | Operation::LogicalXor |
-						Operation::LogicalShortCircuitAnd | Operation::LogicalShortCircuitOr |
 						Operation::SignedDivide | Operation::SignedTruncatedModulo | Operation::UnsignedDivide | Operation::UnsignedModulo |
 						Operation::Dereference | Operation::BitwiseNot | Operation::LogicalNot |
 						Operation::IntegerEqualTo | Operation::IntegerNotEqualTo  | Operation::UnsignedLessThanOrEqualTo | Operation::SignedLessThanOrEqualTo |
@@ -1424,10 +2427,24 @@ impl AstNode {
 								)?;
 							}
 						}
+						// The right operand is only evaluated depending on the value of the left operand,
+						// so variables that are assigned to in it no longer have a known value after the operator
+						Operation::LogicalShortCircuitAnd | Operation::LogicalShortCircuitOr => {
+							for operand in operands.iter_mut() {
+								operand.const_evaluate(
+									main_data, const_evaluated_globals, variable_dependencies, local_variables, is_link_function, false, is_standard_library
+								)?;
+							}
+							operands[1].forget_assigned_variables(local_variables);
+						}
 						Operation::ShortCircuitTernary | Operation::NotShortCircuitTernary => {
 							operands[0].const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, is_link_function, false, is_standard_library)?;
 							operands[1].const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, is_link_function, is_l_value, is_standard_library)?;
 							operands[2].const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, is_link_function, is_l_value, is_standard_library)?;
+							// Only one of the branches of a short circuit ternary is evaluated
+							if let Operation::ShortCircuitTernary = operation {
+								operands[1..].iter().for_each(|operand| operand.forget_assigned_variables(local_variables));
+							}
 						}
 					}
 				}
@@ -1437,9 +2454,15 @@ impl AstNode {
 						// If we have a unary operator or a constant
 						Operation::IntegerNegate | Operation::BitwiseNot | Operation::LogicalNot
 						=> if let AstNode { variant: AstNodeVariant::Constant(value), .. } = operands[0] {
+							if let (true, Operation::IntegerNegate) = (main_data.overflow_check_const, &operation) {
+								if main_data.signed_overflows(-(main_data.value_to_signed(value) as i128)) {
+									return Err((Error::ConstantOverflow, span));
+								}
+							}
 							let new_value = match operation {
 								Operation::IntegerNegate => ((value ^ main_data.int_max_value).wrapping_add(1)) & main_data.int_max_value,
-								Operation::BitwiseNot | Operation::LogicalNot => value ^ main_data.int_max_value,
+								Operation::BitwiseNot => value ^ main_data.int_max_value,
+								Operation::LogicalNot => (value == 0) as u64,
 								_ => unreachable!(),
 							};
 							*self = AstNode { variant: AstNodeVariant::Constant(new_value), start: *start, end: *end };
@@ -1455,12 +2478,12 @@ impl AstNode {
 							) = (&operands[0], &operands[1]) {
 								let new_value = match operation {
 									Operation::UnsignedModulo => left_value.checked_rem(*right_value)
-										.ok_or_else(|| (Error::ModuloByZero, *start))? & main_data.int_max_value,
+										.ok_or_else(|| (Error::ModuloByZero, span))? & main_data.int_max_value,
 									Operation::SignedTruncatedModulo => {
 										let left_value = main_data.value_to_signed(*left_value);
 										let right_value = main_data.value_to_signed(*right_value);
 										if right_value == 0 {
-											return Err((Error::ModuloByZero, *start));
+											return Err((Error::ModuloByZero, span));
 										}
 										main_data.signed_to_value(left_value.wrapping_rem(right_value))
 									}
@@ -1495,10 +2518,32 @@ impl AstNode {
 								*self = AstNode { variant: AstNodeVariant::Constant(new_value), start: *start, end: *end };
 							}
 						}
+						// Float operations are folded on the bits of the operands, results that are NaN are left to runtime as their bits may differ
+						Operation::FloatAdd | Operation::FloatSubtract | Operation::FloatMultiply | Operation::FloatDivide | Operation::FloatTruncatedModulo => {
+							if let (
+								AstNode { variant: AstNodeVariant::Constant(left_value), .. },
+								AstNode { variant: AstNodeVariant::Constant(right_value), .. }
+							) = (&operands[0], &operands[1]) {
+								if let (Some(left_value), Some(right_value)) = (main_data.value_to_float(*left_value), main_data.value_to_float(*right_value)) {
+									let new_float_value = match operation {
+										Operation::FloatAdd => left_value + right_value,
+										Operation::FloatSubtract => left_value - right_value,
+										Operation::FloatMultiply => left_value * right_value,
+										Operation::FloatDivide => left_value / right_value,
+										Operation::FloatTruncatedModulo => left_value % right_value,
+										_ => unreachable!(),
+									};
+									if !new_float_value.is_nan() {
+										let new_value = main_data.float_to_value(new_float_value).unwrap();
+										*self = AstNode { variant: AstNodeVariant::Constant(new_value), start: *start, end: *end };
+									}
+								}
+							}
+						}
 						// Make sure constant null pointers are not dereferenced
 						Operation::Dereference => {
 							if let AstNode { variant: AstNodeVariant::Constant(0), .. } = operands[0] {
-								return Err((Error::NullPointerDereference, *start));
+								return Err((Error::NullPointerDereference, span));
 							}
 						}
 						Operation::Read => {
@@ -1604,6 +2649,9 @@ impl AstNode {
 									*self = AstNode { variant: operands[1].variant.clone(), start: *start, end: *end };
 								}
 								else if let AstNode { variant: AstNodeVariant::Constant(right_value), .. } = operands[1] {
+									if main_data.overflow_check_const && main_data.signed_overflows(main_data.value_to_signed(left_value) as i128 + main_data.value_to_signed(right_value) as i128) {
+										return Err((Error::ConstantOverflow, span));
+									}
 									let new_value = left_value.wrapping_add(right_value) & main_data.int_max_value;
 									*self = AstNode { variant: AstNodeVariant::Constant(new_value), start: *start, end: *end };
 								}
@@ -1628,6 +2676,9 @@ impl AstNode {
 									}
 								}
 								else if let AstNode { variant: AstNodeVariant::Constant(right_value), .. } = operands[1] {
+									if main_data.overflow_check_const && main_data.signed_overflows(main_data.value_to_signed(left_value) as i128 - main_data.value_to_signed(right_value) as i128) {
+										return Err((Error::ConstantOverflow, span));
+									}
 									let new_value = left_value.wrapping_sub(right_value) & main_data.int_max_value;
 									*self = AstNode { variant: AstNodeVariant::Constant(new_value), start: *start, end: *end };
 								}
@@ -1645,6 +2696,9 @@ impl AstNode {
 									*self = AstNode { variant: operands[1].variant.clone(), start: *start, end: *end };
 								}
 								else if let AstNode { variant: AstNodeVariant::Constant(right_value), .. } = operands[1] {
+									if main_data.overflow_check_const && main_data.signed_overflows(main_data.value_to_signed(left_value) as i128 * main_data.value_to_signed(right_value) as i128) {
+										return Err((Error::ConstantOverflow, span));
+									}
 									let new_value = left_value.wrapping_mul(right_value) & main_data.int_max_value;
 									*self = AstNode { variant: AstNodeVariant::Constant(new_value), start: *start, end: *end };
 								}
@@ -1658,14 +2712,14 @@ impl AstNode {
 						// x / 1 = x
 						// x / 0 = Error
 						Operation::UnsignedDivide => {
-							if let AstNode { variant: AstNodeVariant::Constant(right_value), .. } = operands[0] {
+							if let AstNode { variant: AstNodeVariant::Constant(right_value), .. } = operands[1] {
 								if right_value == 1 {
 									*self = AstNode { variant: operands[0].variant.clone(), start: *start, end: *end };
 								}
 								else if right_value == 0 {
-									return Err((Error::DivisionByZero, *start));
+									return Err((Error::DivisionByZero, span));
 								}
-								else if let AstNode { variant: AstNodeVariant::Constant(left_value), .. } = operands[1] {
+								else if let AstNode { variant: AstNodeVariant::Constant(left_value), .. } = operands[0] {
 									let new_value = left_value.wrapping_div(right_value) & main_data.int_max_value;
 									*self = AstNode { variant: AstNodeVariant::Constant(new_value), start: *start, end: *end };
 								}
@@ -1674,16 +2728,19 @@ impl AstNode {
 						// x / 1 = x
 						// x / 0 = Error
 						Operation::SignedDivide => {
-							if let AstNode { variant: AstNodeVariant::Constant(right_value), .. } = operands[0] {
+							if let AstNode { variant: AstNodeVariant::Constant(right_value), .. } = operands[1] {
 								if right_value == 1 {
 									*self = AstNode { variant: operands[0].variant.clone(), start: *start, end: *end };
 								}
 								else if right_value == 0 {
-									return Err((Error::DivisionByZero, *start));
+									return Err((Error::DivisionByZero, span));
 								}
-								else if let AstNode { variant: AstNodeVariant::Constant(left_value), .. } = operands[1] {
+								else if let AstNode { variant: AstNodeVariant::Constant(left_value), .. } = operands[0] {
 									let left_value = main_data.value_to_signed(left_value);
 									let right_value = main_data.value_to_signed(right_value);
+									if main_data.overflow_check_const && main_data.signed_overflows(left_value as i128 / right_value as i128) {
+										return Err((Error::ConstantOverflow, span));
+									}
 									let new_value = main_data.signed_to_value(left_value.wrapping_div(right_value)) & main_data.int_max_value;
 									*self = AstNode { variant: AstNodeVariant::Constant(new_value), start: *start, end: *end };
 								}
@@ -1803,11 +2860,17 @@ impl AstNode {
 			AstNodeVariant::FunctionDefinition(parameters, body) => {
 				let mut inner_local_variables = vec![HashMap::new()];
 				if !is_link_function {
-					for parameter in parameters.iter() {
-						let name = match &parameter.variant {
-							AstNodeVariant::Identifier(name) => name,
-							_ => return Err((Error::ExpectedIdentifier, parameter.start)),
-						}.clone();
+					for parameter in parameters.iter_mut() {
+						// Defaults are evaluated where the function is defined and must fold to a constant
+						if let AstNodeVariant::Operator(Operator::Assignment, operands) = &mut parameter.variant {
+							operands[1].const_evaluate(
+								main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library
+							)?;
+							if !matches!(operands[1].variant, AstNodeVariant::Constant(..)) {
+								return Err((Error::DefaultParameterNotConstant, operands[1].span()));
+							}
+						}
+						let name = parameter.parameter_name_and_default()?.0.into();
 						inner_local_variables[0].insert(name, None);
 					}
 				}
@@ -1825,7 +2888,7 @@ impl AstNode {
 			AstNodeVariant::Block(sub_expressions, ..) => {
 				local_variables.push(HashMap::new());
 				if is_l_value {
-					return Err((Error::FeatureNotYetImplemented("L-value blocks".into()), *start));
+					return Err((Error::FeatureNotYetImplemented("L-value blocks".into()), span));
 				}
 				for sub_expression in sub_expressions {
 					sub_expression
@@ -1844,7 +2907,7 @@ impl AstNode {
 			}
 			AstNodeVariant::Keyword(keyword, arguments, child) => {
 				match keyword {
-					Keyword::Write | Keyword::Stack | Keyword::Loop | Keyword::Import | Keyword::Link => {
+					Keyword::Write | Keyword::Stack | Keyword::Import | Keyword::Link | Keyword::RuntimeAssert => {
 						for argument in arguments.iter_mut() {
 							argument.const_evaluate(
 								main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library
@@ -1852,7 +2915,7 @@ impl AstNode {
 						}
 						if *keyword == Keyword::Link {
 							if arguments.len() < 2 {
-								return Err((Error::InvalidBuiltInFunctionArgumentCount, *start));
+								return Err((Error::InvalidBuiltInFunctionArgumentCount, span));
 							}
 							// Get arguments
 							let library_path = &arguments[1];
@@ -1860,24 +2923,74 @@ impl AstNode {
 							let library_path = match &library_path.variant {
 								AstNodeVariant::String(library_path) => &**library_path,
 								AstNodeVariant::Identifier(library_path) => &**library_path,
-								_ => return Err((Error::ConstValueRequired, library_path.start)),
+								_ => return Err((Error::ConstValueRequired, library_path.span())),
 							};
 							// Add to list of libraries to link to
 							main_data.libraries_to_link_to.insert(library_path.into());
 						}
 					}
-					Keyword::EntryPoint => child.as_mut().unwrap()
+					Keyword::EntryPoint | Keyword::Section => child.as_mut().unwrap()
 						.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, is_link_function, is_l_value, is_standard_library)?,
-					Keyword::Break | Keyword::Continue => if !arguments.is_empty() {
-						return Err((Error::FeatureNotYetImplemented("Arguments for @break and @continue".into()), *start));
+					// The label of a @break is not a variable so only the result value is evaluated
+					Keyword::Break => for argument in arguments.iter_mut().skip(1) {
+						argument.const_evaluate(
+							main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library
+						)?;
+					}
+					Keyword::Continue => if !arguments.is_empty() {
+						return Err((Error::FeatureNotYetImplemented("Arguments for @continue".into()), span));
+					}
+					// The child of a loop is not const evaluated since it runs an unknown amount of times,
+					// variables that are assigned to in it no longer have a known value after the loop
+					Keyword::Loop => child.iter().for_each(|child| child.forget_assigned_variables(local_variables)),
+					// Like a loop, the child is not const evaluated since a @break can skip the rest of it
+					Keyword::Label => child.iter().for_each(|child| child.forget_assigned_variables(local_variables)),
+					// The condition is evaluated after the body each time round the loop so neither can be const evaluated
+					Keyword::DoWhile => {
+						arguments.iter().for_each(|argument| argument.forget_assigned_variables(local_variables));
+						child.iter().for_each(|child| child.forget_assigned_variables(local_variables));
+					}
+					// The range is evaluated once before the loop, the body is not const evaluated for the same reason as a @do_while
+					Keyword::For => {
+						arguments[1]
+							.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library)?;
+						child.iter().for_each(|child| child.forget_assigned_variables(local_variables));
 					}
 					Keyword::Export => unreachable!(),
+					// A passing assertion is replaced with a 0
+					Keyword::Assert => {
+						arguments[0].const_evaluate(
+							main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library
+						)?;
+						match arguments[0].variant {
+							AstNodeVariant::Constant(0) => return Err((Error::StaticAssertionFailed, span)),
+							AstNodeVariant::Constant(..) => {}
+							_ => return Err((Error::StaticAssertionNotConstant, arguments[0].span())),
+						}
+						self.variant = AstNodeVariant::Constant(0);
+					}
+					// A @sizeof is replaced with the byte size of the type with the width given
+					Keyword::SizeOf => {
+						arguments[0].const_evaluate(
+							main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library
+						)?;
+						self.variant = AstNodeVariant::Constant(arguments[0].size_of_width(main_data)?);
+					}
+					Keyword::Const => {
+						let child = child.as_mut().unwrap();
+						child.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library)?;
+						let value = match child.variant {
+							AstNodeVariant::Constant(value) => value,
+							_ => return Err((Error::NotAConstantExpression, child.span())),
+						};
+						self.variant = AstNodeVariant::Constant(value);
+					}
 					Keyword::SystemConstant => {
 						if !is_standard_library {
-							return Err((Error::OnlyUsableInStandardLibrary, *start));
+							return Err((Error::OnlyUsableInStandardLibrary, span));
 						}
 						match child {
-							Some(child) => return Err((Error::ShouldNotHaveChild, child.start)),
+							Some(child) => return Err((Error::ShouldNotHaveChild, child.span())),
 							None => {}
 						}
 						for argument in arguments.iter_mut() {
@@ -1886,16 +2999,16 @@ impl AstNode {
 							)?;
 						}
 						if arguments.len() != 1 {
-							return Err((Error::InvalidBuiltInFunctionArgumentCount, *start));
+							return Err((Error::InvalidBuiltInFunctionArgumentCount, span));
 						}
 						let constant_id = match arguments[0] {
 							AstNode { variant: AstNodeVariant::Constant(value), .. } => value,
-							_ => return Err((Error::ConstValueRequired, arguments[0].start)),
+							_ => return Err((Error::ConstValueRequired, arguments[0].span())),
 						};
 						let constant_value = match constant_id {
 							0 => (main_data.int_bit_width / 8) as u64, // T_WORD
 							1 => main_data.operating_system as u64, // OPERATING_SYSTEM
-							_ => return Err((Error::InvalidSystemConstant, arguments[0].start)),
+							_ => return Err((Error::InvalidSystemConstant, arguments[0].span())),
 						};
 						self.variant = AstNodeVariant::Constant(constant_value);
 					}
@@ -1903,13 +3016,13 @@ impl AstNode {
 					//	// Get arguments
 					//	let library_path = match arguments.len() {
 					//		1 => &arguments[0],
-					//		_ => return Err((Error::InvalidBuiltInFunctionArgumentCount, self.start)),
+					//		_ => return Err((Error::InvalidBuiltInFunctionArgumentCount, self.span())),
 					//	};
 					//	// Get library path
 					//	let library_path = match &library_path.variant {
 					//		AstNodeVariant::String(library_path) => &**library_path,
 					//		AstNodeVariant::Identifier(library_path) => &**library_path,
-					//		_ => return Err((Error::ConstValueRequired, library_path.start)),
+					//		_ => return Err((Error::ConstValueRequired, library_path.span())),
 					//	};
 					//	main_data.libraries_to_link_to.push(library_path.into());
 					//	self.variant = AstNodeVariant::Constant(0);
@@ -1917,6 +3030,39 @@ impl AstNode {
 				}
 			}
 			AstNodeVariant::String(..) => {}
+			AstNodeVariant::MemberAccess(accessed, _) => accessed
+				.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library)?,
+			AstNodeVariant::Range(range_start, range_end) => {
+				range_start.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library)?;
+				range_end.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library)?;
+			}
+			// A chain of constants is folded by folding each of its comparisons
+			AstNodeVariant::ComparisonChain(operations, operands) => {
+				for operand in operands.iter_mut() {
+					operand.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library)?;
+				}
+				if !operands.iter().all(|operand| matches!(operand.variant, AstNodeVariant::Constant(..))) {
+					return Ok(());
+				}
+				let mut result = 1;
+				for (index, operation) in operations.iter().enumerate() {
+					let mut comparison = AstNode {
+						variant: AstNodeVariant::Operator(Operator::Normal(operation.clone()), [operands[index].clone(), operands[index + 1].clone()].into()),
+						start: *start,
+						end: *end,
+					};
+					comparison.const_evaluate(main_data, const_evaluated_globals, variable_dependencies, local_variables, false, false, is_standard_library)?;
+					match comparison.variant {
+						AstNodeVariant::Constant(0) => {
+							result = 0;
+							break;
+						}
+						AstNodeVariant::Constant(..) => {}
+						_ => return Ok(()),
+					}
+				}
+				self.variant = AstNodeVariant::Constant(result);
+			}
 			AstNodeVariant::Identifier(name) => 'a: {
 				if is_l_value {
 					for local_variable_level in local_variables.iter_mut().rev() {
@@ -1924,7 +3070,11 @@ impl AstNode {
 							break 'a;
 						}
 					}
-					let top_local_variable_level = local_variables.last_mut().unwrap();
+					// There are no local variables in global scope, such as for `&other` in `g = &other`, so the identifier is a global
+					let top_local_variable_level = match local_variables.last_mut() {
+						Some(top_local_variable_level) => top_local_variable_level,
+						None => break 'a,
+					};
 					top_local_variable_level.insert(name.clone(), None);
 				}
 				else {
@@ -1949,6 +3099,48 @@ impl AstNode {
 	}
 }
 
+/// Get the narrowest int type that can hold `value` as either an unsigned or a signed int, no wider than the int type.
+fn narrowest_int_type<'a>(main_data: &MainData<'a>, value: u64) -> Type<'a> {
+	let signed_value = main_data.value_to_signed(value);
+	let byte_width = [1, 2, 4, 8].into_iter()
+		.find(|byte_width| {
+			let bit_width = byte_width * 8;
+			bit_width >= main_data.int_bit_width as u32 || value < (1 << bit_width) || (signed_value < 0 && signed_value >= -(1 << (bit_width - 1)))
+		})
+		.unwrap();
+	match byte_width {
+		1 => main_data.llvm_context.int_8_type(),
+		2 => main_data.llvm_context.int_16_type(),
+		4 => main_data.llvm_context.int_32_type(),
+		_ => main_data.int_type,
+	}
+}
+
+/// Build a conversion from an integer to an `i1` that is true if the integer is not zero.
+fn build_int_to_bool<'a>(main_data: &MainData<'a>, llvm_builder: &Builder<'a, 'a>, value: &Value<'a, 'a>) -> Value<'a, 'a> {
+	value.build_compare(&main_data.int_type.const_int(0, false), Comparison::NotEqual, llvm_builder, "int_to_bool_temp")
+}
+
+/// Build a conversion from an `i1` to an integer that is 1 if true or 0 if false.
+fn build_bool_to_int<'a>(main_data: &MainData<'a>, llvm_builder: &Builder<'a, 'a>, value: &Value<'a, 'a>) -> Value<'a, 'a> {
+	value.build_zero_extend(llvm_builder, main_data.int_type, "bool_to_int_temp")
+}
+
+/// Build what happens when a runtime check fails depending on the trap mode, this terminates the current basic block.
+fn build_runtime_failure<'a>(main_data: &MainData<'a>, llvm_module: &'a Module, llvm_builder: &Builder<'a, 'a>) {
+	let function_name = match main_data.trap_mode {
+		TrapMode::Abort => "__bcz_panic",
+		TrapMode::Trap => "llvm.trap",
+	};
+	let function_type = main_data.llvm_context.void_type().function_type(&[], false);
+	let function = match llvm_module.get_function(function_name) {
+		Some(function) => function,
+		None => llvm_module.add_function(function_type, function_name),
+	};
+	function.build_call(&[], function_type, llvm_builder, "");
+	llvm_builder.build_unreachable();
+}
+
 /// Get a local or global variable.
 fn get_variable_by_name<'a, 'b>(
 	main_data: &MainData<'a>,
@@ -1960,7 +3152,10 @@ fn get_variable_by_name<'a, 'b>(
 	if let Some(function_build_data) = function_build_data {
 		for scope_level in function_build_data.block_stack.iter().rev() {
 			if let Some(variable) = scope_level.local_variables.get(name) {
-				return BuiltRValue::Value(variable.get_value(main_data, llvm_builder));
+				return match variable {
+					BuiltLValue::ArrayVariable(array, array_type) => BuiltRValue::Array(array.clone(), *array_type),
+					_ => BuiltRValue::Value(variable.get_value(main_data, llvm_builder)),
+				};
 			}
 		}
 	}
@@ -1968,4 +3163,24 @@ fn get_variable_by_name<'a, 'b>(
 		return built_global.clone();
 	}
 	BuiltRValue::Value(file_build_data.built_global_function_signatures[name].build_ptr_to_int(llvm_builder, main_data.int_type, "fn_ptr_to_int_temp"))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{collections::{HashMap, HashSet}, num::NonZeroUsize, path::PathBuf};
+
+	use crate::{error::Error, with_main_data};
+
+	use super::{AstNode, AstNodeVariant, Operation, Operator};
+
+	#[test]
+	fn operator_with_too_few_operands() {
+		let position = (NonZeroUsize::new(1).unwrap(), NonZeroUsize::new(1).unwrap());
+		let constant = AstNode { variant: AstNodeVariant::Constant(1), start: position, end: position };
+		let node = AstNode { variant: AstNodeVariant::Operator(Operator::Normal(Operation::IntegerAdd), [constant].into()), start: position, end: position };
+		let result = with_main_data(&["--check"], |main_data| node.get_variable_dependencies(
+			main_data, &PathBuf::from("main.bcz"), &mut HashMap::new(), &mut HashSet::new(), &mut Vec::new(), false
+		));
+		assert!(matches!(result, Err((Error::MalformedOperatorArity(2, 1), _))));
+	}
 }
\ No newline at end of file
@@ -1,10 +1,12 @@
-use crate::MainData;
-use llvm_nhb::{builder::Builder, value::Value};
+use crate::{error::Error, MainData};
+use llvm_nhb::{builder::Builder, types::Type, value::Value};
 
 #[derive(Clone, Debug)]
 pub enum BuiltLValue<'a> {
 	AllocaVariable(Value<'a, 'a>),
 	DereferencedPointer(Value<'a, 'a>),
+	/// A local variable that names a stack array of the given array type rather than holding its address.
+	ArrayVariable(Value<'a, 'a>, Type<'a>),
 }
 
 impl<'a> BuiltLValue<'a> {
@@ -14,6 +16,7 @@ impl<'a> BuiltLValue<'a> {
 				alloca_variable.build_load(main_data.int_type, llvm_builder, "alloca_read_temp"),
 			Self::DereferencedPointer(pointer) =>
 				pointer.build_load(main_data.int_type, llvm_builder, "alloca_read_temp"),
+			Self::ArrayVariable(array, _) => array.build_ptr_to_int(llvm_builder, main_data.int_type, "array_address_temp"),
 		}
 	}
 
@@ -21,13 +24,34 @@ impl<'a> BuiltLValue<'a> {
 		match self {
 			Self::AllocaVariable(alloca_variable) => alloca_variable.clone(),
 			Self::DereferencedPointer(pointer) => pointer.clone(),
+			Self::ArrayVariable(array, _) => array.clone(),
 		}
 	}
 
-	pub fn set_value(&self, _main_data: &MainData, llvm_builder: &Builder<'a, 'a>, value: &Value<'a, 'a>) -> Value {
-		match self {
-			Self::AllocaVariable(alloca_variable) => alloca_variable.build_store(value, llvm_builder),
-			Self::DereferencedPointer(pointer) => pointer.build_store(value, llvm_builder),
+	/// Assigns `value` to this l-value, an array variable can only be assigned an array of the same type which has all of its entries copied.
+	pub fn set_value(&self, main_data: &MainData<'a>, llvm_builder: &Builder<'a, 'a>, value: &BuiltRValue<'a>) -> Result<Value<'a, 'a>, Error> {
+		Ok(match (self, value) {
+			(Self::ArrayVariable(_, array_type), BuiltRValue::Array(source, source_type)) if array_type == source_type =>
+				self.copy_from(main_data, llvm_builder, &Self::ArrayVariable(source.clone(), *source_type), *array_type),
+			(Self::ArrayVariable(..), _) => return Err(Error::ArrayAssignmentMismatch),
+			(Self::AllocaVariable(alloca_variable), _) => alloca_variable.build_store(&value.get_value(main_data, llvm_builder), llvm_builder),
+			(Self::DereferencedPointer(pointer), _) => pointer.build_store(&value.get_value(main_data, llvm_builder), llvm_builder),
+		})
+	}
+
+	/// Copies a value of type `value_type` from `source` into this l-value, arrays and structs are copied with `llvm.memcpy` rather than a single load and store.
+	pub fn copy_from(&self, main_data: &MainData<'a>, llvm_builder: &Builder<'a, 'a>, source: &Self, value_type: Type<'a>) -> Value<'a, 'a> {
+		let destination_pointer = self.get_pointer(main_data, llvm_builder);
+		let source_pointer = source.get_pointer(main_data, llvm_builder);
+		match value_type.is_aggregate() {
+			true => {
+				let size = main_data.int_type.const_int(value_type.size_in_bits(main_data.llvm_data_layout).div_ceil(8), false);
+				destination_pointer.build_memcpy(&source_pointer, &size, llvm_builder)
+			}
+			false => {
+				let value = source_pointer.build_load(value_type, llvm_builder, "copy_temp");
+				destination_pointer.build_store(&value, llvm_builder)
+			}
 		}
 	}
 }
@@ -36,6 +60,10 @@ impl<'a> BuiltLValue<'a> {
 pub enum BuiltRValue<'a> {
 	Value(Value<'a, 'a>),
 	ImportedConstant(Value<'a, 'a>),
+	/// A stack array of the given array type, used as its address unless it is assigned to an array variable.
+	Array(Value<'a, 'a>, Type<'a>),
+	/// The float result of a float operation, only cast back to an int when it is read as one.
+	Float(Value<'a, 'a>),
 }
 
 impl<'a> BuiltRValue<'a> {
@@ -43,6 +71,16 @@ impl<'a> BuiltRValue<'a> {
 		match self {
 			Self::Value(value) => value.clone(),
 			Self::ImportedConstant(value) => value.build_load(main_data.int_type, llvm_builder, "global_constant_read_temp"),
+			Self::Array(array, _) => array.build_ptr_to_int(llvm_builder, main_data.int_type, "array_address_temp"),
+			Self::Float(float) => float.build_bit_cast(llvm_builder, main_data.int_type, "float_to_int_temp"),
+		}
+	}
+
+	/// Get the value as a float of type `float_type`, ints are reinterpreted as floats with the same bit pattern.
+	pub fn get_float_value(&self, main_data: &MainData<'a>, llvm_builder: &Builder<'a, 'a>, float_type: Type<'a>) -> Value<'a, 'a> {
+		match self {
+			Self::Float(float) => float.clone(),
+			_ => self.get_value(main_data, llvm_builder).build_bit_cast(llvm_builder, float_type, "int_to_float_temp"),
 		}
 	}
 }
\ No newline at end of file
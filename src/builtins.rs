@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use llvm_nhb::{builder::Builder, module::Module, value::Value};
+
+use crate::MainData;
+
+/// Builds the LLVM IR for a call to a built-in function from the built arguments and returns the result.
+pub type BuiltinLowering = for<'a> fn(main_data: &MainData<'a>, llvm_module: &'a Module, llvm_builder: &'a Builder<'a, 'a>, arguments: &[Value<'a, 'a>]) -> Value<'a, 'a>;
+
+/// A function that is called like a global function but is built by the compiler.
+pub struct Builtin {
+	/// How many arguments a call to the built-in function must have.
+	pub parameter_count: usize,
+	/// Builds a call to the built-in function.
+	pub lower: BuiltinLowering,
+}
+
+/// Get the built-in functions keyed by name, these names are reserved and are looked up before global variables.
+pub fn default_builtins() -> HashMap<&'static str, Builtin> {
+	HashMap::from([
+		("__builtin_trap", Builtin { parameter_count: 0, lower: build_trap }),
+		("__builtin_memcpy", Builtin { parameter_count: 3, lower: build_memcpy }),
+	])
+}
+
+/// `__builtin_trap()` executes a trap instruction, yields an undefined value.
+fn build_trap<'a>(main_data: &MainData<'a>, llvm_module: &'a Module, llvm_builder: &'a Builder<'a, 'a>, _arguments: &[Value<'a, 'a>]) -> Value<'a, 'a> {
+	let function_type = main_data.llvm_context.void_type().function_type(&[], false);
+	let function = match llvm_module.get_function("llvm.trap") {
+		Some(function) => function,
+		None => llvm_module.add_function(function_type, "llvm.trap"),
+	};
+	function.build_call(&[], function_type, llvm_builder, "");
+	main_data.int_type.undefined()
+}
+
+/// `__builtin_memcpy(destination, source, size)` copies `size` bytes from the source address to the destination address, yields the destination address.
+fn build_memcpy<'a>(main_data: &MainData<'a>, _llvm_module: &'a Module, llvm_builder: &'a Builder<'a, 'a>, arguments: &[Value<'a, 'a>]) -> Value<'a, 'a> {
+	let pointer_type = main_data.int_8_type.pointer_to();
+	let destination = arguments[0].build_int_to_ptr(llvm_builder, pointer_type, "int_to_ptr_temp");
+	let source = arguments[1].build_int_to_ptr(llvm_builder, pointer_type, "int_to_ptr_temp");
+	destination.build_memcpy(&source, &arguments[2], llvm_builder);
+	arguments[0].clone()
+}
@@ -0,0 +1,49 @@
+use std::io::{stdout, IsTerminal};
+
+/// ANSI escape code for bold red text.
+pub const BOLD_RED: &str = "\x1B[1;31m";
+/// ANSI escape code for bold yellow text.
+pub const BOLD_YELLOW: &str = "\x1B[1;33m";
+/// ANSI escape code for bold text.
+pub const BOLD: &str = "\x1B[1m";
+/// ANSI escape code for bold blue text.
+pub const BOLD_BLUE: &str = "\x1B[1;34m";
+/// ANSI escape code to reset the text style.
+const RESET: &str = "\x1B[0m";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// When compiler output should be colored using ANSI escape codes.
+pub enum ColorChoice {
+	Always,
+	Auto,
+	Never,
+}
+
+impl ColorChoice {
+	/// Get a color choice from its name as used in compiler arguments.
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"always" => Some(Self::Always),
+			"auto" => Some(Self::Auto),
+			"never" => Some(Self::Never),
+			_ => None,
+		}
+	}
+
+	/// Get if output should be colored, `Auto` will color output if the standard output is a terminal.
+	pub fn should_color(self) -> bool {
+		match self {
+			Self::Always => true,
+			Self::Auto => stdout().is_terminal(),
+			Self::Never => false,
+		}
+	}
+}
+
+/// Wraps `text` in the ANSI escape code `style` if `use_color` is `true`, otherwise returns `text` unchanged.
+pub fn paint(text: &str, style: &str, use_color: bool) -> String {
+	match use_color {
+		true => format!("{style}{text}{RESET}"),
+		false => text.into(),
+	}
+}
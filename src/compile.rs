@@ -1,12 +1,44 @@
-use std::{collections::{HashMap, HashSet}, fs::{create_dir_all, File}, hash::{DefaultHasher, Hash, Hasher}, io::{BufRead, BufReader, Write}, num::NonZeroUsize, path::{Path, PathBuf}};
+use std::{collections::{HashMap, HashSet}, fs::{create_dir_all, File}, hash::{DefaultHasher, Hash, Hasher}, io::{Read, Write}, iter::from_fn, num::NonZeroUsize, path::{Path, PathBuf}, time::Instant};
 
-use crate::{ast_node::AstNode, error::Error, file_build_data::FileBuildData, parse::parse_tokens, token::Token, MainData, OperatingSystem};
+use crate::{ast_node::{AstNode, AstNodeVariant}, global_metadata::GlobalMetadata, compiler_arguments::{IrPhase, BCZ_VERSION}, error::Error, file_build_data::FileBuildData, parse::parse_tokens, position::{Span, GENERATED_POSITION}, token::{Keyword, Tokenizer}, Cpu, ErrorLocation, MainData, OperatingSystem};
 use llvm_nhb::{enums::{CallingConvention, CodegenFileType, Linkage}, module::Module};
 
-/// Compiles the file at `filepath`.
-pub fn compile_file(main_data: &mut MainData, filepath: &PathBuf) -> Result<(), (Error, Option<(PathBuf, Option<(NonZeroUsize, Option<NonZeroUsize>)>)>)> {
-	// Get output path
-	let filepath_stem: PathBuf = filepath.file_stem().ok_or_else(|| (Error::UnableToWriteObject, Some((filepath.clone(), None))))?.into();
+/// Compiles the file at `filepath` to an object file that is added to the list of object files to link.
+pub fn compile_file(main_data: &mut MainData, filepath: &PathBuf) -> Result<(), (Error, ErrorLocation)> {
+	let output_filepath = object_filepath(main_data, filepath)?;
+	// Skip if this file is already compiled
+	if main_data.object_files_to_link.contains(&output_filepath) {
+		return Ok(());
+	}
+	// Build the module, there is no module if we are only checking for errors but the output filepath is still recorded so that the file is not checked again
+	let llvm_module = match build_file_module(main_data, filepath)? {
+		Some(llvm_module) => llvm_module,
+		None => {
+			main_data.print_phase("Emit", &filepath.display().to_string(), false);
+			main_data.object_files_to_link.push(output_filepath);
+			return Ok(());
+		}
+	};
+	// Write .o file
+	let directory: PathBuf = output_filepath.parent().ok_or_else(|| (Error::UnableToWriteObject, Some((filepath.clone(), None))))?.into();
+	if !directory.exists() {
+		create_dir_all(directory).map_err(|_| (Error::UnableToWriteObject, Some((filepath.clone(), None))))?;
+	}
+	dump_ir_after(main_data, IrPhase::Opt, &llvm_module, filepath);
+	let emit_started_at = Instant::now();
+	let filepath = output_filepath.to_str().ok_or_else(|| (Error::UnableToWriteObject, Some((filepath.clone(), None))))?;
+	llvm_module.emit_to_file(&main_data.llvm_target_machine, filepath, CodegenFileType::Object)
+		.map_err(|error| (Error::UnableToEmitObjectFile(error), Some((output_filepath.clone(), None))))?;
+	main_data.record_time_trace_event("EmitObject", filepath, emit_started_at);
+	main_data.print_phase("Emit", filepath, true);
+	main_data.object_files_to_link.push(output_filepath);
+	// Return
+	Ok(())
+}
+
+/// Get the path of the object file that the source file at `filepath` is compiled to.
+fn object_filepath(main_data: &MainData, filepath: &Path) -> Result<PathBuf, (Error, ErrorLocation)> {
+	let filepath_stem: PathBuf = filepath.file_stem().ok_or_else(|| (Error::UnableToWriteObject, Some((filepath.to_path_buf(), None))))?.into();
 	let mut output_filepath = main_data.binary_path.clone();
 	let mut hasher = DefaultHasher::new();
 	filepath.parent().unwrap().hash(&mut hasher);
@@ -16,50 +48,41 @@ pub fn compile_file(main_data: &mut MainData, filepath: &PathBuf) -> Result<(),
 		Err(_) => &filepath_stem,
 	});
 	output_filepath.set_extension("o");
+	Ok(output_filepath)
+}
+
+/// Tokenizes, parses, analyzes and builds the file at `filepath` to a LLVM module without emitting it, imported files are compiled with `compile_file()`.
+///
+/// Returns `None` if we are only checking for errors. The module can be emitted or inspected by the caller, such as when embedding the compiler.
+pub fn build_file_module<'a>(main_data: &mut MainData<'a>, filepath: &PathBuf) -> Result<Option<Module<'a>>, (Error, ErrorLocation)> {
 	// Get if we are in the standard library
 	let is_in_standard_library = filepath.starts_with(&main_data.standard_library_path);
-	// Skip if this file is already compiled
-	if main_data.object_files_to_link.contains(&output_filepath) {
-		return Ok(());
-	}
 	// Open file
 	println!("{}", filepath.to_str().unwrap());
-	let file = File::open(filepath)
+	main_data.compiled_source_files.push(filepath.clone());
+	let mut file = File::open(filepath)
 		.map_err(|error| (Error::CouldNotOpenFile(error), Some((filepath.clone(), None))))?;
-	let mut file_reader = BufReader::new(file);
-	// Go over each line
-	let mut tokens = Vec::new();
-	let mut in_a_block_comment = false;
-	for line_number in 1.. {
-		let line_number = line_number.try_into().unwrap();
-		let mut line_content = String::new();
-		// Read the line
-		match file_reader.read_line(&mut line_content) {
-			// End of file encountered
-			Ok(0) => break,
-			// Normal
-			Ok(_) => {},
-			// Error
-			Err(_) => return Err((Error::CouldNotReadLine, Some((filepath.clone(), Some((line_number, None)))))),
-		}
-		// Read tokens from line
-		let line_content = line_content.as_str();
-		in_a_block_comment = tokenize_line(main_data, line_content, line_number, &mut tokens, in_a_block_comment)
-			.map_err(|(error, column)| (error, Some((filepath.clone(), Some((line_number, Some(column)))))))?;
-	}
-	if in_a_block_comment {
-		return Err((Error::UnterminatedBlockComment, Some((filepath.clone(), None))));
-	}
+	let mut file_content = String::new();
+	file.read_to_string(&mut file_content).map_err(|_| (Error::CouldNotReadLine, Some((filepath.clone(), None))))?;
+	// Tokenize the whole file
+	let mut phase_started_at = Instant::now();
+	let tokens = Tokenizer::new(&file_content).tokenize(main_data)
+		.map_err(|(error, (line, column))| (error, Some((filepath.clone(), Some((line, Some(column), None))))))?;
+	main_data.record_time_trace_event("Tokenize", filepath.display().to_string(), phase_started_at);
+	main_data.print_phase("Tokenize", &filepath.display().to_string(), true);
 	// Print tokens if commanded to do so
 	if main_data.print_tokens {
 		println!("Tokens from tokenizing file {}:", filepath.display());
 		for token in tokens.iter() {
-			println!("{:?}", token);
+			token.print();
 		}
 	}
 	// Parse
-	let mut ast_nodes = parse_tokens(tokens)
-		.map_err(|(error, (line, column))| (error, Some((filepath.clone(), Some((line, Some(column)))))))?;
+	phase_started_at = Instant::now();
+	let mut ast_nodes = parse_tokens(main_data, tokens)
+		.map_err(|(error, span)| (error, Some((filepath.clone(), Some(span_location(span))))))?;
+	main_data.record_time_trace_event("Parse", filepath.display().to_string(), phase_started_at);
+	main_data.print_phase("Parse", &filepath.display().to_string(), true);
 	// Print parsed AST nodes if commanded to do so
 	if main_data.print_ast_nodes {
 		println!("Tokens from parsing file {}:", filepath.display());
@@ -67,21 +90,60 @@ pub fn compile_file(main_data: &mut MainData, filepath: &PathBuf) -> Result<(),
 			ast_node.print_tree(0);
 		}
 	}
+	// Print metadata keywords if commanded to do so
+	if main_data.dump_metadata {
+		println!("Metadata of {}:", filepath.display());
+		for ast_node in ast_nodes.iter() {
+			ast_node.print_metadata();
+		}
+	}
 	// Separate global variables out
+	phase_started_at = Instant::now();
 	let mut globals = HashMap::new();
 	for ast_node in ast_nodes.iter_mut() {
 		ast_node.separate_globals(&mut globals, true, false)
-			.map_err(|(error, (line, column))| (error, Some((filepath.clone(), Some((line, Some(column)))))))?;
+			.map_err(|(error, span)| (error, Some((filepath.clone(), Some(span_location(span))))))?;
+	}
+	// Replace operators with calls to the functions that overload them, the overload functions themselves use the built in operators
+	if main_data.operator_overloading {
+		let overloads: HashSet<Box<str>> = globals.iter()
+			.filter(|(name, (expression, _))| name.starts_with("__") && expression.is_function())
+			.map(|(name, _)| name.clone())
+			.collect();
+		for (name, (expression, _)) in globals.iter_mut() {
+			if !overloads.contains(name) {
+				expression.desugar_operator_overloads(&overloads);
+			}
+		}
+	}
+	main_data.print_phase("SeparateGlobals", &filepath.display().to_string(), true);
+	// Add constants defined from the command line unless the file assigns to a global of the same name
+	for (name, value) in main_data.defined_constants.iter() {
+		if !globals.contains_key(name) {
+			globals.insert(name.clone(), (AstNode { variant: AstNodeVariant::Constant(*value), start: GENERATED_POSITION, end: GENERATED_POSITION }, false));
+		}
 	}
 	// Get dependencies for each global variable
 	let mut import_dependencies = HashSet::new();
 	let mut globals_and_dependencies: HashMap<Box<str>, (AstNode, bool, HashSet<Box<str>>)> = HashMap::new();
+	let mut variable_dependency_uses = Vec::new();
 	for (name, (expression, is_exported)) in globals.into_iter() {
-		let mut variable_dependencies = HashSet::new();
+		let mut variable_dependencies = HashMap::new();
 		expression.get_variable_dependencies(
 			main_data, filepath, &mut variable_dependencies, &mut import_dependencies, &mut Vec::new(), false
-		).map_err(|(error, (line, column))| (error, Some((filepath.clone(), Some((line, Some(column)))))))?;
-		globals_and_dependencies.insert(name, (expression, is_exported, variable_dependencies));
+		).map_err(|(error, span)| (error, Some((filepath.clone(), Some(span_location(span))))))?;
+		variable_dependency_uses.extend(variable_dependencies.iter().map(|(variable_dependency, span)| (variable_dependency.clone(), *span)));
+		globals_and_dependencies.insert(name, (expression, is_exported, variable_dependencies.into_keys().collect()));
+	}
+	main_data.print_phase("Dependencies", &filepath.display().to_string(), true);
+	// Make sure that each global variable that is used is assigned to if commanded to do so, the use of an undefined global that is first in the file is reported
+	if main_data.strict_globals {
+		let undefined_global = variable_dependency_uses.into_iter()
+			.filter(|(variable_dependency, _)| !globals_and_dependencies.contains_key(variable_dependency))
+			.min_by_key(|(_, span)| *span);
+		if let Some((variable_dependency, span)) = undefined_global {
+			return Err((Error::UndefinedGlobal(variable_dependency.into()), Some((filepath.clone(), Some(span_location(span))))));
+		}
 	}
 	// Print global variables if commanded to do so
 	if main_data.print_after_analyzer {
@@ -98,11 +160,19 @@ pub fn compile_file(main_data: &mut MainData, filepath: &PathBuf) -> Result<(),
 			println!("{}", import_dependency.display());
 		}
 	}
+	main_data.record_time_trace_event("Analyze", filepath.display().to_string(), phase_started_at);
 	// Compile imports
 	for import_dependency_filepath in import_dependencies.iter() {
 		compile_file(main_data, import_dependency_filepath)?;
 	}
+	// Imports of constants can be replaced with the constant value now that the imported files are compiled
+	for (global, _, _) in globals_and_dependencies.values_mut() {
+		if let Some(value) = global.imported_constant_value(main_data, filepath) {
+			global.variant = AstNodeVariant::Constant(value);
+		}
+	}
 	// Const evaluate globals
+	phase_started_at = Instant::now();
 	let mut global_function_list = HashSet::new();
 	for (name, (global, _is_exported, _)) in globals_and_dependencies.iter_mut() {
 		if !global.is_function() {
@@ -130,18 +200,69 @@ pub fn compile_file(main_data: &mut MainData, filepath: &PathBuf) -> Result<(),
 				main_data, &globals_and_dependencies_after_const_evaluate,
 				&mut new_variable_dependencies, &mut Vec::new(), false, false,
 				is_in_standard_library
-			).map_err(|(error, (line, column))| (error, Some((filepath.clone(), Some((line, Some(column)))))))?;
+			).map_err(|(error, span)| (error, Some((filepath.clone(), Some(span_location(span))))))?;
 			// Add to list
 			globals_and_dependencies_after_const_evaluate.insert(name.clone(), (new_global, *is_exported, new_variable_dependencies));
 			globals_have_been_const_evaluated_this_round = true;
 		}
 		// If we did not const evaluate anything this round, there is a cyclic dependency
 		if !globals_have_been_const_evaluated_this_round {
-			let error_pos = globals_and_dependencies.iter().next().unwrap().1.0.start;
-			return Err((Error::InvalidDependency, Some((filepath.clone(), Some((error_pos.0, Some(error_pos.1)))))));
+			let error_span = globals_and_dependencies.iter().next().unwrap().1.0.span();
+			return Err((Error::InvalidDependency, Some((filepath.clone(), Some(span_location(error_span))))));
 		}
 	}
 	drop(globals_and_dependencies);
+	// Check static assertions outside of globals now that the globals they use are const evaluated
+	for ast_node in ast_nodes.iter_mut() {
+		if !matches!(ast_node.variant, AstNodeVariant::Keyword(Keyword::Assert, ..)) {
+			continue;
+		}
+		let mut variable_dependencies = HashMap::new();
+		ast_node.get_variable_dependencies(main_data, filepath, &mut variable_dependencies, &mut HashSet::new(), &mut Vec::new(), false)
+			.map_err(|(error, span)| (error, Some((filepath.clone(), Some(span_location(span))))))?;
+		let undefined_global = variable_dependencies.iter()
+			.filter(|(variable_dependency, _)| !globals_and_dependencies_after_const_evaluate.contains_key(*variable_dependency))
+			.min_by_key(|(_, span)| **span);
+		if let Some((variable_dependency, span)) = undefined_global {
+			return Err((Error::UndefinedGlobal(variable_dependency.to_string()), Some((filepath.clone(), Some(span_location(*span))))));
+		}
+		let mut variable_dependencies = variable_dependencies.into_keys().collect();
+		ast_node.const_evaluate(
+			main_data, &globals_and_dependencies_after_const_evaluate, &mut variable_dependencies, &mut Vec::new(), false, false, is_in_standard_library
+		).map_err(|(error, span)| (error, Some((filepath.clone(), Some(span_location(span))))))?;
+	}
+	main_data.record_time_trace_event("ConstEvaluate", filepath.display().to_string(), phase_started_at);
+	main_data.print_phase("ConstEvaluate", &filepath.display().to_string(), true);
+	// Store exported constants so that files that import them can use the value directly
+	// and the defaults of exported functions so that calls from files that import them can fill in omitted arguments
+	for (name, (global, is_exported, _)) in globals_and_dependencies_after_const_evaluate.iter() {
+		if let (true, AstNodeVariant::Constant(value)) = (is_exported, &global.variant) {
+			main_data.exported_constants.insert((filepath.clone(), name.clone()), *value);
+		}
+		if !is_exported || !global.is_function() {
+			continue;
+		}
+		let parameter_defaults = global.parameter_defaults().map_err(|(error, span)| (error, Some((filepath.clone(), Some(span_location(span))))))?;
+		if parameter_defaults.iter().any(Option::is_some) {
+			main_data.exported_parameter_defaults.insert((filepath.clone(), name.clone()), parameter_defaults);
+		}
+	}
+	// Describe each global for the metadata JSON file if commanded to do so, sorted by name so that the file is the same each build
+	if main_data.metadata_json_filepath.is_some() {
+		let mut globals_metadata: Vec<GlobalMetadata> = globals_and_dependencies_after_const_evaluate.iter()
+			.map(|(name, (global, is_exported, _))| GlobalMetadata {
+				filepath: filepath.clone(),
+				name: name.clone(),
+				is_function: global.parameter_count().is_some(),
+				parameter_count: global.parameter_count(),
+				is_link: matches!(global.variant, AstNodeVariant::Keyword(Keyword::Link, ..)),
+				is_exported: *is_exported,
+				is_entry_point: matches!(global.variant, AstNodeVariant::Keyword(Keyword::EntryPoint, ..)),
+			})
+			.collect();
+		globals_metadata.sort_by(|a, b| a.name.cmp(&b.name));
+		main_data.globals_metadata.extend(globals_metadata);
+	}
 	// Print const evaluated globals if commanded to do so
 	if main_data.print_after_const_evaluate {
 		println!("Const evaluated globals of {}:", filepath.display());
@@ -153,6 +274,11 @@ pub fn compile_file(main_data: &mut MainData, filepath: &PathBuf) -> Result<(),
 			global.print_tree(0);
 		}
 	}
+	// Stop here if we are only checking for errors
+	if main_data.check_only {
+		main_data.print_phase("Codegen", &filepath.display().to_string(), false);
+		return Ok(None);
+	}
 	// Build LLVM module
 	let module_name = match filepath.file_stem() {
 		None => "invalid_name",
@@ -162,60 +288,78 @@ pub fn compile_file(main_data: &mut MainData, filepath: &PathBuf) -> Result<(),
 		}
 	};
 	let llvm_module = main_data.llvm_context.new_module(module_name);
+	// Record the source filename, deterministic builds use a path relative to the source or standard library directory with '/' separators
+	let source_file_name = match main_data.deterministic {
+		true => {
+			let relative_filepath = main_data.source_path.canonicalize().ok()
+				.and_then(|source_path| filepath.strip_prefix(source_path).ok())
+				.or_else(|| filepath.strip_prefix(&main_data.standard_library_path).ok())
+				.unwrap_or_else(|| Path::new(module_name));
+			relative_filepath.components()
+				.map(|component| component.as_os_str().to_string_lossy())
+				.collect::<Vec<_>>()
+				.join("/")
+		}
+		false => filepath.to_string_lossy().into_owned(),
+	};
+	llvm_module.set_source_file_name(&source_file_name);
+	phase_started_at = Instant::now();
 	build_llvm_module(main_data, &llvm_module, globals_and_dependencies_after_const_evaluate, filepath)
-		.map_err(|(error, location)| (error, Some((filepath.clone(), match location {
-			Some((line, column)) => Some((line, Some(column))),
-			None => None,
-		}))))?;
-	// Write .o file
-	let directory: PathBuf = output_filepath.parent().ok_or_else(|| (Error::UnableToWriteObject, Some((filepath.clone(), None))))?.into();
-	if !directory.exists() {
-		create_dir_all(directory).map_err(|_| (Error::UnableToWriteObject, Some((filepath.clone(), None))))?;
+		.map_err(|(error, span)| (error, Some((filepath.clone(), span.map(span_location)))))?;
+	main_data.record_time_trace_event("BuildModule", filepath.display().to_string(), phase_started_at);
+	main_data.print_phase("Codegen", &filepath.display().to_string(), true);
+	dump_ir_after(main_data, IrPhase::Codegen, &llvm_module, filepath);
+	Ok(Some(llvm_module))
+}
+
+/// Print the LLVM IR of the module built from the file at `filepath` if `--dump-ir-after` selected `phase`.
+fn dump_ir_after(main_data: &MainData, phase: IrPhase, llvm_module: &Module, filepath: &Path) {
+	if main_data.dump_ir_after != Some(phase) {
+		return;
+	}
+	println!("LLVM IR of {} after {}:", filepath.display(), phase.name());
+	print!("{}", llvm_module.print_to_string());
+}
+
+/// Converts the span of an error into a line, column and end column, the end column is only given if the span ends on the same line it starts on.
+pub fn span_location(((line, column), (end_line, end_column)): Span) -> (NonZeroUsize, Option<NonZeroUsize>, Option<NonZeroUsize>) {
+	match end_line == line && end_column > column {
+		true => (line, Some(column), Some(end_column)),
+		false => (line, Some(column), None),
 	}
-	let filepath = output_filepath.to_str().ok_or_else(|| (Error::UnableToWriteObject, Some((filepath.clone(), None))))?;
-	llvm_module.emit_to_file(&main_data.llvm_target_machine, filepath, CodegenFileType::Object)
-		.map_err(|error| (Error::UnableToEmitObjectFile(error), Some((output_filepath.clone(), None))))?;
-	main_data.object_files_to_link.push(output_filepath);
-	// Return
-	Ok(())
 }
 
-/// Takes in a line of source code and tokenizes it to `Token`s that are appended to `push_to`.
-fn tokenize_line(main_data: &mut MainData, mut line_string: &str, line_number: NonZeroUsize, push_to: &mut Vec<Token>, mut starts_with_block_comment: bool) -> Result<bool, (Error, NonZeroUsize)> {
-	let mut column_number = NonZeroUsize::MIN;
-	loop {
-		// Get how many whitespace chars there are untill the next non-whitespace,
-		// chars and bytes are the same size sice since we are only looking for ASCII whitespace chars
-		let start_whitespace_length = match line_string.find(|chr: char| !chr.is_ascii_whitespace()) {
-			Some(start_whitespace_length) => start_whitespace_length,
-			None => break,
-		};
-		// Skip said amount of chars
-		column_number = column_number.saturating_add(start_whitespace_length);
-		line_string = &line_string[start_whitespace_length..];
-		// Tokenize a token from the string and push to list of read tokens
-		let (token, new_line_string, starts_block_comment) = Token::tokenize_from_line(main_data, line_string, line_number, column_number, starts_with_block_comment)
-			.map_err(|error| (error, column_number))?;
-		match token {
-			Some(token) => push_to.push(token),
-			None => {},
-		}
-		starts_with_block_comment = starts_block_comment;
-		// Skip over the chars that where consumed by the tokenization
-		let bytes_consumed_by_parse = line_string.len() - new_line_string.len();
-		let chars_consumed_by_parse = line_string[..bytes_consumed_by_parse].chars().count();
-		column_number = column_number.saturating_add(chars_consumed_by_parse);
-		line_string = new_line_string;
-	}
-	Ok(starts_with_block_comment)
+/// Splits source code into lines without their line endings, lines can end with `\n`, `\r\n` or a lone `\r`.
+pub fn source_lines(source: &str) -> impl Iterator<Item = &str> {
+	let mut remaining_source = Some(source);
+	from_fn(move || {
+		let source = remaining_source?;
+		match source.find(['\r', '\n']) {
+			Some(index) => {
+				let line_ending_length = match source[index..].starts_with("\r\n") {
+					true => 2,
+					false => 1,
+				};
+				remaining_source = Some(&source[index + line_ending_length..]);
+				Some(&source[..index])
+			}
+			None => {
+				remaining_source = None;
+				Some(source)
+			}
+		}
+	})
 }
 
 /// Take in a list of global variables and build them into a LLVM module.
 fn build_llvm_module(main_data: &mut MainData, llvm_module: &Module, globals_and_dependencies: HashMap<Box<str>, (AstNode, bool, HashSet<Box<str>>)>, filepath: &PathBuf)
-	-> Result<(), (Error, Option<(NonZeroUsize, NonZeroUsize)>)> {
+	-> Result<(), (Error, Option<Span>)> {
 	// Set up module
 	llvm_module.set_target_triple(&*main_data.llvm_target_triple);
 	llvm_module.set_data_layout(&main_data.llvm_data_layout);
+	// Identify the compiler that built the module
+	let ident = main_data.llvm_context.metadata_string(&format!("BCZ compiler version {BCZ_VERSION}"));
+	llvm_module.add_named_metadata_operand("llvm.ident", main_data.llvm_context.metadata_node(&[ident]));
 	// Create data struct for builder
 	let llvm_builder = main_data.llvm_context.new_builder();
 	let mut file_build_data = FileBuildData {
@@ -223,12 +367,30 @@ fn build_llvm_module(main_data: &mut MainData, llvm_module: &Module, globals_and
 		built_global_function_signatures: HashMap::new(),
 		entrypoint: None,
 		filepath,
+		unnamed_function_count: 0,
+		default_arguments: HashMap::new(),
+		global_names: globals_and_dependencies.keys().cloned().collect(),
+		referenced_globals: HashSet::new(),
+		int_function_types: HashMap::new(),
 	};
+	for (global, _, _) in globals_and_dependencies.values() {
+		global.add_referenced_names(&mut file_build_data.referenced_globals);
+	}
+	// Get the default parameter values of imported functions so that calls to them can fill in omitted arguments
+	for (name, (global, _, _)) in globals_and_dependencies.iter() {
+		if let Some(parameter_defaults) = global.imported_parameter_defaults(main_data, filepath) {
+			file_build_data.default_arguments.insert(name.clone(), parameter_defaults);
+		}
+	}
 	// Build function signatures
 	for (name, (global, _is_exported, _)) in globals_and_dependencies.iter() {
 		if !global.is_function() {
 			continue;
 		}
+		let parameter_defaults = global.parameter_defaults().map_err(|(error, location)| (error, Some(location)))?;
+		if parameter_defaults.iter().any(Option::is_some) {
+			file_build_data.default_arguments.insert(name.clone(), parameter_defaults);
+		}
 		let function_signature = global.build_function_signature(main_data, &mut file_build_data, llvm_module, &llvm_builder, name, false)
 			.map_err(|(error, location)| (error, Some(location)))?;
 		file_build_data.built_global_function_signatures.insert(name.clone(), function_signature);
@@ -254,15 +416,17 @@ fn build_llvm_module(main_data: &mut MainData, llvm_module: &Module, globals_and
 				}
 			}
 			// Build
+			let global_started_at = Instant::now();
 			let built_result = global.build_global_assignment(main_data, llvm_module, &llvm_builder, &mut file_build_data, name, *is_exported)
 				.map_err(|(error, location)| (error, Some(location)))?;
+			main_data.record_time_trace_event("BuildGlobal", &**name, global_started_at);
 			// Add to list
 			file_build_data.built_globals.insert(name.clone(), built_result);
 			globals_built_this_round.insert(name.clone());
 		}
 		// If we did not compile anything this round, there is a cyclic dependency
 		if globals_built_this_round.is_empty() {
-			return Err((Error::InvalidDependency, globals_and_dependencies.iter().next().unwrap().1.0.start))
+			return Err((Error::InvalidDependency, globals_and_dependencies.iter().next().unwrap().1.0.span()))
 				.map_err(|(error, location)| (error, Some(location)))?;
 		}
 		// Remove built globals from the to build list
@@ -270,10 +434,26 @@ fn build_llvm_module(main_data: &mut MainData, llvm_module: &Module, globals_and
 			globals_built.insert(name.clone());
 		}
 	}
-	// Build entry point
-	if let Some((wrapped_entry_point, wrapped_entry_point_name)) = file_build_data.entrypoint {
-		match main_data.operating_system {
-			OperatingSystem::Windows => {
+	// Build entry point, libraries built with --no-entry are linked elsewhere so they do not get one
+	if let (false, Some((wrapped_entry_point, wrapped_entry_point_name))) = (main_data.no_entry, file_build_data.entrypoint) {
+		match (main_data.freestanding, main_data.operating_system) {
+			// There is nothing for the entry point to return to, so it is called from `_start` which then loops forever
+			(true, _) => {
+				let start_function_type = main_data.llvm_context.void_type().function_type(&[], false);
+				let start_function = llvm_module.add_function(start_function_type, "_start");
+				start_function.set_linkage(Linkage::External);
+				let start_basic_block = start_function.append_basic_block(main_data.llvm_context, "entry");
+				llvm_builder.position_at_end(&start_basic_block);
+				let wrapped_entry_point_function_type = main_data.int_type.function_type(&[], false);
+				let wrapped_entry_point_function_pointer = wrapped_entry_point
+					.build_int_to_ptr(&llvm_builder, wrapped_entry_point_function_type.pointer_to(), "int_to_fn_ptr_temp");
+				wrapped_entry_point_function_pointer.build_call(&[], wrapped_entry_point_function_type, &llvm_builder, "function_call_temp");
+				let halt_basic_block = start_function.append_basic_block(main_data.llvm_context, "halt");
+				llvm_builder.build_branch(&halt_basic_block);
+				llvm_builder.position_at_end(&halt_basic_block);
+				llvm_builder.build_branch(&halt_basic_block);
+			}
+			(false, OperatingSystem::Windows) => {
 				// Get types of wrapper function
 				let int_32_type = main_data.llvm_context.int_32_type();
 				let entry_point_function_parameters = [main_data.int_type, main_data.int_type, main_data.int_type, int_32_type];
@@ -286,7 +466,10 @@ fn build_llvm_module(main_data: &mut MainData, llvm_module: &Module, globals_and
 				// Build wrapper function
 				let entry_point_function = llvm_module.add_function(entry_point_function_type, "WinMain");
 				entry_point_function.set_linkage(Linkage::External);
-				entry_point_function.set_calling_convention(CallingConvention::Win64);
+				entry_point_function.set_calling_convention(match main_data.cpu {
+					Cpu::X86_64 => CallingConvention::Win64,
+					Cpu::I686 => CallingConvention::X86StdCall,
+				});
 				let entry_point_function_basic_block = entry_point_function.append_basic_block(&main_data.llvm_context, "entry");
 				llvm_builder.position_at_end(&entry_point_function_basic_block);
 				let built_function_call = wrapped_entry_point_function_pointer
@@ -294,7 +477,12 @@ fn build_llvm_module(main_data: &mut MainData, llvm_module: &Module, globals_and
 				let truncated_result = built_function_call.build_truncate(&llvm_builder, int_32_type, "trunc_cast_temp");
 				truncated_result.build_return(&llvm_builder);
 			}
-			OperatingSystem::Linux => {
+			(false, OperatingSystem::Linux) => {
+				// The entry point returns the exit code, which is passed to the exit syscall of the target CPU
+				let exit_syscall = match main_data.cpu {
+					Cpu::X86_64 => "movl %eax, %edi\n\tmovl $60, %eax\n\tsyscall",
+					Cpu::I686 => "movl %eax, %ebx\n\tmovl $1, %eax\n\tint $0x80",
+				};
 				let mut entry_filepath = main_data.binary_path.clone();
 				entry_filepath.push("entry.s");
 				let mut file = File::create(&entry_filepath)
@@ -304,21 +492,32 @@ fn build_llvm_module(main_data: &mut MainData, llvm_module: &Module, globals_and
 	.weak {wrapped_entry_point_name}
 _start:
 	call {wrapped_entry_point_name}
-	movl %eax, %ebx
-	movl $1, %eax
-	int $0x80
+	{exit_syscall}
 "				);
 				file.write_all(entry_cile_content.as_bytes()).map_err(|_| (Error::UnableToWriteObject, None))?;
 				file.flush().map_err(|_| (Error::UnableToWriteObject, None))?;
 				main_data.object_files_to_link.push(entry_filepath);
 			}
 		}
+		main_data.has_entry_point = true;
 	}
 	// Dump module if commanded to do so
 	if main_data.dump_llvm_module {
 		println!("LLVM IR of {}:", filepath.display());
 		llvm_module.dump();
 	}
+	// Print the symbols of the module if commanded to do so
+	if main_data.dump_symbols {
+		println!("Symbols of {}:", filepath.display());
+		for (kind, value) in llvm_module.functions().map(|function| ("function", function))
+			.chain(llvm_module.globals().map(|global| ("global", global))) {
+			let linkage = match value.get_linkage() {
+				Some(linkage) => format!("{linkage:?}"),
+				None => "Other".into(),
+			};
+			println!("{kind} {} {linkage}", value.get_name());
+		}
+	}
 	Ok(())
 }
 
@@ -334,7 +533,39 @@ pub fn relative_filepath_to_absolute(main_data: &MainData, current_filepath: &Pa
 		}
 		return Ok(result.canonicalize().map_err(|_| Error::InvalidFilepath)?)
 	}
-	let result = current_filepath.parent().ok_or(Error::InvalidFilepath)?
-		.join(relative_filepath).canonicalize().map_err(|_| Error::InvalidFilepath)?;
-	Ok(result)
-}
\ No newline at end of file
+	// Search the directory of the importing file then each include directory
+	let searched_directories: Vec<&Path> = [current_filepath.parent().ok_or(Error::InvalidFilepath)?].into_iter()
+		.chain(main_data.include_directories.iter().map(PathBuf::as_path))
+		.collect();
+	for directory in searched_directories.iter() {
+		if let Ok(result) = directory.join(relative_filepath).canonicalize() {
+			return Ok(result);
+		}
+	}
+	Err(Error::ImportNotFound(relative_filepath.into(), searched_directories.into_iter().map(Path::to_path_buf).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{env::temp_dir, fs::{create_dir_all, remove_dir_all, write}};
+
+	use crate::with_main_data;
+
+	use super::build_file_module;
+
+	#[test]
+	fn built_module_is_returned() {
+		// The entry point wrapper is written to the binary directory so it is set to a temporary directory
+		let directory = temp_dir().join(format!("bcz_test_{}_built_module_is_returned", std::process::id()));
+		create_dir_all(&directory).unwrap();
+		let filepath = directory.join("main.bcz");
+		write(&filepath, "double = (a) {\n\ta * 2\n};\nmain = @entry_point () {\n\tdouble(3)\n};\n").unwrap();
+		let function_names: Vec<String> = with_main_data(&["-c", "-b", directory.to_str().unwrap()], |main_data| {
+			let module = build_file_module(main_data, &filepath).unwrap_or_else(|(error, _)| panic!("{error}")).expect("module should be built");
+			module.functions().map(|function| function.get_name()).collect()
+		});
+		remove_dir_all(&directory).unwrap();
+		assert!(function_names.iter().any(|name| name == "main"), "{function_names:?}");
+		assert!(function_names.iter().any(|name| name == "double"), "{function_names:?}");
+	}
+}
@@ -1,12 +1,16 @@
-use std::{collections::HashMap, env::current_dir, path::PathBuf};
+use std::{collections::{BTreeMap, HashMap, HashSet}, env::{current_dir, vars}, fs::read_to_string, path::{Path, PathBuf}};
+use llvm_nhb::enums::CodeModel;
+use serde::Deserialize;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 use target_triple::TARGET;
 
-use crate::error::Error;
+use crate::{color::ColorChoice, error::{Error, ErrorFormat}};
 
 /// The version of the BCZ compiler taken from `Cargo.toml`.
-const BCZ_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+pub const BCZ_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+/// How deep response files can include other response files, stops response files that include each other from recursing forever.
+const MAX_RESPONSE_FILE_DEPTH: usize = 16;
 
 pub struct CompilerArgumentsData<'a> {
 	pub do_link: bool,
@@ -23,6 +27,47 @@ pub struct CompilerArgumentsData<'a> {
 	pub binary_path: PathBuf,
 	pub target_triplet: Box<str>,
 	pub link_command: Box<str>,
+	pub color_choice: ColorChoice,
+	/// Should position independent code be generated, `None` uses the default for the target.
+	pub position_independent_code: Option<bool>,
+	pub code_model: CodeModel,
+	pub strict_globals: bool,
+	pub check_only: bool,
+	pub error_format: ErrorFormat,
+	pub trap_mode: TrapMode,
+	pub dump_symbols: bool,
+	pub freestanding: bool,
+	pub no_asserts: bool,
+	pub defined_constants: HashMap<Box<str>, u64>,
+	pub dump_target_data_layout: bool,
+	pub library_search_paths: Vec<&'a str>,
+	pub linked_libraries: Vec<&'a str>,
+	pub sysroot: Option<&'a str>,
+	pub deterministic: bool,
+	pub keep_going: bool,
+	pub dep_filepath: Option<&'a str>,
+	pub fast_math: bool,
+	pub include_directories: Vec<&'a str>,
+	pub ir_comments: bool,
+	pub max_function_parameters: u16,
+	pub max_function_arguments: u16,
+	pub language_version: LanguageVersion,
+	pub checked_division: bool,
+	pub time_trace_filepath: Option<&'a str>,
+	pub abi_check: bool,
+	pub dump_scopes: bool,
+	pub operator_overloading: bool,
+	pub no_entry: bool,
+	pub strict: bool,
+	pub print_phases: bool,
+	pub narrow_globals: bool,
+	pub overflow_check_const: bool,
+	/// The phase after which the LLVM IR of each module should be printed.
+	pub dump_ir_after: Option<IrPhase>,
+	pub timeout: Option<u64>,
+	pub dump_metadata: bool,
+	pub metadata_json_filepath: Option<&'a str>,
+	pub suppressed_codes: HashSet<Box<str>>,
 }
 
 impl<'a> CompilerArgumentsData<'a> {
@@ -42,6 +87,108 @@ impl<'a> CompilerArgumentsData<'a> {
 			primary_output_file: None,
 			target_triplet: TARGET.into(),
 			link_command: "gcc".into(),
+			color_choice: ColorChoice::Auto,
+			position_independent_code: None,
+			code_model: CodeModel::Default,
+			strict_globals: false,
+			check_only: false,
+			error_format: ErrorFormat::Human,
+			trap_mode: TrapMode::Trap,
+			dump_symbols: false,
+			freestanding: false,
+			no_asserts: false,
+			defined_constants: HashMap::new(),
+			dump_target_data_layout: false,
+			library_search_paths: Vec::new(),
+			linked_libraries: Vec::new(),
+			sysroot: None,
+			deterministic: false,
+			keep_going: false,
+			dep_filepath: None,
+			fast_math: false,
+			include_directories: Vec::new(),
+			ir_comments: false,
+			max_function_parameters: u16::MAX,
+			max_function_arguments: u16::MAX,
+			language_version: LanguageVersion::LATEST,
+			checked_division: false,
+			time_trace_filepath: None,
+			abi_check: false,
+			dump_scopes: false,
+			operator_overloading: false,
+			no_entry: false,
+			strict: false,
+			print_phases: false,
+			narrow_globals: false,
+			overflow_check_const: false,
+			dump_ir_after: None,
+			timeout: None,
+			dump_metadata: false,
+			metadata_json_filepath: None,
+			suppressed_codes: HashSet::new(),
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// What the compiled program does when a runtime check fails.
+pub enum TrapMode {
+	/// Call the `__bcz_panic` function, which should be provided by the program and should not return.
+	Abort,
+	/// Execute a trap instruction using `llvm.trap`.
+	Trap,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// A point in building a module at which its LLVM IR can be printed with `--dump-ir-after`.
+pub enum IrPhase {
+	/// After the module is built from the AST, before any optimization.
+	Codegen,
+	/// After the module is optimized, just before it is emitted.
+	Opt,
+}
+
+impl IrPhase {
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"codegen" => Some(Self::Codegen),
+			"opt" => Some(Self::Opt),
+			_ => None,
+		}
+	}
+
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Codegen => "codegen",
+			Self::Opt => "opt",
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+/// A version of the language selected with `--std`, so that older code keeps compiling the same way as the language changes.
+pub enum LanguageVersion {
+	/// The language before pipeline operators and comparison chains.
+	V1,
+	/// Adds the `|>` pipeline operator and comparison chains such as `a < b < c`.
+	V2,
+}
+
+impl LanguageVersion {
+	pub const LATEST: Self = Self::V2;
+
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"1" => Some(Self::V1),
+			"2" => Some(Self::V2),
+			_ => None,
+		}
+	}
+
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::V1 => "1",
+			Self::V2 => "2",
 		}
 	}
 }
@@ -55,6 +202,24 @@ enum ArgumentProcessingState {
 	SetBinaryHomeFilepath,
 	SetTargetTriplet,
 	SetLinkCommand,
+	SetColorChoice,
+	SetCodeModel,
+	SetErrorFormat,
+	SetTrapMode,
+	SetDefineFromEnvPrefix,
+	AddLibrarySearchPath,
+	AddLinkedLibrary,
+	SetSysroot,
+	SetDepFilepath,
+	AddIncludeDirectory,
+	SetMaxFunctionParameters,
+	SetMaxFunctionArguments,
+	SetLanguageVersion,
+	SetTimeTraceFilepath,
+	SetDumpIrAfter,
+	SetTimeout,
+	SetMetadataJsonFilepath,
+	AddSuppressedCode,
 }
 
 #[derive(Clone, Copy, EnumIter)]
@@ -76,6 +241,47 @@ enum CompilerOptionToken {
 	DumpLlvmModule,
 	TargetTriplet,
 	LinkCommand,
+	Color,
+	NoColor,
+	PositionIndependentCode,
+	NoPositionIndependentCode,
+	CodeModel,
+	StrictGlobals,
+	Check,
+	ErrorFormat,
+	TrapMode,
+	DumpSymbols,
+	Freestanding,
+	NoAsserts,
+	DefineFromEnv,
+	DumpTargetDataLayout,
+	LibrarySearchPath,
+	LinkLibrary,
+	Sysroot,
+	Deterministic,
+	KeepGoing,
+	EmitDepFile,
+	FastMath,
+	IncludeDirectory,
+	IrComments,
+	MaxFunctionParameters,
+	MaxFunctionArguments,
+	LanguageVersion,
+	CheckedDivision,
+	TimeTrace,
+	AbiCheck,
+	DumpScopes,
+	OperatorOverloading,
+	NoEntry,
+	Strict,
+	PrintPhases,
+	NarrowGlobals,
+	OverflowCheckConst,
+	DumpIrAfter,
+	Timeout,
+	DumpMetadata,
+	EmitMetadataJson,
+	Suppress,
 }
 
 impl CompilerOptionToken {
@@ -98,6 +304,47 @@ impl CompilerOptionToken {
 			Self::DumpLlvmModule => None,
 			Self::PrintAfterConstEvaluate => None,
 			Self::PrintAstNodesAfterFunctionSignatureBuild => None,
+			Self::Color => None,
+			Self::NoColor => None,
+			Self::PositionIndependentCode => None,
+			Self::NoPositionIndependentCode => None,
+			Self::CodeModel => None,
+			Self::StrictGlobals => None,
+			Self::Check => None,
+			Self::ErrorFormat => None,
+			Self::TrapMode => None,
+			Self::DumpSymbols => None,
+			Self::Freestanding => None,
+			Self::NoAsserts => None,
+			Self::DefineFromEnv => None,
+			Self::DumpTargetDataLayout => None,
+			Self::LibrarySearchPath => Some("L"),
+			Self::LinkLibrary => None,
+			Self::Sysroot => None,
+			Self::Deterministic => None,
+			Self::KeepGoing => None,
+			Self::EmitDepFile => None,
+			Self::FastMath => None,
+			Self::IncludeDirectory => Some("I"),
+			Self::IrComments => None,
+			Self::MaxFunctionParameters => None,
+			Self::MaxFunctionArguments => None,
+			Self::LanguageVersion => None,
+			Self::CheckedDivision => None,
+			Self::TimeTrace => None,
+			Self::AbiCheck => None,
+			Self::DumpScopes => None,
+			Self::OperatorOverloading => None,
+			Self::NoEntry => None,
+			Self::Strict => None,
+			Self::PrintPhases => None,
+			Self::NarrowGlobals => None,
+			Self::OverflowCheckConst => None,
+			Self::DumpIrAfter => None,
+			Self::Timeout => None,
+			Self::DumpMetadata => None,
+			Self::EmitMetadataJson => None,
+			Self::Suppress => None,
 		}
 	}
 
@@ -119,6 +366,47 @@ impl CompilerOptionToken {
 			Self::PrintAstNodesAfterFunctionSignatureBuild => Some("print-ast-nodes-after-function-signature-build"),
 			Self::TargetTriplet => Some("target-triplet"),
 			Self::LinkCommand => Some("link-command"),
+			Self::Color => Some("color"),
+			Self::NoColor => Some("no-color"),
+			Self::PositionIndependentCode => Some("pic"),
+			Self::NoPositionIndependentCode => Some("no-pic"),
+			Self::CodeModel => Some("code-model"),
+			Self::StrictGlobals => Some("strict-globals"),
+			Self::Check => Some("check"),
+			Self::ErrorFormat => Some("error-format"),
+			Self::TrapMode => Some("trap-mode"),
+			Self::DumpSymbols => Some("dump-symbols"),
+			Self::Freestanding => Some("freestanding"),
+			Self::NoAsserts => Some("no-asserts"),
+			Self::DefineFromEnv => Some("define-from-env"),
+			Self::DumpTargetDataLayout => Some("dump-target-data-layout"),
+			Self::LibrarySearchPath => Some("library-path"),
+			Self::LinkLibrary => Some("library"),
+			Self::Sysroot => Some("sysroot"),
+			Self::Deterministic => Some("deterministic"),
+			Self::KeepGoing => Some("keep-going"),
+			Self::EmitDepFile => Some("emit-dep-file"),
+			Self::FastMath => Some("ffast-math"),
+			Self::IncludeDirectory => Some("include-dir"),
+			Self::IrComments => Some("ir-comments"),
+			Self::MaxFunctionParameters => Some("max-function-params"),
+			Self::MaxFunctionArguments => Some("max-function-args"),
+			Self::LanguageVersion => Some("std"),
+			Self::CheckedDivision => Some("checked-div"),
+			Self::TimeTrace => Some("time-trace"),
+			Self::AbiCheck => Some("abi-check"),
+			Self::DumpScopes => Some("dump-scopes"),
+			Self::OperatorOverloading => Some("operator-overloading"),
+			Self::NoEntry => Some("no-entry"),
+			Self::Strict => Some("strict"),
+			Self::PrintPhases => Some("print-phases"),
+			Self::NarrowGlobals => Some("narrow-globals"),
+			Self::OverflowCheckConst => Some("overflow-check-const"),
+			Self::DumpIrAfter => Some("dump-ir-after"),
+			Self::Timeout => Some("timeout"),
+			Self::DumpMetadata => Some("dump-metadata"),
+			Self::EmitMetadataJson => Some("emit-metadata-json"),
+			Self::Suppress => Some("suppress"),
 		}
 	}
 
@@ -140,9 +428,61 @@ impl CompilerOptionToken {
 			Self::PrintAstNodesAfterFunctionSignatureBuild => Some("Print AST nodes after global function signatures have been built"),
 			Self::TargetTriplet => Some("Set the target triplet for the compiler"),
 			Self::LinkCommand => Some("Set the link command to use for linking the resulting object files"),
+			Self::Color => Some("Set when error messages should be colored, one of always, auto or never, auto colors when printing to a terminal"),
+			Self::NoColor => Some("Do not color error messages, same as --color never"),
+			Self::PositionIndependentCode => Some("Generate position independent code"),
+			Self::NoPositionIndependentCode => Some("Do not generate position independent code"),
+			Self::CodeModel => Some("Set the code model, one of default, tiny, small, kernel, medium or large"),
+			Self::StrictGlobals => Some("Error when a global variable that is not assigned to in the file is used"),
+			Self::Check => Some("Only check source files for errors, no object files are built and nothing is linked"),
+			Self::ErrorFormat => Some("Set how errors are printed, one of human or json"),
+			Self::TrapMode => Some("Set what happens when a runtime check fails, abort calls __bcz_panic and trap executes a trap instruction"),
+			Self::DumpSymbols => Some("Print the name and linkage of each function and global variable in the built LLVM module"),
+			Self::Freestanding => Some("Compile for a target without an operating system or C runtime, the entry point is called from _start and linked functions are not imported from DLLs"),
+			Self::NoAsserts => Some("Compile out @runtime_assert checks"),
+			Self::DefineFromEnv => Some("Define a global constant for each environment variable with a name starting with the given prefix, the values must be integers"),
+			Self::DumpTargetDataLayout => Some("Print the data layout string of the target machine being compiled for"),
+			Self::LibrarySearchPath => Some("Add a directory for the linker to search for libraries in"),
+			Self::LinkLibrary => Some("Link to a library by name, such as m for libm"),
+			Self::Sysroot => Some("Set the directory the linker uses as the root for headers and libraries"),
+			Self::Deterministic => Some("Record source filenames relative to the source directory so object files do not depend on where the project is on the host"),
+			Self::KeepGoing => Some("Keep compiling the remaining files after a file fails to compile, then report how many files failed"),
+			Self::EmitDepFile => Some("Write a Makefile style dependency file listing the source files the output depends on"),
+			Self::FastMath => Some("Allow float operations to be optimized in ways that do not follow IEEE 754, such as assuming there are no NaNs or infinities"),
+			Self::IncludeDirectory => Some("Add a directory to search for imported files in after the directory of the importing file"),
+			Self::IrComments => Some("Append the source line and column to the names of values in the generated IR, such as add_temp_L3C5"),
+			Self::MaxFunctionParameters => Some("Set the most parameters a function can have, for targets with stricter ABIs"),
+			Self::MaxFunctionArguments => Some("Set the most arguments a function call can have, for targets with stricter ABIs"),
+			Self::LanguageVersion => Some("Set the version of the language to compile, features added in a later version are errors, one of 1 or 2"),
+			Self::CheckedDivision => Some("Check for division and modulo by zero at runtime, failing as set by --trap-mode instead of being undefined behavior"),
+			Self::TimeTrace => Some("Write how long each phase of compilation took to a Chrome tracing JSON file that can be loaded in chrome://tracing"),
+			Self::AbiCheck => Some("Check that the parameter and return widths of linked functions are representable in the C ABI of the target"),
+			Self::DumpScopes => Some("Print the local variables at each scope level as blocks are entered and exited and variables are created while building functions"),
+			Self::OperatorOverloading => Some("Build binary operators as calls to a global function such as __add(a, b) if the file defines one, otherwise the built in int operator is used"),
+			Self::NoEntry => Some("Compile a library to be linked elsewhere, no entry point is required or emitted and the object files are not linked"),
+			Self::Strict => Some("Treat warnings as errors"),
+			Self::PrintPhases => Some("Print each phase of compilation as it runs for each file and if it was skipped"),
+			Self::NarrowGlobals => Some("Store global variables that are constant ints in the narrowest int type that holds their value instead of a full width int"),
+			Self::OverflowCheckConst => Some("Error when folding constant arithmetic overflows a signed int instead of wrapping"),
+			Self::DumpIrAfter => Some("Print the LLVM IR of each module after a phase, codegen prints it as built and opt prints it as it is emitted"),
+			Self::Timeout => Some("Stop compiling with an error if compilation takes longer than the given number of milliseconds"),
+			Self::DumpMetadata => Some("Print each metadata keyword such as @entry_point or @link found by the parser with its position and what it applies to"),
+			Self::EmitMetadataJson => Some("Write a JSON file that describes each global, including if it is a function, its parameter count and if it is linked, exported or the entry point"),
+			Self::Suppress => Some("Do not print errors or warnings with the given code such as E0103, suppressed errors still stop compilation"),
 		}
 	}
 
+	/// If the option takes the argument after it as its value.
+	const fn takes_value(&self) -> bool {
+		matches!(
+			self,
+			Self::SetPrimaryOutput | Self::SetSourceHomeFilepath | Self::SetBinaryHomeFilepath | Self::TargetTriplet | Self::LinkCommand | Self::Color |
+			Self::CodeModel | Self::ErrorFormat | Self::TrapMode | Self::DefineFromEnv | Self::LibrarySearchPath | Self::LinkLibrary | Self::Sysroot |
+			Self::EmitDepFile | Self::IncludeDirectory | Self::MaxFunctionParameters | Self::MaxFunctionArguments | Self::LanguageVersion |
+			Self::TimeTrace | Self::DumpIrAfter | Self::Timeout | Self::EmitMetadataJson | Self::Suppress
+		)
+	}
+
 	/// Get a short name (without the preceding dash) to token mapping.
 	fn get_short_options() -> HashMap<&'static str, Self> {
 		Self::iter()
@@ -162,6 +502,121 @@ impl CompilerOptionToken {
 	}
 }
 
+/// Replaces each `@file` argument with the whitespace separated arguments read from the file, the file can contain more `@file` arguments.
+/// Each input filepath argument ending in `.bcz.toml` is a manifest file and is replaced with the arguments the manifest describes.
+pub fn expand_response_files(arguments: impl IntoIterator<Item = Box<str>>, depth: usize) -> Result<Vec<Box<str>>, Error> {
+	let short_options = CompilerOptionToken::get_short_options();
+	let long_options = CompilerOptionToken::get_long_options();
+	let mut expanded_arguments = Vec::new();
+	let mut is_option_value = false;
+	for argument in arguments {
+		// Options and the values of options are not manifests, such as `trace.toml` in `--time-trace trace.toml`
+		let is_input_filepath = !is_option_value && !argument.starts_with('-');
+		is_option_value = match (argument.strip_prefix("--"), argument.strip_prefix('-')) {
+			(Some(long_name), _) => long_options.get(long_name).is_some_and(CompilerOptionToken::takes_value),
+			(None, Some(short_name)) => short_options.get(short_name).is_some_and(CompilerOptionToken::takes_value),
+			(None, None) => false,
+		};
+		if is_input_filepath && argument.ends_with(".bcz.toml") {
+			expanded_arguments.extend(expand_manifest(&argument)?);
+			continue;
+		}
+		let response_filepath = match argument.strip_prefix('@') {
+			Some(response_filepath) => response_filepath,
+			None => {
+				expanded_arguments.push(argument);
+				continue;
+			}
+		};
+		if depth >= MAX_RESPONSE_FILE_DEPTH {
+			return Err(Error::ResponseFileTooDeep(response_filepath.into()));
+		}
+		let file_content = read_to_string(response_filepath).map_err(Error::CouldNotOpenFile)?;
+		expanded_arguments.extend(expand_response_files(file_content.split_whitespace().map(Box::from).collect::<Vec<_>>(), depth + 1)?);
+	}
+	Ok(expanded_arguments)
+}
+
+/// A manifest file, the `files` key lists the files to compile relative to the manifest and each other key is the long name of a compiler option.
+#[derive(Deserialize)]
+struct Manifest {
+	#[serde(default)]
+	files: ManifestStrings,
+	#[serde(flatten)]
+	options: BTreeMap<String, ManifestOptionValue>,
+}
+
+/// A manifest value that is one string or an array of strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ManifestStrings {
+	One(String),
+	Many(Vec<String>),
+}
+
+impl Default for ManifestStrings {
+	fn default() -> Self {
+		Self::Many(Vec::new())
+	}
+}
+
+impl ManifestStrings {
+	fn into_vec(self) -> Vec<String> {
+		match self {
+			Self::One(string) => vec![string],
+			Self::Many(strings) => strings,
+		}
+	}
+}
+
+/// The value of a compiler option in a manifest, `true` passes the option and strings pass the option once with each string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ManifestOptionValue {
+	Flag(bool),
+	Strings(ManifestStrings),
+}
+
+/// Reads a manifest file and returns the compiler arguments it describes.
+fn expand_manifest(manifest_filepath: &str) -> Result<Vec<Box<str>>, Error> {
+	let file_content = read_to_string(manifest_filepath).map_err(Error::CouldNotOpenFile)?;
+	let manifest: Manifest = toml::from_str(&file_content)
+		.map_err(|error| Error::InvalidManifest(manifest_filepath.into(), error.message().into()))?;
+	let long_options = CompilerOptionToken::get_long_options();
+	let mut arguments = Vec::new();
+	for (key, value) in manifest.options {
+		if !long_options.contains_key(key.as_str()) {
+			return Err(Error::InvalidManifestKey(key));
+		}
+		match value {
+			ManifestOptionValue::Flag(true) => arguments.push(format!("--{key}").into()),
+			ManifestOptionValue::Flag(false) => {}
+			ManifestOptionValue::Strings(strings) => for string in strings.into_vec() {
+				arguments.push(format!("--{key}").into());
+				arguments.push(string.into());
+			}
+		}
+	}
+	// Files are relative to the directory containing the manifest rather than the working directory
+	let manifest_directory = Path::new(manifest_filepath).parent().unwrap_or(Path::new(""));
+	for file in manifest.files.into_vec() {
+		arguments.push(manifest_directory.join(file).to_string_lossy().into());
+	}
+	Ok(arguments)
+}
+
+/// Parses the value of a defined constant, this is a decimal integer that may be negative or a hexadecimal integer starting with `0x`.
+fn parse_define_value(value: &str) -> Option<u64> {
+	let value = value.trim();
+	if let Some(hexadecimal) = value.strip_prefix("0x") {
+		return u64::from_str_radix(hexadecimal, 16).ok();
+	}
+	match value.strip_prefix('-') {
+		Some(_) => value.parse::<i64>().ok().map(|value| value as u64),
+		None => value.parse().ok(),
+	}
+}
+
 /// Process a list of compiler arguments.
 pub fn process_arguments<'a>(arguments: &[&'a str], data_out: &mut CompilerArgumentsData<'a>) -> Result<(), Error> {
 	let mut argument_processing_state = ArgumentProcessingState::Normal;
@@ -235,6 +690,47 @@ pub fn process_arguments<'a>(arguments: &[&'a str], data_out: &mut CompilerArgum
 					CompilerOptionToken::PrintAstNodesAfterFunctionSignatureBuild => data_out.dump_llvm_module_after_function_signatures_build = true,
 					CompilerOptionToken::TargetTriplet => argument_processing_state = ArgumentProcessingState::SetTargetTriplet,
 					CompilerOptionToken::LinkCommand => argument_processing_state = ArgumentProcessingState::SetLinkCommand,
+					CompilerOptionToken::Color => argument_processing_state = ArgumentProcessingState::SetColorChoice,
+					CompilerOptionToken::NoColor => data_out.color_choice = ColorChoice::Never,
+					CompilerOptionToken::PositionIndependentCode => data_out.position_independent_code = Some(true),
+					CompilerOptionToken::NoPositionIndependentCode => data_out.position_independent_code = Some(false),
+					CompilerOptionToken::CodeModel => argument_processing_state = ArgumentProcessingState::SetCodeModel,
+					CompilerOptionToken::StrictGlobals => data_out.strict_globals = true,
+					CompilerOptionToken::Check => data_out.check_only = true,
+					CompilerOptionToken::ErrorFormat => argument_processing_state = ArgumentProcessingState::SetErrorFormat,
+					CompilerOptionToken::TrapMode => argument_processing_state = ArgumentProcessingState::SetTrapMode,
+					CompilerOptionToken::DumpSymbols => data_out.dump_symbols = true,
+					CompilerOptionToken::Freestanding => data_out.freestanding = true,
+					CompilerOptionToken::NoAsserts => data_out.no_asserts = true,
+					CompilerOptionToken::DefineFromEnv => argument_processing_state = ArgumentProcessingState::SetDefineFromEnvPrefix,
+					CompilerOptionToken::DumpTargetDataLayout => data_out.dump_target_data_layout = true,
+					CompilerOptionToken::LibrarySearchPath => argument_processing_state = ArgumentProcessingState::AddLibrarySearchPath,
+					CompilerOptionToken::LinkLibrary => argument_processing_state = ArgumentProcessingState::AddLinkedLibrary,
+					CompilerOptionToken::Sysroot => argument_processing_state = ArgumentProcessingState::SetSysroot,
+					CompilerOptionToken::Deterministic => data_out.deterministic = true,
+					CompilerOptionToken::KeepGoing => data_out.keep_going = true,
+					CompilerOptionToken::EmitDepFile => argument_processing_state = ArgumentProcessingState::SetDepFilepath,
+					CompilerOptionToken::FastMath => data_out.fast_math = true,
+					CompilerOptionToken::IncludeDirectory => argument_processing_state = ArgumentProcessingState::AddIncludeDirectory,
+					CompilerOptionToken::IrComments => data_out.ir_comments = true,
+					CompilerOptionToken::MaxFunctionParameters => argument_processing_state = ArgumentProcessingState::SetMaxFunctionParameters,
+					CompilerOptionToken::MaxFunctionArguments => argument_processing_state = ArgumentProcessingState::SetMaxFunctionArguments,
+					CompilerOptionToken::LanguageVersion => argument_processing_state = ArgumentProcessingState::SetLanguageVersion,
+					CompilerOptionToken::CheckedDivision => data_out.checked_division = true,
+					CompilerOptionToken::TimeTrace => argument_processing_state = ArgumentProcessingState::SetTimeTraceFilepath,
+					CompilerOptionToken::AbiCheck => data_out.abi_check = true,
+					CompilerOptionToken::DumpScopes => data_out.dump_scopes = true,
+					CompilerOptionToken::OperatorOverloading => data_out.operator_overloading = true,
+					CompilerOptionToken::NoEntry => data_out.no_entry = true,
+					CompilerOptionToken::Strict => data_out.strict = true,
+					CompilerOptionToken::PrintPhases => data_out.print_phases = true,
+					CompilerOptionToken::NarrowGlobals => data_out.narrow_globals = true,
+					CompilerOptionToken::OverflowCheckConst => data_out.overflow_check_const = true,
+					CompilerOptionToken::DumpIrAfter => argument_processing_state = ArgumentProcessingState::SetDumpIrAfter,
+					CompilerOptionToken::Timeout => argument_processing_state = ArgumentProcessingState::SetTimeout,
+					CompilerOptionToken::DumpMetadata => data_out.dump_metadata = true,
+					CompilerOptionToken::EmitMetadataJson => argument_processing_state = ArgumentProcessingState::SetMetadataJsonFilepath,
+					CompilerOptionToken::Suppress => argument_processing_state = ArgumentProcessingState::AddSuppressedCode,
 				}
 			}
 			ArgumentProcessingState::SetPrimaryOutput => {
@@ -257,6 +753,96 @@ pub fn process_arguments<'a>(arguments: &[&'a str], data_out: &mut CompilerArgum
 				data_out.link_command = argument.into();
 				argument_processing_state = ArgumentProcessingState::Normal;
 			}
+			ArgumentProcessingState::SetColorChoice => {
+				data_out.color_choice = ColorChoice::from_name(argument).ok_or_else(|| Error::InvalidColorChoice(argument.into()))?;
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetCodeModel => {
+				data_out.code_model = match argument {
+					"default" => CodeModel::Default,
+					"tiny" => CodeModel::Tiny,
+					"small" => CodeModel::Small,
+					"kernel" => CodeModel::Kernel,
+					"medium" => CodeModel::Medium,
+					"large" => CodeModel::Large,
+					_ => return Err(Error::InvalidCodeModel(argument.into())),
+				};
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetErrorFormat => {
+				data_out.error_format = ErrorFormat::from_name(argument).ok_or_else(|| Error::InvalidErrorFormat(argument.into()))?;
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetTrapMode => {
+				data_out.trap_mode = match argument {
+					"abort" => TrapMode::Abort,
+					"trap" => TrapMode::Trap,
+					_ => return Err(Error::InvalidTrapMode(argument.into())),
+				};
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetDefineFromEnvPrefix => {
+				for (name, value) in vars() {
+					if !name.starts_with(argument) {
+						continue;
+					}
+					let parsed_value = parse_define_value(&value).ok_or_else(|| Error::InvalidDefineValue(name.clone(), value.clone()))?;
+					data_out.defined_constants.insert(name.into(), parsed_value);
+				}
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::AddLibrarySearchPath => {
+				data_out.library_search_paths.push(argument);
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::AddLinkedLibrary => {
+				data_out.linked_libraries.push(argument);
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetSysroot => {
+				data_out.sysroot = Some(argument);
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetDepFilepath => {
+				data_out.dep_filepath = Some(argument);
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::AddIncludeDirectory => {
+				data_out.include_directories.push(argument);
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetMaxFunctionParameters => {
+				data_out.max_function_parameters = argument.parse().map_err(|_| Error::InvalidFunctionLimit(argument.into()))?;
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetMaxFunctionArguments => {
+				data_out.max_function_arguments = argument.parse().map_err(|_| Error::InvalidFunctionLimit(argument.into()))?;
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetLanguageVersion => {
+				data_out.language_version = LanguageVersion::from_name(argument).ok_or_else(|| Error::InvalidLanguageVersion(argument.into()))?;
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetTimeTraceFilepath => {
+				data_out.time_trace_filepath = Some(argument);
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetDumpIrAfter => {
+				data_out.dump_ir_after = Some(IrPhase::from_name(argument).ok_or_else(|| Error::InvalidIrPhase(argument.into()))?);
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetTimeout => {
+				data_out.timeout = Some(argument.parse().map_err(|_| Error::InvalidTimeout(argument.into()))?);
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::SetMetadataJsonFilepath => {
+				data_out.metadata_json_filepath = Some(argument);
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
+			ArgumentProcessingState::AddSuppressedCode => {
+				data_out.suppressed_codes.insert(argument.into());
+				argument_processing_state = ArgumentProcessingState::Normal;
+			}
 		}
 	}
 	// Make sure that an option that requires a continuation option was not at the end to the argument list
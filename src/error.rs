@@ -1,6 +1,26 @@
-use std::{fmt::Display, io};
+use std::{fmt::Display, io, num::NonZeroUsize, path::PathBuf};
 
-use crate::token::{OperatorSymbol, Separator};
+use crate::{compiler_arguments::LanguageVersion, token::{OperatorSymbol, Separator}};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// How errors are printed.
+pub enum ErrorFormat {
+	/// A message for people to read, with the line of source code the error is on.
+	Human,
+	/// A single line JSON object for tools to read.
+	Json,
+}
+
+impl ErrorFormat {
+	/// Get an error format from its name as used in compiler arguments.
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"human" => Some(Self::Human),
+			"json" => Some(Self::Json),
+			_ => None,
+		}
+	}
+}
 
 pub enum Error {
 	InvalidShortArgument(String),
@@ -12,13 +32,13 @@ pub enum Error {
 	InvalidTokenStartChar(char),
 	InvalidNumericalLiteralBase(char),
 	InvalidDigitForBase(char, u8),
-	NumericalLiteralTooLarge,
-	InvalidKeyword(String),
-	InvalidOperator(String),
+	NumericalLiteralTooLarge(u8),
+	InvalidKeyword(String, Option<String>),
+	InvalidOperator(String, Option<String>),
 	TooManyOpenParentheses,
 	TooManyCloseParentheses,
 	BlankExpression,
-	ParenthesisMismatch(Separator, Separator),
+	ParenthesisMismatch(Separator, (NonZeroUsize, NonZeroUsize), Separator),
 	NoOperatorBase,
 	BinaryOperatorNotUsedOnExpressions,
 	TernaryOperatorNotUsedOnExpressions,
@@ -72,6 +92,171 @@ pub enum Error {
 	UnsupportedOS(String),
 	InvalidTargetTriplet(String),
 	ErrorWhileLinking(Option<i32>),
+	MemberAccessNotSupported,
+	InvalidColorChoice(String),
+	InvalidCodeModel(String),
+	UndefinedGlobal(String),
+	InvalidNumericSuffix(String),
+	InvalidErrorFormat(String),
+	InvalidTrapMode(String),
+	MalformedOperatorArity(usize, usize),
+	ResponseFileTooDeep(String),
+	NotAConstantExpression,
+	NumericalLiteralBaseOutOfRange(String),
+	ParameterWithoutDefault,
+	DefaultParameterNotConstant,
+	UndefinedLabel(String),
+	StaticAssertionFailed,
+	StaticAssertionNotConstant,
+	InvalidManifest(String, String),
+	InvalidManifestKey(String),
+	InvalidDefineValue(String, String),
+	UseOfVoidValue,
+	NoEntryPoint,
+	FilesFailedToCompile(usize),
+	CouldNotWriteDepFile(io::Error),
+	ExpressionTooDeep(usize),
+	ImportNotFound(String, Box<[PathBuf]>),
+	InvalidFloatLiteral(String),
+	InvalidFunctionLimit(String),
+	AssignmentToConstant(String),
+	InvalidLanguageVersion(String),
+	FeatureNotInLanguageVersion(&'static str, LanguageVersion),
+	MissingReturn,
+	CouldNotWriteTimeTrace(io::Error),
+	UnsupportedAbiType(u32),
+	ReturnedStackAddress,
+	ExpectedRange,
+	ConstantOverflow,
+	InvalidIrPhase(String),
+	InvalidTimeout(String),
+	CompilationTimedOut,
+	CouldNotWriteMetadataJson(io::Error),
+	ArrayAssignmentMismatch,
+	MissingFunctionArguments(usize, usize),
+	DefaultParameterInLocalFunction,
+}
+
+impl Error {
+	/// Get the stable code of this error, such as `E0012`.
+	///
+	/// Codes are never reused or changed, new variants get the next unused code.
+	pub fn code(&self) -> &'static str {
+		match self {
+			Self::InvalidShortArgument(..) => "E0001",
+			Self::InvalidLongArgument(..) => "E0002",
+			Self::NoOptionContinuation => "E0003",
+			Self::CouldNotOpenFile(..) => "E0004",
+			Self::CouldNotReadLine => "E0005",
+			Self::FeatureNotYetImplemented(..) => "E0006",
+			Self::InvalidTokenStartChar(..) => "E0007",
+			Self::InvalidNumericalLiteralBase(..) => "E0008",
+			Self::InvalidDigitForBase(..) => "E0009",
+			Self::NumericalLiteralTooLarge(..) => "E0010",
+			Self::InvalidKeyword(..) => "E0011",
+			Self::InvalidOperator(..) => "E0012",
+			Self::TooManyOpenParentheses => "E0013",
+			Self::TooManyCloseParentheses => "E0014",
+			Self::BlankExpression => "E0015",
+			Self::ParenthesisMismatch(..) => "E0016",
+			Self::NoOperatorBase => "E0017",
+			Self::BinaryOperatorNotUsedOnExpressions => "E0018",
+			Self::TernaryOperatorNotUsedOnExpressions => "E0019",
+			Self::OperatorUsedOnNothing => "E0020",
+			Self::InvalidPrefixOperatorSymbol(..) => "E0021",
+			Self::InvalidInfixOperatorSymbol(..) => "E0022",
+			Self::InvalidTernaryOperator => "E0023",
+			Self::FunctionParametersWithoutBody => "E0024",
+			Self::UnterminatedCharLiteral => "E0025",
+			Self::EmptyCharLiteral => "E0026",
+			Self::NothingEscaped => "E0027",
+			Self::InvalidEscapeSequence(..) => "E0028",
+			Self::MultipleCharsInCharLiteral => "E0029",
+			Self::UnterminatedStringLiteral => "E0030",
+			Self::MetadataItemWithoutChildNode => "E0031",
+			Self::GlobalAugmentedOperator => "E0032",
+			Self::DiscardedGlobalFunctionCall => "E0033",
+			Self::GlobalAssignmentToNonIdentifier => "E0034",
+			Self::GlobalVariableConflict(..) => "E0035",
+			Self::ExpectedIdentifier => "E0036",
+			Self::InvalidDependency => "E0037",
+			Self::TooManyFunctionParameters => "E0038",
+			Self::GlobalLValueAssignment => "E0039",
+			Self::LValueFunctionCall => "E0040",
+			Self::LValueFunctionDefinition => "E0041",
+			Self::MultipleEntryPoints => "E0042",
+			Self::TooManyFunctionArguments => "E0043",
+			Self::InvalidTypeWidth => "E0044",
+			Self::UnableToWriteObject => "E0045",
+			Self::CouldNotGetTarget(..) => "E0046",
+			Self::InvalidArchitectureBitWidth(..) => "E0047",
+			Self::UnableToEmitObjectFile(..) => "E0048",
+			Self::InvalidLValue => "E0049",
+			Self::VoidParameter => "E0050",
+			Self::DivisionByZero => "E0051",
+			Self::ModuloByZero => "E0052",
+			Self::NullPointerDereference => "E0053",
+			Self::InvalidBuiltInFunctionArgumentCount => "E0054",
+			Self::ConstValueRequired => "E0055",
+			Self::UnmatchedTernary => "E0056",
+			Self::KeywordWithTwoChildren => "E0057",
+			Self::GlobalOperatorNotConstEvaluated => "E0058",
+			Self::NotUsedInsideLoop => "E0059",
+			Self::InvalidExport => "E0060",
+			Self::UnterminatedBlockComment => "E0061",
+			Self::ShouldNotHaveChild => "E0062",
+			Self::InvalidSystemConstant => "E0063",
+			Self::OnlyUsableInStandardLibrary => "E0064",
+			Self::InvalidFilepath => "E0065",
+			Self::UnsupportedCPU(..) => "E0066",
+			Self::UnsupportedOS(..) => "E0067",
+			Self::InvalidTargetTriplet(..) => "E0068",
+			Self::ErrorWhileLinking(..) => "E0069",
+			Self::MemberAccessNotSupported => "E0070",
+			Self::InvalidColorChoice(..) => "E0071",
+			Self::InvalidCodeModel(..) => "E0072",
+			Self::UndefinedGlobal(..) => "E0073",
+			Self::InvalidNumericSuffix(..) => "E0074",
+			Self::InvalidErrorFormat(..) => "E0075",
+			Self::InvalidTrapMode(..) => "E0076",
+			Self::MalformedOperatorArity(..) => "E0077",
+			Self::ResponseFileTooDeep(..) => "E0078",
+			Self::NotAConstantExpression => "E0079",
+			Self::NumericalLiteralBaseOutOfRange(..) => "E0080",
+			Self::ParameterWithoutDefault => "E0081",
+			Self::DefaultParameterNotConstant => "E0082",
+			Self::UndefinedLabel(..) => "E0083",
+			Self::StaticAssertionFailed => "E0084",
+			Self::StaticAssertionNotConstant => "E0085",
+			Self::InvalidManifest(..) => "E0086",
+			Self::InvalidManifestKey(..) => "E0087",
+			Self::InvalidDefineValue(..) => "E0088",
+			Self::UseOfVoidValue => "E0089",
+			Self::NoEntryPoint => "E0090",
+			Self::FilesFailedToCompile(..) => "E0091",
+			Self::CouldNotWriteDepFile(..) => "E0092",
+			Self::ExpressionTooDeep(..) => "E0093",
+			Self::ImportNotFound(..) => "E0094",
+			Self::InvalidFloatLiteral(..) => "E0095",
+			Self::InvalidFunctionLimit(..) => "E0096",
+			Self::AssignmentToConstant(..) => "E0097",
+			Self::InvalidLanguageVersion(..) => "E0098",
+			Self::FeatureNotInLanguageVersion(..) => "E0099",
+			Self::MissingReturn => "E0100",
+			Self::CouldNotWriteTimeTrace(..) => "E0101",
+			Self::UnsupportedAbiType(..) => "E0102",
+			Self::ReturnedStackAddress => "E0103",
+			Self::ExpectedRange => "E0104",
+			Self::ConstantOverflow => "E0105",
+			Self::InvalidIrPhase(..) => "E0106",
+			Self::InvalidTimeout(..) => "E0107",
+			Self::CompilationTimedOut => "E0108",
+			Self::CouldNotWriteMetadataJson(..) => "E0109",
+			Self::ArrayAssignmentMismatch => "E0110",
+			Self::MissingFunctionArguments(..) => "E0111",
+			Self::DefaultParameterInLocalFunction => "E0112",
+		}
+	}
 }
 
 impl Display for Error {
@@ -86,13 +271,16 @@ impl Display for Error {
 			Self::InvalidTokenStartChar(c) => write!(f, "Invalid token start character '{c}'"),
 			Self::InvalidNumericalLiteralBase(c) => write!(f, "Invalid numerical literal base \"0{c}\""),
 			Self::InvalidDigitForBase(c, base) => write!(f, "Invalid digit '{c}' for base {base}"),
-			Self::NumericalLiteralTooLarge => write!(f, "Numerical literal too large"),
-			Self::InvalidKeyword(keyword) => write!(f, "Invalid keyword \"{keyword}\""),
-			Self::InvalidOperator(operator) => write!(f, "Invalid operator \"{operator}\""),
+			Self::NumericalLiteralTooLarge(bit_width) => write!(f, "Numerical literal too large, exceeds the {bit_width}-bit maximum"),
+			Self::InvalidKeyword(keyword, None) => write!(f, "Invalid keyword \"{keyword}\""),
+			Self::InvalidKeyword(keyword, Some(suggestion)) => write!(f, "Invalid keyword \"{keyword}\", did you mean \"{suggestion}\"?"),
+			Self::InvalidOperator(operator, None) => write!(f, "Invalid operator \"{operator}\""),
+			Self::InvalidOperator(operator, Some(suggestion)) => write!(f, "Invalid operator \"{operator}\", did you mean \"{suggestion}\"?"),
 			Self::TooManyCloseParentheses => write!(f, "Too many close parentheses"),
 			Self::TooManyOpenParentheses => write!(f, "Too many open parentheses"),
 			Self::BlankExpression => write!(f, "Blank expression"),
-			Self::ParenthesisMismatch(open, close) => write!(f, "Open '{}' mismatched with close '{}'", open.get_symbol(), close.get_symbol()),
+			Self::ParenthesisMismatch(open, (open_line, open_column), close) =>
+				write!(f, "Open '{}' at {open_line}:{open_column} mismatched with close '{}'", open.get_symbol(), close.get_symbol()),
 			Self::NoOperatorBase => write!(f, "No operator base"),
 			Self::BinaryOperatorNotUsedOnExpressions => write!(f, "Binary operator used on non-expressions"),
 			Self::InvalidPrefixOperatorSymbol(symbol) => write!(f, "Invalid prefix operator symbol base \"{}\"", symbol.get_symbol()),
@@ -147,6 +335,76 @@ impl Display for Error {
 			Self::InvalidTargetTriplet(triplet) => write!(f, "Invalid target triplet: {triplet}"),
 			Self::ErrorWhileLinking(None) => write!(f, "Error while linking"),
 			Self::ErrorWhileLinking(Some(code)) => write!(f, "Error while linking with code {code}"),
+			Self::MemberAccessNotSupported => write!(f, "Member access not yet supported for this type"),
+			Self::InvalidColorChoice(choice) => write!(f, "Invalid color choice \"{choice}\", expected always, auto or never"),
+			Self::UndefinedGlobal(name) => write!(f, "Use of undefined global variable {name}"),
+			Self::InvalidCodeModel(code_model) => write!(f, "Invalid code model \"{code_model}\", expected default, tiny, small, kernel, medium or large"),
+			Self::ResponseFileTooDeep(filepath) => write!(f, "Response file @{filepath} is nested too deeply, response files may be including each other"),
+			Self::NotAConstantExpression => write!(f, "Expression marked @const can not be evaluated at compile time"),
+			Self::NumericalLiteralBaseOutOfRange(base) => write!(f, "Invalid numerical literal base \"{base}\", expected a base from 2 to 36"),
+			Self::ParameterWithoutDefault => write!(f, "Parameter without a default value after a parameter with a default value"),
+			Self::DefaultParameterNotConstant => write!(f, "Default parameter value can not be evaluated at compile time"),
+			Self::UndefinedLabel(label) => write!(f, "Undefined label \"{label}\", a @break to a label must be inside a @label block with that name"),
+			Self::StaticAssertionFailed => write!(f, "Static assertion failed"),
+			Self::StaticAssertionNotConstant => write!(f, "Static assertion condition can not be evaluated at compile time"),
+			Self::InvalidManifest(filepath, message) => write!(f, "Invalid manifest {filepath}: {message}"),
+			Self::InvalidManifestKey(key) => write!(f, "Unknown manifest key \"{key}\", expected files or the long name of a compiler option"),
+			Self::InvalidDefineValue(name, value) => write!(f, "Value \"{value}\" of defined constant {name} is not an integer"),
+			Self::UseOfVoidValue => write!(f, "Value of expression is void and can not be used as an operand"),
+			Self::NoEntryPoint => write!(f, "No entry point, a function marked with @entry_point is required to link an executable"),
+			Self::FilesFailedToCompile(1) => write!(f, "1 file failed to compile"),
+			Self::FilesFailedToCompile(count) => write!(f, "{count} files failed to compile"),
+			Self::CouldNotWriteDepFile(error) => write!(f, "Could not write dependency file: {error}"),
+			Self::ExpressionTooDeep(max_depth) => write!(f, "Expression is nested more than {max_depth} levels deep"),
+			Self::ImportNotFound(import_name, searched_directories) => {
+				write!(f, "Could not find imported file \"{import_name}\", searched in")?;
+				for (index, directory) in searched_directories.iter().enumerate() {
+					match index {
+						0 => write!(f, " \"{}\"", directory.display())?,
+						_ => write!(f, ", \"{}\"", directory.display())?,
+					}
+				}
+				Ok(())
+			}
+			Self::InvalidFloatLiteral(literal) => write!(f, "Invalid float literal \"{literal}\""),
+			Self::InvalidFunctionLimit(limit) => write!(f, "Invalid function limit \"{limit}\", expected a number from 0 to 65535"),
+			Self::AssignmentToConstant(name) => write!(f, "Can not assign to the constant global \"{name}\""),
+			Self::MissingReturn => write!(f, "Function yields a value on some paths but not on this one"),
+			Self::CouldNotWriteTimeTrace(error) => write!(f, "Could not write time trace file: {error}"),
+			Self::UnsupportedAbiType(bit_width) => write!(f, "A {bit_width} bit integer cannot be passed to or returned from a linked function in the C ABI of the target"),
+			Self::ReturnedStackAddress => write!(f, "The address of a local variable is returned from its function, it will not be valid after the function returns"),
+			Self::ExpectedRange => write!(f, "Expected a range such as `0..5`"),
+			Self::ConstantOverflow => write!(f, "Constant arithmetic overflows a signed int"),
+			Self::InvalidIrPhase(phase) => write!(f, "Invalid IR phase \"{phase}\", expected codegen or opt"),
+			Self::InvalidTimeout(timeout) => write!(f, "Invalid timeout \"{timeout}\", expected a number of milliseconds"),
+			Self::CompilationTimedOut => write!(f, "Compilation took longer than the time limit set with --timeout"),
+			Self::CouldNotWriteMetadataJson(error) => write!(f, "Could not write metadata JSON file: {error}"),
+			Self::ArrayAssignmentMismatch => write!(f, "An array can only be assigned an array with the same entry count and width"),
+			Self::MissingFunctionArguments(expected, found) =>
+				write!(f, "Function with {expected} parameters called with {found} arguments, only parameters with a default value can be omitted"),
+			Self::DefaultParameterInLocalFunction =>
+				write!(f, "Only global functions can have default parameter values, calls through a local variable or address can not fill them in"),
+			Self::InvalidLanguageVersion(version) => write!(f, "Invalid language version \"{version}\", expected 1 or 2"),
+			Self::FeatureNotInLanguageVersion(feature, version) =>
+				write!(f, "{feature} require language version {} or later, use --std {}", version.name(), version.name()),
+			Self::MalformedOperatorArity(expected, found) => write!(f, "Operator expected {expected} operands but has {found}"),
+			Self::InvalidTrapMode(trap_mode) => write!(f, "Invalid trap mode \"{trap_mode}\", expected abort or trap"),
+			Self::InvalidErrorFormat(format) => write!(f, "Invalid error format \"{format}\", expected human or json"),
+			Self::InvalidNumericSuffix(suffix) => write!(f, "Invalid numeric literal suffix \"{suffix}\", expected i or u followed by 8, 16, 32 or 64"),
 		}
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Error;
+
+	// Error::code matches on every variant without a wildcard so a variant without a code does not compile
+	#[test]
+	fn codes() {
+		assert_eq!(Error::InvalidShortArgument("q".into()).code(), "E0001");
+		assert_eq!(Error::NoOptionContinuation.code(), "E0003");
+		assert_eq!(Error::InvalidKeyword("@entry_pint".into(), None).code(), "E0011");
+		assert_eq!(Error::TooManyCloseParentheses.code(), "E0014");
+	}
+}
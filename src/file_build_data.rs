@@ -1,12 +1,93 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::{HashMap, HashSet}, hash::{DefaultHasher, Hash, Hasher}, iter::repeat_n, path::{Path, PathBuf}};
 
-use llvm_nhb::value::Value;
+use llvm_nhb::{types::Type, value::Value};
 
-use crate::built_value::BuiltRValue;
+use crate::{built_value::BuiltRValue, ParameterDefaults};
 
 pub struct FileBuildData<'a, 'b> {
 	pub built_globals: HashMap<Box<str>, BuiltRValue<'a>>,
 	pub built_global_function_signatures: HashMap<Box<str>, Value<'a, 'b>>,
 	pub entrypoint: Option<(Value<'a, 'b>, Box<str>)>,
 	pub filepath: &'a PathBuf,
-}
\ No newline at end of file
+	/// How many unnamed functions have been built in this file, used to give each one a unique name.
+	pub unnamed_function_count: usize,
+	/// The default values of the parameters of global functions that have defaults, `None` for parameters without a default.
+	pub default_arguments: HashMap<Box<str>, ParameterDefaults>,
+	/// The names of all the globals in this file, globals are constant so these names can't be assigned to in functions.
+	pub global_names: HashSet<Box<str>>,
+	/// The names of variables that have their address taken with `&` anywhere in this file, globals with these names are not narrowed.
+	pub referenced_globals: HashSet<Box<str>>,
+	/// The types of functions that take and return ints that have been built, keyed by parameter count and if they are variadic.
+	pub int_function_types: HashMap<(usize, bool), Type<'a>>,
+}
+
+/// A symbol that the compiler generates the name of.
+pub enum MangledSymbol<'a> {
+	/// A function that is not directly assigned to a global variable.
+	UnnamedFunction,
+	/// The wrapper function around a function imported with `@link`.
+	LinkWrapper(&'a str),
+	/// A global variable exported from the file at the filepath.
+	Export(&'a Path, &'a str),
+}
+
+impl<'a, 'b> FileBuildData<'a, 'b> {
+	/// Get the type of a function that takes `parameter_count` ints and returns an int, types are cached since most functions share a few parameter counts.
+	pub fn int_function_type(&mut self, int_type: Type<'a>, parameter_count: usize, is_variadic: bool) -> Type<'a> {
+		*self.int_function_types.entry((parameter_count, is_variadic)).or_insert_with(|| {
+			let parameter_types: Box<[Type]> = repeat_n(int_type, parameter_count).collect();
+			int_type.function_type(&parameter_types, is_variadic)
+		})
+	}
+
+	/// Get the name of a compiler generated symbol, unnamed functions are numbered so that each one gets a different name.
+	pub fn mangle_name(&mut self, symbol: MangledSymbol) -> String {
+		match symbol {
+			MangledSymbol::UnnamedFunction => {
+				let name = format!("__bcz__unnamedFunction__{}", self.unnamed_function_count);
+				self.unnamed_function_count += 1;
+				name
+			}
+			MangledSymbol::LinkWrapper(name) => format!("__bcz__link__{name}"),
+			MangledSymbol::Export(filepath, name) => {
+				let mut hasher = DefaultHasher::new();
+				filepath.hash(&mut hasher);
+				format!("__bcz__export__{}__{name}", hasher.finish())
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{collections::{HashMap, HashSet}, path::PathBuf};
+
+	use crate::with_main_data;
+
+	use super::FileBuildData;
+
+	#[test]
+	fn int_function_types_are_cached() {
+		with_main_data(&["--check"], |main_data| {
+			let filepath = PathBuf::from("main.bcz");
+			let mut file_build_data = FileBuildData {
+				built_globals: HashMap::new(),
+				built_global_function_signatures: HashMap::new(),
+				entrypoint: None,
+				filepath: &filepath,
+				unnamed_function_count: 0,
+				default_arguments: HashMap::new(),
+				global_names: HashSet::new(),
+				referenced_globals: HashSet::new(),
+				int_function_types: HashMap::new(),
+			};
+			let two_parameters = file_build_data.int_function_type(main_data.int_type, 2, false);
+			assert!(two_parameters == file_build_data.int_function_type(main_data.int_type, 2, false));
+			assert_eq!(file_build_data.int_function_types.len(), 1);
+			// Different parameter counts and variadic functions each get their own type
+			assert!(two_parameters != file_build_data.int_function_type(main_data.int_type, 3, false));
+			assert!(two_parameters != file_build_data.int_function_type(main_data.int_type, 2, true));
+			assert_eq!(file_build_data.int_function_types.len(), 3);
+		});
+	}
+}
@@ -18,6 +18,7 @@ impl<'a, 'b> FunctionBuildData<'a, 'b> {
 			Some(alloca) => {
 				let alloca = alloca.clone();
 				self.allocas_not_in_use.remove(&alloca);
+				self.block_stack.last_mut().unwrap().allocas_in_use.insert(alloca.clone());
 				alloca
 			}
 			None => {
@@ -31,6 +32,11 @@ impl<'a, 'b> FunctionBuildData<'a, 'b> {
 		}
 	}
 
+	/// Get the local variable `name` from the innermost scope that declares it.
+	pub fn get_local_variable(&self, name: &str) -> Option<&BuiltLValue<'a>> {
+		self.block_stack.iter().rev().find_map(|scope_level| scope_level.local_variables.get(name))
+	}
+
 	pub fn surrender_alloca(&mut self, alloca: Value<'a, 'a>) {
 		for block in self.block_stack.iter_mut().rev() {
 			if block.allocas_in_use.remove(&alloca) {
@@ -68,6 +74,21 @@ impl<'a, 'b> FunctionBuildData<'a, 'b> {
 		}
 	}
 
+	/// Print the names of the local variables at each level of the block stack if `--dump-scopes` is set, indented by the depth of the block stack.
+	pub fn dump_scopes(&self, main_data: &MainData<'a>, event: &str) {
+		if !main_data.dump_scopes {
+			return;
+		}
+		let levels: Vec<String> = self.block_stack.iter()
+			.map(|level| {
+				let mut names: Vec<&str> = level.local_variables.keys().map(|name| &**name).collect();
+				names.sort_unstable();
+				format!("[{}]", names.join(", "))
+			})
+			.collect();
+		println!("{}{event}: {}", "\t".repeat(self.block_stack.len() - 1), levels.join(" "));
+	}
+
 	//pub fn surrender_array_alloca(&mut self, alloca: Value<'a, 'a>) {
 	//	for block in self.block_stack.iter_mut().rev() {
 	//		for (array_type, arrays) in block.array_allocas_in_use.iter_mut() {
@@ -93,7 +114,10 @@ pub struct BlockLevel<'a> {
 	pub basic_blocks: Vec<BasicBlock<'a, 'a>>,
 	pub allocas_in_use: HashSet<Value<'a, 'a>>,
 	pub array_allocas_in_use: HashMap<(Type<'a>, u64), HashSet<Value<'a, 'a>>>,
-	pub is_loop: bool,
+	/// If this level is a loop, the basic block that `@continue` branches to and the basic block that `@break` branches to.
+	pub loop_blocks: Option<(BasicBlock<'a, 'a>, BasicBlock<'a, 'a>)>,
+	/// The name of a `@label` block and the alloca that its result is stored in.
+	pub label: Option<(Box<str>, Value<'a, 'a>)>,
 }
 
 impl<'a> BlockLevel<'a> {
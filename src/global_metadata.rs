@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use crate::json_string;
+
+/// A description of a global variable of a compiled file, written to the `--emit-metadata-json` file for tooling that uses compiled BCZ.
+pub struct GlobalMetadata {
+	/// The source file the global is assigned to in.
+	pub filepath: PathBuf,
+	pub name: Box<str>,
+	/// Is the global a function, including functions linked with `@link`.
+	pub is_function: bool,
+	/// How many parameters the function has, `None` if the global is not a function.
+	pub parameter_count: Option<usize>,
+	/// Is the global a function from another library linked with `@link`.
+	pub is_link: bool,
+	pub is_exported: bool,
+	pub is_entry_point: bool,
+}
+
+impl GlobalMetadata {
+	/// Get the metadata as a JSON object.
+	pub fn to_json(&self) -> String {
+		let parameter_count = match self.parameter_count {
+			Some(parameter_count) => parameter_count.to_string(),
+			None => "null".into(),
+		};
+		format!(
+			"{{\"file\":{},\"name\":{},\"function\":{},\"parameter_count\":{parameter_count},\"link\":{},\"export\":{},\"entry_point\":{}}}",
+			json_string(&self.filepath.display().to_string()), json_string(&self.name), self.is_function, self.is_link, self.is_exported, self.is_entry_point,
+		)
+	}
+}
+
+/// Get the metadata of each global as a JSON object with a `globals` array.
+pub fn globals_metadata_to_json(globals_metadata: &[GlobalMetadata]) -> String {
+	let globals: Vec<String> = globals_metadata.iter()
+		.map(GlobalMetadata::to_json)
+		.collect();
+	format!("{{\"globals\":[\n{}\n]}}\n", globals.join(",\n"))
+}
@@ -1,10 +1,16 @@
-use std::{collections::{HashMap, HashSet}, env::args, i64, mem::take, num::NonZeroUsize, path::PathBuf, process::Command};
+use std::{cell::RefCell, collections::{HashMap, HashSet}, env::args, fs::{read_to_string, write}, i64, mem::take, num::NonZeroUsize, path::{Path, PathBuf}, process::{exit, Command}, time::{Duration, Instant}};
 
-use compile::compile_file;
-use compiler_arguments::{process_arguments, CompilerArgumentsData};
-use error::Error;
+use builtins::{default_builtins, Builtin};
+use global_metadata::{globals_metadata_to_json, GlobalMetadata};
+use color::{paint, ColorChoice, BOLD, BOLD_BLUE, BOLD_RED, BOLD_YELLOW};
+use position::{is_generated_line, Position, Span};
+use time_trace::TimeTrace;
+
+use compile::{compile_file, source_lines, span_location};
+use compiler_arguments::{expand_response_files, process_arguments, CompilerArgumentsData, IrPhase, LanguageVersion, TrapMode};
+use error::{Error, ErrorFormat};
 use llvm_nhb::{context::Context, other::initialize_x86, target::Target, target_data::TargetData, target_machine::TargetMachine, types::Type};
-use llvm_nhb::enums::{CodeModel, CodegenOptLevel, RealocMode};
+use llvm_nhb::enums::{CodegenOptLevel, RealocMode};
 use token::{Keyword, OperatorSymbol, OperatorType, Separator};
 
 mod compiler_arguments;
@@ -16,6 +22,11 @@ mod parse;
 mod built_value;
 mod file_build_data;
 mod function_building_data;
+mod color;
+mod position;
+mod time_trace;
+mod builtins;
+mod global_metadata;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum OperatingSystem {
@@ -23,6 +34,18 @@ enum OperatingSystem {
 	Linux = 1,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Cpu {
+	X86_64,
+	I686,
+}
+
+/// The file, line, column and end column that an error occurred at, if they are known, the end column is the column of the char after the last char of the error.
+pub type ErrorLocation = Option<(PathBuf, Option<(NonZeroUsize, Option<NonZeroUsize>, Option<NonZeroUsize>)>)>;
+
+/// The default value of each parameter of a function, `None` for parameters without a default.
+pub type ParameterDefaults = Box<[Option<u64>]>;
+
 /// Info that applies while compiling all files.
 pub struct MainData<'a> {
 	/// Should the compiled .o files be linked to create a primary output file?
@@ -82,14 +105,102 @@ pub struct MainData<'a> {
 	llvm_target_machine: &'a TargetMachine,
 	/// A list of object files that have been outputted as a result of compiling that should be linked to create a primary output file.
 	object_files_to_link: Vec<PathBuf>,
+	/// Has any compiled file defined an entry point.
+	has_entry_point: bool,
+	/// The source files that have been compiled, including imported and standard library files.
+	compiled_source_files: Vec<PathBuf>,
 	/// The path to the BCZ standard library.
 	standard_library_path: PathBuf,
+	/// Directories searched for imported files after the directory of the importing file, in order.
+	include_directories: Vec<PathBuf>,
 
 	operating_system: OperatingSystem,
 
+	cpu: Cpu,
+
 	link_command: Box<str>,
 
 	libraries_to_link_to: HashSet<Box<str>>,
+	/// The values of exported global variables that where const evaluated to constants, keyed by the absolute filepath of the file that exports them and their name.
+	exported_constants: HashMap<(PathBuf, Box<str>), u64>,
+	/// The default parameter values of exported functions that have defaults, keyed the same way as `exported_constants`.
+	exported_parameter_defaults: HashMap<(PathBuf, Box<str>), ParameterDefaults>,
+	/// Should using a global variable that is not assigned to in the file be an error.
+	strict_globals: bool,
+	/// Should compiling stop after const evaluation, skipping building, emitting and linking.
+	check_only: bool,
+	/// What the compiled program does when a runtime check fails.
+	trap_mode: TrapMode,
+	/// Should the functions and global variables of each built LLVM module be printed with their linkage.
+	dump_symbols: bool,
+	/// Is the program being compiled for a target without an operating system or C runtime.
+	freestanding: bool,
+	/// Should `@runtime_assert` checks be left out of the built program.
+	no_asserts: bool,
+	/// Global constants defined from the command line that each file can use without assigning to them.
+	defined_constants: HashMap<Box<str>, u64>,
+	/// Directories passed to the linker to search for libraries in.
+	library_search_paths: Vec<&'a str>,
+	/// Libraries passed to the linker by name.
+	linked_libraries: Vec<&'a str>,
+	/// The root directory the linker searches for libraries in, `None` uses the linker default.
+	sysroot: Option<&'a str>,
+	/// Should source filenames recorded in object files be relative so that output does not depend on where the project is.
+	deterministic: bool,
+	/// Should the remaining files be compiled after a file fails to compile.
+	keep_going: bool,
+	/// Where to write a Makefile style dependency file, `None` if one should not be written.
+	dep_filepath: Option<&'a str>,
+	/// Should float operations be built with fast-math flags.
+	fast_math: bool,
+	/// Should the names of built values have the line and column of the node they are built from appended.
+	ir_comments: bool,
+	/// The most parameters a function definition or linked function can have.
+	max_function_parameters: u16,
+	/// The most arguments a function call can have.
+	max_function_arguments: u16,
+	/// The version of the language being compiled, features added in later versions are errors.
+	language_version: LanguageVersion,
+	/// Should integer division and modulo check for a zero divisor at runtime.
+	checked_division: bool,
+	/// Where to write the Chrome tracing JSON of how long each phase of compilation took, `None` if it should not be written.
+	time_trace_filepath: Option<&'a str>,
+	/// The timed phases of compilation, `None` if they are not being timed.
+	time_trace: Option<RefCell<TimeTrace>>,
+	/// Should the parameter and return widths of linked functions be checked against the C ABI of the target.
+	abi_check: bool,
+	/// Should the local variable scopes be printed as functions are built.
+	dump_scopes: bool,
+	/// Should binary operators be desugared to calls to global overload functions such as `__add` that the file defines.
+	operator_overloading: bool,
+	/// Is a library being compiled that does not need an entry point and is not linked.
+	no_entry: bool,
+	/// Should warnings be reported as errors.
+	strict: bool,
+	/// If errors and warnings should be colored.
+	color_choice: ColorChoice,
+	/// If errors and warnings should be printed for humans or as JSON.
+	error_format: ErrorFormat,
+	/// Should each phase of compilation be printed as it runs.
+	print_phases: bool,
+	/// Should constant global variables be stored in the narrowest int type that holds them.
+	narrow_globals: bool,
+	/// Should constant arithmetic that overflows a signed int be an error instead of wrapping.
+	overflow_check_const: bool,
+	/// The functions that are built by the compiler when called, keyed by name, looked up before global variables.
+	builtins: HashMap<&'static str, Builtin>,
+	/// When compilation should stop with an error if it has not finished, set with `--timeout`.
+	deadline: Option<Instant>,
+	/// The phase after which the LLVM IR of each module should be printed.
+	dump_ir_after: Option<IrPhase>,
+	/// Should each metadata keyword found by the parser be printed with its position and what it applies to.
+	dump_metadata: bool,
+	/// The file to write a JSON description of each global to, set with `--emit-metadata-json`.
+	metadata_json_filepath: Option<&'a str>,
+	/// The descriptions of the globals of each compiled file for `--emit-metadata-json`.
+	globals_metadata: Vec<GlobalMetadata>,
+	/// The codes of errors and warnings that should not be printed, set with `--suppress`.
+	suppressed_codes: HashSet<Box<str>>,
 }
 
 impl<'a> MainData<'a> {
@@ -102,11 +213,12 @@ impl<'a> MainData<'a> {
 		// Parse target triplet
 		//println!("{}", compiler_arguments_data.target_triplet);
 		let mut target_triplet_parts = compiler_arguments_data.target_triplet.split('-');
-		match target_triplet_parts.next() {
-			Some("x86_64") => {}
+		let cpu = match target_triplet_parts.next() {
+			Some("x86_64") => Cpu::X86_64,
+			Some("i686") => Cpu::I686,
 			Some(other) => return Err(Error::UnsupportedCPU(other.into())),
 			None => return Err(Error::InvalidTargetTriplet(compiler_arguments_data.target_triplet.into_string())),
-		}
+		};
 		target_triplet_parts.next();
 		let operating_system = match target_triplet_parts.next() {
 			Some("windows") => OperatingSystem::Windows,
@@ -143,84 +255,346 @@ impl<'a> MainData<'a> {
 			llvm_target_triple: compiler_arguments_data.target_triplet,
 			llvm_target_machine: target_machine,
 			object_files_to_link: Vec::new(),
+			has_entry_point: false,
+			compiled_source_files: Vec::new(),
 			int_8_type,
 			standard_library_path,
+			include_directories: compiler_arguments_data.include_directories.iter().map(PathBuf::from).collect(),
 			operating_system,
+			cpu,
 			link_command: compiler_arguments_data.link_command,
 			libraries_to_link_to: HashSet::new(),
+			exported_constants: HashMap::new(),
+			exported_parameter_defaults: HashMap::new(),
+			strict_globals: compiler_arguments_data.strict_globals,
+			check_only: compiler_arguments_data.check_only,
+			trap_mode: compiler_arguments_data.trap_mode,
+			dump_symbols: compiler_arguments_data.dump_symbols,
+			freestanding: compiler_arguments_data.freestanding,
+			no_asserts: compiler_arguments_data.no_asserts,
+			defined_constants: compiler_arguments_data.defined_constants,
+			library_search_paths: compiler_arguments_data.library_search_paths,
+			linked_libraries: compiler_arguments_data.linked_libraries,
+			sysroot: compiler_arguments_data.sysroot,
+			deterministic: compiler_arguments_data.deterministic,
+			keep_going: compiler_arguments_data.keep_going,
+			dep_filepath: compiler_arguments_data.dep_filepath,
+			fast_math: compiler_arguments_data.fast_math,
+			ir_comments: compiler_arguments_data.ir_comments,
+			max_function_parameters: compiler_arguments_data.max_function_parameters,
+			max_function_arguments: compiler_arguments_data.max_function_arguments,
+			language_version: compiler_arguments_data.language_version,
+			checked_division: compiler_arguments_data.checked_division,
+			time_trace_filepath: compiler_arguments_data.time_trace_filepath,
+			time_trace: compiler_arguments_data.time_trace_filepath.map(|_| RefCell::new(TimeTrace::new())),
+			abi_check: compiler_arguments_data.abi_check,
+			dump_scopes: compiler_arguments_data.dump_scopes,
+			operator_overloading: compiler_arguments_data.operator_overloading,
+			no_entry: compiler_arguments_data.no_entry,
+			strict: compiler_arguments_data.strict,
+			color_choice: compiler_arguments_data.color_choice,
+			error_format: compiler_arguments_data.error_format,
+			print_phases: compiler_arguments_data.print_phases,
+			narrow_globals: compiler_arguments_data.narrow_globals,
+			overflow_check_const: compiler_arguments_data.overflow_check_const,
+			builtins: default_builtins(),
+			deadline: compiler_arguments_data.timeout.map(|timeout| Instant::now() + Duration::from_millis(timeout)),
+			dump_ir_after: compiler_arguments_data.dump_ir_after,
+			dump_metadata: compiler_arguments_data.dump_metadata,
+			metadata_json_filepath: compiler_arguments_data.metadata_json_filepath,
+			globals_metadata: Vec::new(),
+			suppressed_codes: compiler_arguments_data.suppressed_codes.clone(),
 		})
 	}
 
+	/// Get info about the int type of the machine being compiled for.
+	pub fn set_int_width(&mut self) -> Result<(), Error> {
+		let int_type_width = self.int_type.size_in_bits(&self.llvm_data_layout);
+		if int_type_width > 64 {
+			return Err(Error::InvalidArchitectureBitWidth(int_type_width));
+		}
+		self.int_bit_width = int_type_width as u8;
+		self.int_max_value = ((1u128 << self.int_bit_width) - 1) as u64;
+		self.sign_bit_mask = self.int_max_value & !(self.int_max_value >> 1);
+		// Negative defined constants are sign extended to 64 bits so they are truncated to the int width
+		for value in self.defined_constants.values_mut() {
+			if *value > self.int_max_value && *value | self.int_max_value != u64::MAX {
+				return Err(Error::NumericalLiteralTooLarge(self.int_bit_width));
+			}
+			*value &= self.int_max_value;
+		}
+		self.int_power_width = (self.int_bit_width / 8).ilog2() as u8;
+		Ok(())
+	}
+
+	/// Interpret an int value as a signed int by sign extending it from the int width, so that signed comparisons and arithmetic on constants
+	/// give the same result as the signed instructions they are folded from.
 	pub fn value_to_signed(&self, value: u64) -> i64 {
-		let sign_bit = (value & self.sign_bit_mask) != 0;
-		(value & (self.int_max_value >> 1)) as i64 | match sign_bit {
-			true => i64::MIN,
-			false => 0,
+		let unused_bit_count = 64 - self.int_bit_width as u32;
+		((value << unused_bit_count) as i64) >> unused_bit_count
+	}
+
+	/// Interpret the bits of an int value as a float of the same width, returns `None` if there is no float type of the int width.
+	pub fn value_to_float(&self, value: u64) -> Option<f64> {
+		match self.int_bit_width {
+			32 => Some(f32::from_bits(value as u32) as f64),
+			64 => Some(f64::from_bits(value)),
+			_ => None,
 		}
 	}
 
-	pub fn signed_to_value(&self, signed: i64) -> u64 {
-		let sign_bit = (signed & i64::MIN) != 0;
-		(signed as u64 & (self.int_max_value >> 1)) | match sign_bit {
-			true => self.sign_bit_mask,
-			false => 0,
+	/// Get the bits of a float rounded to the float type of the same width as an int, returns `None` if there is no float type of the int width.
+	pub fn float_to_value(&self, float: f64) -> Option<u64> {
+		match self.int_bit_width {
+			32 => Some((float as f32).to_bits() as u64),
+			64 => Some(float.to_bits()),
+			_ => None,
+		}
+	}
+
+	/// Record that the phase of compilation `name` ran from `started_at` until now if phases are being timed.
+	pub fn record_time_trace_event(&self, name: &'static str, detail: impl Into<String>, started_at: Instant) {
+		if let Some(time_trace) = &self.time_trace {
+			time_trace.borrow_mut().record(name, detail.into(), started_at);
+		}
+	}
+
+	/// Print a warning at `span` in the file at `filepath`, or return it as an error if `--strict` is set.
+	pub fn report_warning(&self, warning: Error, filepath: &Path, span: Span) -> Result<(), (Error, Span)> {
+		if self.strict {
+			return Err((warning, span));
+		}
+		report_diagnostic(
+			&warning, &Some((filepath.to_path_buf(), Some(span_location(span)))), self.color_choice, self.error_format, &self.suppressed_codes, true
+		);
+		Ok(())
+	}
+
+	/// Return an error at `span` if compilation has gone on past the `--timeout` deadline.
+	pub fn check_deadline(&self, span: Span) -> Result<(), (Error, Span)> {
+		match self.deadline {
+			Some(deadline) if Instant::now() >= deadline => Err((Error::CompilationTimedOut, span)),
+			_ => Ok(()),
 		}
 	}
+
+	/// Print that the phase of compilation `name` ran or was skipped for `detail` if `--print-phases` is set.
+	pub fn print_phase(&self, name: &str, detail: &str, did_run: bool) {
+		if !self.print_phases {
+			return;
+		}
+		let separator = match detail.is_empty() {
+			true => "",
+			false => " ",
+		};
+		match did_run {
+			true => println!("[\u{2713}] {name}{separator}{detail}"),
+			false => println!("[ ] {name}{separator}{detail} (skipped)"),
+		}
+	}
+
+	/// Get the name of a value built from a node at `position`, with the position appended if IR comments are on.
+	pub fn temp_name(&self, name: &str, position: Position) -> String {
+		match self.ir_comments && !is_generated_line(position.0) {
+			true => format!("{name}_L{}C{}", position.0, position.1),
+			false => name.into(),
+		}
+	}
+
+	/// Returns if the result of signed arithmetic on constants is out of the range of a signed int.
+	/// Constants are untyped and negative numbers are stored as their unsigned two's complement, so `-1 + 1` wraps past the int width as unsigned.
+	/// Unsigned wraparound is therefore not reported, only results that overflow when both operands are read as signed.
+	pub fn signed_overflows(&self, result: i128) -> bool {
+		let signed_max = (self.int_max_value >> 1) as i128;
+		result > signed_max || result < -signed_max - 1
+	}
+
+	/// Truncate a signed int to an int value of the int width.
+	pub fn signed_to_value(&self, signed: i64) -> u64 {
+		signed as u64 & self.int_max_value
+	}
 }
 
 fn main() {
-	match main_error_handled() {
+	let mut color_choice = ColorChoice::Auto;
+	let mut error_format = ErrorFormat::Human;
+	let mut suppressed_codes = HashSet::new();
+	match main_error_handled(&mut color_choice, &mut error_format, &mut suppressed_codes) {
 		Ok(..) => {}
 		Err((error, error_location)) => {
-			print!("Error");
-			if let Some((error_file, error_row_column)) = error_location {
-				print!(" in file {}", error_file.display());
-				if let Some((error_row, error_column)) = error_row_column {
-					print!(":{error_row}");
-					if let Some(error_column) = error_column {
-						print!(":{error_column}");
-					}
+			report_error(&error, &error_location, color_choice, error_format, &suppressed_codes);
+			exit(1);
+		}
+	}
+}
+
+/// Prints an error in the selected error format.
+fn report_error(error: &Error, error_location: &ErrorLocation, color_choice: ColorChoice, error_format: ErrorFormat, suppressed_codes: &HashSet<Box<str>>) {
+	report_diagnostic(error, error_location, color_choice, error_format, suppressed_codes, false);
+}
+
+/// Print an error, or a warning if `is_warning` is set, in the error format, unless its code is suppressed.
+fn report_diagnostic(
+	error: &Error, error_location: &ErrorLocation, color_choice: ColorChoice, error_format: ErrorFormat, suppressed_codes: &HashSet<Box<str>>, is_warning: bool
+) {
+	if suppressed_codes.contains(error.code()) {
+		return;
+	}
+	match error_format {
+		ErrorFormat::Human => print_error(error, error_location, color_choice.should_color(), is_warning),
+		ErrorFormat::Json => print_error_json(error, error_location, is_warning),
+	}
+}
+
+/// Prints an error as a single line JSON object with the keys `severity`, `code`, `message`, `file`, `line`, `column` and `end_column`, unknown values are `null`.
+fn print_error_json(error: &Error, error_location: &ErrorLocation, is_warning: bool) {
+	let (file, line, column, end_column) = match error_location {
+		None => (None, None, None, None),
+		Some((error_file, None)) => (Some(error_file), None, None, None),
+		Some((error_file, Some((error_row, _, _)))) if is_generated_line(*error_row) => (Some(error_file), None, None, None),
+		Some((error_file, Some((error_row, error_column, error_end_column)))) => (Some(error_file), Some(*error_row), *error_column, *error_end_column),
+	};
+	let file = match file {
+		Some(file) => json_string(&file.display().to_string()),
+		None => "null".into(),
+	};
+	let line = line.map_or_else(|| "null".into(), |line| line.to_string());
+	let column = column.map_or_else(|| "null".into(), |column| column.to_string());
+	let end_column = end_column.map_or_else(|| "null".into(), |end_column| end_column.to_string());
+	let severity = match is_warning {
+		true => "warning",
+		false => "error",
+	};
+	println!(
+		"{{\"severity\":\"{severity}\",\"code\":{},\"message\":{},\"file\":{file},\"line\":{line},\"column\":{column},\"end_column\":{end_column}}}",
+		json_string(error.code()), json_string(&error.to_string()),
+	);
+}
+
+/// Quotes and escapes a string so that it is a valid JSON string.
+fn json_string(string: &str) -> String {
+	let mut out = String::from('"');
+	for chr in string.chars() {
+		match chr {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			chr if (chr as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", chr as u32)),
+			chr => out.push(chr),
+		}
+	}
+	out.push('"');
+	out
+}
+
+/// Get the text that describes where an error is, such as ` in file main.bcz:1:5`.
+fn error_location_text(error_location: &ErrorLocation) -> String {
+	let mut location_text = String::new();
+	if let Some((error_file, error_row_column)) = error_location {
+		location_text.push_str(&format!(" in file {}", error_file.display()));
+		match error_row_column {
+			Some((error_row, _, _)) if is_generated_line(*error_row) => location_text.push_str(" (generated)"),
+			Some((error_row, error_column, _)) => {
+				location_text.push_str(&format!(":{error_row}"));
+				if let Some(error_column) = error_column {
+					location_text.push_str(&format!(":{error_column}"));
 				}
 			}
-			println!(": {error}.");
+			None => {}
 		}
 	}
+	location_text
+}
+
+/// Prints an error and its location, if the line and column are known then the line of source code is printed with carets underlining the error.
+fn print_error(error: &Error, error_location: &ErrorLocation, use_color: bool, is_warning: bool) {
+	match is_warning {
+		true => print!("{}", paint(&format!("Warning[{}]", error.code()), BOLD_YELLOW, use_color)),
+		false => print!("{}", paint(&format!("Error[{}]", error.code()), BOLD_RED, use_color)),
+	}
+	print!("{}", paint(&error_location_text(error_location), BOLD, use_color));
+	println!(": {error}.");
+	// Print the line of source code the error is on
+	let (error_file, error_row, error_column, error_end_column) = match error_location {
+		Some((error_file, Some((error_row, Some(error_column), error_end_column)))) if !is_generated_line(*error_row) =>
+			(error_file, *error_row, *error_column, *error_end_column),
+		_ => return,
+	};
+	let file_content = match read_to_string(error_file) {
+		Ok(file_content) => file_content,
+		Err(_) => return,
+	};
+	let line = match source_lines(&file_content).nth(error_row.get() - 1) {
+		Some(line) => line,
+		None => return,
+	};
+	let line_number_text = format!("{error_row} | ");
+	println!("{}{line}", paint(&line_number_text, BOLD_BLUE, use_color));
+	// Underline from the column to the end column with carets, or print a single caret if the end is not known, tabs are kept so that the carets line up with the line above
+	let caret_indent: String = line.chars().take(error_column.get() - 1).map(|chr| if chr == '\t' { '\t' } else { ' ' }).collect();
+	let caret_count = match error_end_column {
+		Some(error_end_column) => (error_end_column.get() - error_column.get()).clamp(1, line.chars().count().saturating_sub(error_column.get() - 1).max(1)),
+		None => 1,
+	};
+	let carets = "^".repeat(caret_count);
+	let gutter = format!("{:width$} | ", "", width = line_number_text.len() - 3);
+	println!("{}{caret_indent}{}", paint(&gutter, BOLD_BLUE, use_color), paint(&carets, BOLD_RED, use_color));
 }
 
-fn main_error_handled() -> Result<(), (Error, Option<(PathBuf, Option<(NonZeroUsize, Option<NonZeroUsize>)>)>)> {
+fn main_error_handled(
+	color_choice: &mut ColorChoice, error_format: &mut ErrorFormat, suppressed_codes: &mut HashSet<Box<str>>
+) -> Result<(), (Error, ErrorLocation)> {
+	let setup_started_at = Instant::now();
 	// Get and process arguments
-	let arguments: Box<[Box<str>]> = args().skip(1).map(|string| string.into_boxed_str()).collect();
+	let arguments = expand_response_files(args().skip(1).map(|string| string.into_boxed_str()), 0).map_err(|error| (error, None))?;
 	let arguments: Box<[&str]> = arguments.iter().map(|argument| &**argument).collect();
 	let mut compiler_arguments_data = CompilerArgumentsData::new();
 	process_arguments(&arguments, &mut compiler_arguments_data).map_err(|error| (error, None))?;
+	*color_choice = compiler_arguments_data.color_choice;
+	*error_format = compiler_arguments_data.error_format;
+	*suppressed_codes = compiler_arguments_data.suppressed_codes.clone();
 	// Setup LLVM
 	initialize_x86();
 	let llvm_target = Target::from_triple(&compiler_arguments_data.target_triplet).map_err(|llvm_error| (Error::CouldNotGetTarget(llvm_error), None))?;
+	let realoc_mode = match compiler_arguments_data.position_independent_code {
+		None => RealocMode::Default,
+		Some(true) => RealocMode::PositionIndependent,
+		Some(false) => RealocMode::Static,
+	};
 	let llvm_target_machine = llvm_target.create_target_machine(
-		&compiler_arguments_data.target_triplet, "generic", "", CodegenOptLevel::Default, RealocMode::Default, CodeModel::Default
+		&compiler_arguments_data.target_triplet, "generic", "", CodegenOptLevel::Default, realoc_mode, compiler_arguments_data.code_model
 	);
 	let llvm_data_layout = llvm_target_machine.get_target_data();
+	if compiler_arguments_data.dump_target_data_layout {
+		println!("Target data layout: {}", llvm_data_layout.string_rep());
+	}
 	let context = Context::new();
 	let int_type = llvm_data_layout.int_ptr_type(&context);
 	let int_8_type = context.int_8_type();
 	let mut main_data = MainData::new(compiler_arguments_data, &context, &llvm_target_machine, &llvm_data_layout, int_type, int_8_type)
 		.map_err(|error| (error, None))?;
-	// Get info about machine being compiled for
-	let int_type_width = main_data.int_type.size_in_bits(&main_data.llvm_data_layout);
-	if int_type_width > 64 {
-		return Err((Error::InvalidArchitectureBitWidth(int_type_width), None));
-	}
-	main_data.int_bit_width = int_type_width as u8;
-	main_data.int_max_value = ((1u128 << main_data.int_bit_width) - 1) as u64;
-	main_data.sign_bit_mask = main_data.int_max_value & !(main_data.int_max_value >> 1);
-	main_data.int_power_width = (main_data.int_bit_width / 8).ilog2() as u8;
-	// Compile
+	main_data.set_int_width().map_err(|error| (error, None))?;
+	main_data.record_time_trace_event("Setup", "", setup_started_at);
+	// Compile, when keeping going the errors of failed files are reported and the next file is compiled
+	let mut failed_file_count = 0;
 	for filepath in take(&mut main_data.filepaths_to_compile).iter() {
 		let absolute_filepath = main_data.source_path.join(filepath).canonicalize().unwrap();
-		compile_file(&mut main_data, &absolute_filepath)?;
+		match compile_file(&mut main_data, &absolute_filepath) {
+			Ok(()) => {}
+			Err((error, error_location)) if main_data.keep_going => {
+				report_error(&error, &error_location, *color_choice, *error_format, suppressed_codes);
+				failed_file_count += 1;
+			}
+			Err(error) => return Err(error),
+		}
+	}
+	if failed_file_count > 0 {
+		return Err((Error::FilesFailedToCompile(failed_file_count), None));
 	}
-	// Link
-	let primary_output_file = match (main_data.primary_output_file, main_data.do_link) {
+	// Link, a library built with --no-entry has no entry point so only its object files are emitted
+	let primary_output_file = match (main_data.primary_output_file, main_data.do_link && !main_data.check_only && !main_data.no_entry) {
 		(Some(primary_output_file), true) => Some(primary_output_file),
 		(None, true) => Some(match main_data.operating_system {
 			OperatingSystem::Windows => "out.exe",
@@ -228,7 +602,24 @@ fn main_error_handled() -> Result<(), (Error, Option<(PathBuf, Option<(NonZeroUs
 		}),
 		(_, false) => None,
 	};
+	// Write a Makefile style dependency file, the target is the linked output or each object file if not linking
+	if let Some(dep_filepath) = main_data.dep_filepath {
+		let targets: Vec<PathBuf> = match primary_output_file {
+			Some(primary_output_file) => vec![main_data.binary_path.join(primary_output_file)],
+			None => main_data.object_files_to_link.iter().filter(|object_file| object_file.extension().is_some_and(|extension| extension == "o")).cloned().collect(),
+		};
+		let escape_path = |path: &PathBuf| path.display().to_string().replace(' ', "\\ ");
+		let targets: Vec<String> = targets.iter().map(escape_path).collect();
+		let dependencies: Vec<String> = main_data.compiled_source_files.iter().map(escape_path).collect();
+		let dep_file_content = format!("{}: {}\n", targets.join(" "), dependencies.join(" \\\n\t"));
+		write(dep_filepath, dep_file_content).map_err(|error| (Error::CouldNotWriteDepFile(error), None))?;
+	}
 	if let Some(primary_output_file) = primary_output_file {
+		// An executable can not be linked without an entry point, this also covers compiling only empty files
+		if !main_data.has_entry_point {
+			return Err((Error::NoEntryPoint, None));
+		}
+		let link_started_at = Instant::now();
 		let primary_output_file_path = main_data.binary_path.join(primary_output_file);
 		let mut command = Command::new(&*main_data.link_command);
 		for object_file in main_data.object_files_to_link.iter() {
@@ -237,9 +628,20 @@ fn main_error_handled() -> Result<(), (Error, Option<(PathBuf, Option<(NonZeroUs
 		for library_to_link_to in main_data.libraries_to_link_to.iter() {
 			command.arg(&**library_to_link_to);
 		}
-		if main_data.operating_system == OperatingSystem::Linux {
+		if let Some(sysroot) = main_data.sysroot {
+			command.arg(format!("--sysroot={sysroot}"));
+		}
+		for library_search_path in main_data.library_search_paths.iter() {
+			command.arg(format!("-L{library_search_path}"));
+		}
+		for linked_library in main_data.linked_libraries.iter() {
+			command.arg(format!("-l{linked_library}"));
+		}
+		if main_data.operating_system == OperatingSystem::Linux || main_data.freestanding {
 			command.arg("-nostdlib");
 			command.arg("-static");
+		}
+		if main_data.operating_system == OperatingSystem::Linux {
 			command.arg("-no-pie");
 		}
 		command.arg("-o");
@@ -248,6 +650,61 @@ fn main_error_handled() -> Result<(), (Error, Option<(PathBuf, Option<(NonZeroUs
 		if !result.status.success() {
 			return Err((Error::ErrorWhileLinking(result.status.code()), None));
 		}
+		main_data.record_time_trace_event("Link", primary_output_file, link_started_at);
+		main_data.print_phase("Link", primary_output_file, true);
+	}
+	else {
+		main_data.print_phase("Link", "", false);
+	}
+	// Write the timed phases of compilation if commanded to do so
+	if let (Some(time_trace_filepath), Some(time_trace)) = (main_data.time_trace_filepath, &main_data.time_trace) {
+		write(time_trace_filepath, time_trace.borrow().to_json()).map_err(|error| (Error::CouldNotWriteTimeTrace(error), None))?;
+	}
+	// Write the description of each global for tooling if commanded to do so
+	if let Some(metadata_json_filepath) = main_data.metadata_json_filepath {
+		write(metadata_json_filepath, globals_metadata_to_json(&main_data.globals_metadata))
+			.map_err(|error| (Error::CouldNotWriteMetadataJson(error), None))?;
 	}
 	Ok(())
-}
\ No newline at end of file
+}
+
+/// Calls `test` with the main data used to compile for the host with the compiler arguments `arguments`, for unit tests that run parts of the compiler.
+#[cfg(test)]
+pub fn with_main_data<R>(arguments: &[&str], test: impl FnOnce(&mut MainData) -> R) -> R {
+	let mut compiler_arguments_data = CompilerArgumentsData::new();
+	process_arguments(arguments, &mut compiler_arguments_data).unwrap_or_else(|error| panic!("{error}"));
+	initialize_x86();
+	let llvm_target = Target::from_triple(&compiler_arguments_data.target_triplet).unwrap();
+	let llvm_target_machine = llvm_target.create_target_machine(
+		&compiler_arguments_data.target_triplet, "generic", "", CodegenOptLevel::Default, RealocMode::Default, compiler_arguments_data.code_model
+	);
+	let llvm_data_layout = llvm_target_machine.get_target_data();
+	let context = Context::new();
+	let int_type = llvm_data_layout.int_ptr_type(&context);
+	let int_8_type = context.int_8_type();
+	let mut main_data = MainData::new(compiler_arguments_data, &context, &llvm_target_machine, &llvm_data_layout, int_type, int_8_type)
+		.unwrap_or_else(|error| panic!("{error}"));
+	main_data.set_int_width().unwrap_or_else(|error| panic!("{error}"));
+	test(&mut main_data)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{num::NonZeroUsize, path::PathBuf};
+
+	use crate::{ast_node::AstNode, error_location_text, position::GENERATED_POSITION};
+
+	#[test]
+	fn generated_node_location() {
+		let node = AstNode::generated();
+		let error_location = Some((PathBuf::from("main.bcz"), Some((node.start.0, Some(node.start.1), Some(node.end.1)))));
+		assert_eq!(error_location_text(&error_location), " in file main.bcz (generated)");
+		assert_eq!(node.start, GENERATED_POSITION);
+	}
+
+	#[test]
+	fn source_location() {
+		let error_location = Some((PathBuf::from("main.bcz"), Some((NonZeroUsize::new(1).unwrap(), NonZeroUsize::new(5), NonZeroUsize::new(6)))));
+		assert_eq!(error_location_text(&error_location), " in file main.bcz:1:5");
+	}
+}
@@ -2,8 +2,8 @@ use std::{mem::take, num::NonZeroUsize};
 
 use auto_const_array::auto_const_array;
 
-use crate::{ast_node::{AstNode, AstNodeVariant, Operation, Operator}, error::Error};
-use crate::token::{Keyword, OperatorSymbol, OperatorType, Separator, Token, TokenVariant};
+use crate::{ast_node::{AstNode, AstNodeVariant, Operation, Operator}, error::Error, position::Span, MainData};
+use crate::token::{Keyword, OperatorSymbol, OperatorToken, OperatorType, Separator, Token, TokenVariant};
 
 #[derive(Debug)]
 enum ParseState {
@@ -36,17 +36,32 @@ impl ParseState {
 			ParseState::FunctionArgumentsOrParameters(_, _, end) => *end,
 		}
 	}
+
+	const fn get_span(&self) -> Span {
+		match self {
+			ParseState::Token(token) => token.span(),
+			_ => (self.get_start(), self.get_end()),
+		}
+	}
 }
 
 auto_const_array! {
+	/// Binary operator symbols grouped by precedence, from the tightest binding to the loosest.
+	///
+	/// Each level is parsed from left to right before moving on to the next level, so `2 + 3 * 4` is `2 + (3 * 4)` and `10 - 2 - 3` is `(10 - 2) - 3`.
 	const BINARY_OPERATOR_PRECEDENCE: [&'static [OperatorSymbol]; _] = [
 		&[OperatorSymbol::MultiplyDereference, OperatorSymbol::DivideReciprocal, OperatorSymbol::ModuloPercent],
 		&[OperatorSymbol::AddRead, OperatorSymbol::SubtractNegate],
+		&[OperatorSymbol::BitwiseLeftShift, OperatorSymbol::BitwiseRightShift],
+		&[OperatorSymbol::ThreeWayCompare],
 		&[OperatorSymbol::LessThan, OperatorSymbol::LessThanOrEqualTo, OperatorSymbol::GreaterThan, OperatorSymbol::GreaterThanOrEqualTo],
 		&[OperatorSymbol::EqualTo, OperatorSymbol::NotEqualTo],
 		&[OperatorSymbol::AndTakeRefrence],
 		&[OperatorSymbol::Xor],
 		&[OperatorSymbol::Or],
+		&[OperatorSymbol::LogicalAnd],
+		&[OperatorSymbol::LogicalOr],
+		&[OperatorSymbol::ZeroCoalesce],
 	];
 }
 
@@ -99,11 +114,20 @@ const fn binary_operator_from_symbol(symbol: OperatorSymbol, operator_type: Oper
 		(OperatorSymbol::ThreeWayCompare, OperatorType::SignedLogicalNotShortCircuit) => Some(Operation::SignedThreeWayCompare),
 		(OperatorSymbol::ThreeWayCompare, OperatorType::FloatingPointBitwise) => Some(Operation::FloatThreeWayCompare),
 		(OperatorSymbol::Increment | OperatorSymbol::Decrement, _) => None,
+		(OperatorSymbol::Pipeline, _) => None,
+		(OperatorSymbol::LogicalAnd, OperatorType::UnsignedLogicalShortCircuit) => Some(Operation::LogicalShortCircuitAnd),
+		(OperatorSymbol::LogicalAnd, OperatorType::SignedLogicalNotShortCircuit) => Some(Operation::LogicalNotShortCircuitAnd),
+		(OperatorSymbol::LogicalOr, OperatorType::UnsignedLogicalShortCircuit) => Some(Operation::LogicalShortCircuitOr),
+		(OperatorSymbol::LogicalOr, OperatorType::SignedLogicalNotShortCircuit) => Some(Operation::LogicalNotShortCircuitOr),
+		(OperatorSymbol::LogicalAnd | OperatorSymbol::LogicalOr, OperatorType::FloatingPointBitwise) => None,
+		// `a ?? b` is `a` if it is not zero and is otherwise `b`, which is only evaluated if `a` is zero
+		(OperatorSymbol::ZeroCoalesce, OperatorType::UnsignedLogicalShortCircuit) => Some(Operation::LogicalShortCircuitOr),
+		(OperatorSymbol::ZeroCoalesce, _) => None,
 		//_ => None,
 	}
 }
 
-const fn prefix_operator_from_symbol(symbol: OperatorSymbol, operator_type: OperatorType) -> Option<Operation> {
+pub const fn prefix_operator_from_symbol(symbol: OperatorSymbol, operator_type: OperatorType) -> Option<Operation> {
 	match (symbol, operator_type) {
 		(OperatorSymbol::AddRead, _) => Some(Operation::Read),
 		(OperatorSymbol::MultiplyDereference, _) => Some(Operation::Dereference),
@@ -129,8 +153,8 @@ const fn postfix_operator_from_symbol(symbol: OperatorSymbol, operator_type: Ope
 /// Will parse a semi-colon separated expressions into a list of AST nodes if `are_arguments_or_parameters` is `false`
 /// or from comma separated function arguments/parameters if `true`.
 /// The `bool` returned is `true` if the bracketed area ends in a separator.
-fn parse_separated_expressions(mut items_being_parsed: Vec<ParseState>, are_arguments_or_parameters: bool)
-	-> Result<(Box<[AstNode]>, bool), (Error, (NonZeroUsize, NonZeroUsize))> {
+fn parse_separated_expressions(main_data: &MainData, mut items_being_parsed: Vec<ParseState>, are_arguments_or_parameters: bool)
+	-> Result<(Box<[AstNode]>, bool), (Error, Span)> {
 	let mut ast_nodes_out: Vec<AstNode> = Vec::new();
 	loop {
 		let mut parenthesis_depth = 0usize;
@@ -142,7 +166,7 @@ fn parse_separated_expressions(mut items_being_parsed: Vec<ParseState>, are_argu
 					parenthesis_depth += 1;
 				}
 				if separator.is_close_parenthesis() {
-					parenthesis_depth = parenthesis_depth.checked_sub(1).ok_or_else(|| (Error::TooManyCloseParentheses, item.get_start()))?;
+					parenthesis_depth = parenthesis_depth.checked_sub(1).ok_or_else(|| (Error::TooManyCloseParentheses, item.get_span()))?;
 				}
 				if parenthesis_depth == 0 && (
 					((!are_arguments_or_parameters) && *separator == Separator::Semicolon) || (are_arguments_or_parameters && *separator == Separator::Comma)
@@ -165,11 +189,12 @@ fn parse_separated_expressions(mut items_being_parsed: Vec<ParseState>, are_argu
 		}
 		if length == 0 {
 			if are_arguments_or_parameters && !is_last {
-				return Err((Error::BlankExpression, items_being_parsed.first().unwrap().get_start()));
+				return Err((Error::BlankExpression, items_being_parsed.first().unwrap().get_span()));
 			}
 		}
 		else {
-			ast_nodes_out.push(parse_expression(expression_items)?);
+			main_data.check_deadline(expression_items[0].get_span())?;
+			ast_nodes_out.push(parse_expression(main_data, expression_items)?);
 		}
 		// Return if at the end
 		if is_last {
@@ -179,7 +204,7 @@ fn parse_separated_expressions(mut items_being_parsed: Vec<ParseState>, are_argu
 }
 
 /// Parses a single expression into an AST node.
-fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode, (Error, (NonZeroUsize, NonZeroUsize))> {
+fn parse_expression(main_data: &MainData, mut items_being_parsed: Vec<ParseState>) -> Result<AstNode, (Error, Span)> {
 	// Parse bracketed expressions
 	let mut index = 0;
 	while index < items_being_parsed.len() {
@@ -202,7 +227,7 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 			};
 			let length = match length {
 				Some(length) => length,
-				None => return Err((Error::TooManyOpenParentheses, items_being_parsed.last().unwrap().get_end())),
+				None => return Err((Error::TooManyOpenParentheses, items_being_parsed[index].get_span())),
 			};
 			// Remove parenthesised area into vec
 			let mut parenthesised_items: Vec<ParseState> = items_being_parsed.drain(index..index + length + 1).collect();
@@ -220,21 +245,21 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 			if (open_separator == Separator::OpenParenthesis && close_separator != Separator::CloseParenthesis) ||
 				(open_separator == Separator::OpenCurlyParenthesis && close_separator != Separator::CloseCurlyParenthesis) ||
 				(open_separator == Separator::OpenSquareParenthesis && close_separator != Separator::CloseSquareParenthesis) {
-				return Err((Error::ParenthesisMismatch(open_separator, close_separator), close_parenthesis.get_start()));
+				return Err((Error::ParenthesisMismatch(open_separator, open_parenthesis.get_start(), close_separator), close_parenthesis.get_span()));
 			}
 			// Parse bracketed area
 			let result_of_parse = match open_separator {
 				Separator::OpenParenthesis => {
-					let (arguments_or_parameters, _) = parse_separated_expressions(parenthesised_items, true)?;
+					let (arguments_or_parameters, _) = parse_separated_expressions(main_data, parenthesised_items, true)?;
 					ParseState::FunctionArgumentsOrParameters(arguments_or_parameters, open_parenthesis.get_start(), close_parenthesis.get_end())
 				}
 				Separator::OpenCurlyParenthesis => {
-					let (expressions, result_is_undefined) = parse_separated_expressions(parenthesised_items, false)?;
+					let (expressions, result_is_undefined) = parse_separated_expressions(main_data, parenthesised_items, false)?;
 					ParseState::AstNode(AstNode {
 						start: open_parenthesis.get_start(), end: close_parenthesis.get_end(), variant: AstNodeVariant::Block(expressions, result_is_undefined)
 					})
 				},
-				Separator::OpenSquareParenthesis => return Err((Error::FeatureNotYetImplemented("Index operator".into()), open_parenthesis.get_start())),
+				Separator::OpenSquareParenthesis => return Err((Error::FeatureNotYetImplemented("Index operator".into()), open_parenthesis.get_span())),
 				_ => unreachable!(),
 			};
 			// Insert result of parse back into list
@@ -242,6 +267,61 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 		}
 		index += 1;
 	}
+	// Parse grouping parentheses, parentheses containing one expression group it unless they directly follow an expression, keyword or call arguments (a call)
+	// or are directly followed by an expression or keyword (the parameters of a function definition)
+	for index in 0..items_being_parsed.len() {
+		if !matches!(&items_being_parsed[index], ParseState::FunctionArgumentsOrParameters(expressions, _, _) if expressions.len() == 1) {
+			continue;
+		}
+		let follows_callee = index > 0 && matches!(
+			&items_being_parsed[index - 1],
+			ParseState::AstNode(..) | ParseState::Token(Token { variant: TokenVariant::Keyword(..), .. }) | ParseState::FunctionArgumentsOrParameters(..)
+		);
+		let precedes_function_body = matches!(
+			items_being_parsed.get(index + 1), Some(ParseState::AstNode(..) | ParseState::Token(Token { variant: TokenVariant::Keyword(..), .. }))
+		);
+		if follows_callee || precedes_function_body {
+			continue;
+		}
+		let grouped_expression = match &mut items_being_parsed[index] {
+			ParseState::FunctionArgumentsOrParameters(expressions, _, _) => take(expressions).into_vec().pop().unwrap(),
+			_ => unreachable!(),
+		};
+		items_being_parsed[index] = ParseState::AstNode(grouped_expression);
+	}
+	// Parse member accesses
+	let mut index = 1;
+	while index < items_being_parsed.len() {
+		// Make sure the item is a period with an expression to the left of it
+		if !matches!(&items_being_parsed[index], ParseState::Token(Token { variant: TokenVariant::Separator(Separator::Period), .. })) ||
+			!matches!(&items_being_parsed[index - 1], ParseState::AstNode(..)) ||
+			matches!(items_being_parsed.get(index + 1), Some(ParseState::Token(Token { variant: TokenVariant::Separator(Separator::Period), .. }))) {
+			index += 1;
+			continue;
+		}
+		// Get the member name to the right of the period
+		let period = items_being_parsed.remove(index);
+		if index >= items_being_parsed.len() {
+			return Err((Error::ExpectedIdentifier, (period.get_end(), period.get_end())));
+		}
+		let (member_name, member_name_end) = match items_being_parsed.remove(index) {
+			ParseState::AstNode(AstNode { variant: AstNodeVariant::Identifier(name), end, .. }) => (name, end),
+			other => return Err((Error::ExpectedIdentifier, other.get_span())),
+		};
+		// Get the expression to the left of the period
+		let accessed = match items_being_parsed.remove(index - 1) {
+			ParseState::AstNode(ast_node) => ast_node,
+			_ => unreachable!(),
+		};
+		// Construct member access node, the next period will use it as its left operand so that accesses nest from left to right
+		let member_access_ast_node = AstNode {
+			start: accessed.start,
+			end: member_name_end,
+			variant: AstNodeVariant::MemberAccess(Box::new(accessed), member_name),
+		};
+		// Insert back into list
+		items_being_parsed.insert(index - 1, ParseState::AstNode(member_access_ast_node));
+	}
 	// Parse function calls
 	let mut index = 1;
 	'w: while index < items_being_parsed.len() {
@@ -250,6 +330,8 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 			let (_, arguments_end) = (*arguments_start, *arguments_end);
 			// Make sure the item to the left is not a parsed expression
 			match &items_being_parsed[index - 1] {
+				// Keywords with arguments that are followed by a child node, such as the function in `@section(".fastcode") (a) { a }`
+				ParseState::AstNode(AstNode { variant: AstNodeVariant::Keyword(Keyword::Section | Keyword::Label | Keyword::DoWhile | Keyword::For, _, None), .. }) => {}
 				// User defined functions
 				ParseState::AstNode(..) => {
 					// Get function pointer
@@ -317,10 +399,10 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 	// Parse unary prefix operators
 	for index in (0..items_being_parsed.len().saturating_sub(1)).rev() {
 		// Make sure the item is an operator token
-		let (operator_symbol, operator_type, is_assignment, start) = match &items_being_parsed[index] {
+		let (operator_symbol, operator_type, is_assignment, start, end) = match &items_being_parsed[index] {
 			ParseState::Token(Token {
-				variant: TokenVariant::Operator(operator_symbol, operator_type, is_assignment, _), start, end: _
-			}) => (*operator_symbol, *operator_type, *is_assignment, *start),
+				variant: TokenVariant::Operator(OperatorToken { base: operator_symbol, operator_type, is_assignment, .. }), start, end
+			}) => (*operator_symbol, *operator_type, *is_assignment, *start, *end),
 			_ => continue,
 		};
 		// Make sure the item to the left is not a parsed expression
@@ -333,23 +415,22 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 		}
 		// Make sure it's not an assignment
 		if is_assignment {
-			return Err((Error::FeatureNotYetImplemented("Augmented prefix operators".into()), start));
+			return Err((Error::FeatureNotYetImplemented("Augmented prefix operators".into()), (start, end)));
 		}
-		// Make sure the base operator is Some
-		let operator_symbol = match operator_symbol {
-			Some(operator_symbol) => operator_symbol,
-			None => return Err((Error::NoOperatorBase, start)),
-		};
-		// Get operator
-		let operator = match prefix_operator_from_symbol(operator_symbol, operator_type) {
-			Some(operator) => operator,
-			None => return Err((Error::InvalidPrefixOperatorSymbol(operator_symbol), start)),
+		// Get operator, a '~' on its own is a bitwise not
+		let operator = match (operator_symbol, operator_type) {
+			(None, OperatorType::FloatingPointBitwise) => Operation::BitwiseNot,
+			(None, _) => return Err((Error::NoOperatorBase, (start, end))),
+			(Some(operator_symbol), operator_type) => match prefix_operator_from_symbol(operator_symbol, operator_type) {
+				Some(operator) => operator,
+				None => return Err((Error::InvalidPrefixOperatorSymbol(operator_symbol), (start, end))),
+			},
 		};
 		// Get operand
 		let operand = items_being_parsed.remove(index + 1);
 		let operand = match operand {
 			ParseState::AstNode(ast_node) => ast_node,
-			_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, operand.get_start())),
+			_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, operand.get_span())),
 		};
 		// Construct operator node
 		let operator_ast_node = AstNode {
@@ -365,7 +446,7 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 	while index < items_being_parsed.len().saturating_sub(1) {
 		// Make sure the item is an operator token
 		if let ParseState::Token(Token {
-			variant: TokenVariant::Operator(operator_symbol, operator_type, is_assignment, _), start, end
+			variant: TokenVariant::Operator(OperatorToken { base: operator_symbol, operator_type, is_assignment, .. }), start, end
 		}) = &items_being_parsed[index] {
 			let (operator_symbol, operator_type, is_assignment, start, end) =
 			(*operator_symbol, *operator_type, *is_assignment, *start, *end);
@@ -376,23 +457,23 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 			) {
 				// Assignments not yet implemented
 				if is_assignment {
-					return Err((Error::FeatureNotYetImplemented("Augmented suffix operators".into()), start));
+					return Err((Error::FeatureNotYetImplemented("Augmented suffix operators".into()), (start, end)));
 				}
 				// Make sure the base operator is Some
 				let operator_symbol = match operator_symbol {
 					Some(operator_symbol) => operator_symbol,
-					None => return Err((Error::NoOperatorBase, start)),
+					None => return Err((Error::NoOperatorBase, (start, end))),
 				};
 				// Get operator
 				let operator = match postfix_operator_from_symbol(operator_symbol, operator_type) {
 					Some(operator) => operator,
-					None => return Err((Error::InvalidPrefixOperatorSymbol(operator_symbol), start)),
+					None => return Err((Error::InvalidPrefixOperatorSymbol(operator_symbol), (start, end))),
 				};
 				// Get operand
 				let operand = items_being_parsed.remove(index - 1);
 				let operand = match operand {
 					ParseState::AstNode(ast_node) => ast_node,
-					_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, operand.get_start())),
+					_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, operand.get_span())),
 				};
 				// Construct operator node
 				let operator_ast_node = AstNode {
@@ -409,22 +490,26 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 	}
 	// Parse non-augmented binary operators
 	for operator_precedence_level in BINARY_OPERATOR_PRECEDENCE {
+		// Comparisons such as `a < b < c` chain rather than comparing the result of `a < b`
+		let is_comparison_level = operator_precedence_level.contains(&OperatorSymbol::LessThan);
+		// If the expression to the left of the index was just parsed from an operator in this level
+		let mut left_is_from_this_level = false;
 		// Search for operators in the precedence level
 		let mut index = 1;
 		while index < items_being_parsed.len().saturating_sub(1) {
 			if let ParseState::Token(Token {
-				variant: TokenVariant::Operator(operator_symbol, operator_type, false, false), start, end: _
+				variant: TokenVariant::Operator(OperatorToken { base: operator_symbol, operator_type, is_assignment: false, is_l_value_assignment: false }), start, end
 			}) = &items_being_parsed[index] {
 				let operator_symbol = match operator_symbol {
 					Some(operator_symbol) => *operator_symbol,
-					None => return Err((Error::NoOperatorBase, *start)),
+					None => return Err((Error::NoOperatorBase, (*start, *end))),
 				};
 				if operator_precedence_level.contains(&operator_symbol) {
 					// If we find one
 					// Convert to AST operator
 					let operator = match binary_operator_from_symbol(operator_symbol, *operator_type) {
 						Some(operator) => operator,
-						None => return Err((Error::InvalidInfixOperatorSymbol(operator_symbol), *start)),
+						None => return Err((Error::InvalidInfixOperatorSymbol(operator_symbol), (*start, *end))),
 					};
 					// Get left and right operands
 					let left_operand = items_being_parsed.remove(index - 1);
@@ -432,38 +517,82 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 					let right_operand = items_being_parsed.remove(index - 1);
 					let left_operand = match left_operand {
 						ParseState::AstNode(ast_node) => ast_node,
-						_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, left_operand.get_start())),
+						_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, left_operand.get_span())),
 					};
 					let right_operand = match right_operand {
 						ParseState::AstNode(ast_node) => ast_node,
-						_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, right_operand.get_start())),
+						_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, right_operand.get_span())),
 					};
-					// Construct operator node
-					let operator_ast_node = AstNode {
-						start: left_operand.start,
-						end: right_operand.end,
-						variant: AstNodeVariant::Operator(Operator::Normal(operator), [left_operand, right_operand].into()),
+					// Construct operator node, or add to a comparison chain
+					let (start, end) = (left_operand.start, right_operand.end);
+					let variant = match left_operand.variant {
+						AstNodeVariant::Operator(Operator::Normal(left_operation), left_operands) if is_comparison_level && left_is_from_this_level =>
+							AstNodeVariant::ComparisonChain([left_operation, operator].into(), left_operands.into_vec().into_iter().chain([right_operand]).collect()),
+						AstNodeVariant::ComparisonChain(operations, chain_operands) if is_comparison_level && left_is_from_this_level =>
+							AstNodeVariant::ComparisonChain(
+								operations.into_vec().into_iter().chain([operator]).collect(), chain_operands.into_vec().into_iter().chain([right_operand]).collect()
+							),
+						left_operand_variant => AstNodeVariant::Operator(
+							Operator::Normal(operator), [AstNode { variant: left_operand_variant, ..left_operand }, right_operand].into()
+						),
 					};
+					let operator_ast_node = AstNode { start, end, variant };
 					// Insert back into list
 					items_being_parsed.insert(index - 1, ParseState::AstNode(operator_ast_node));
+					left_is_from_this_level = true;
 					continue;
 				}
 			}
 			index += 1;
+			left_is_from_this_level = false;
+		}
+	}
+	// Parse pipeline operators from left to right, `x |> f` is parsed as the function call `f(x)`
+	let mut index = 1;
+	while index < items_being_parsed.len().saturating_sub(1) {
+		if let ParseState::Token(Token {
+			variant: TokenVariant::Operator(OperatorToken { base: Some(OperatorSymbol::Pipeline), operator_type, is_assignment: false, is_l_value_assignment: false }), start, end
+		}) = &items_being_parsed[index] {
+			if !matches!(operator_type, OperatorType::UnsignedLogicalShortCircuit) {
+				return Err((Error::InvalidInfixOperatorSymbol(OperatorSymbol::Pipeline), (*start, *end)));
+			}
+			// Get the argument and function operands
+			let argument = items_being_parsed.remove(index - 1);
+			items_being_parsed.remove(index - 1);
+			let function = items_being_parsed.remove(index - 1);
+			let argument = match argument {
+				ParseState::AstNode(ast_node) => ast_node,
+				_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, argument.get_span())),
+			};
+			let function = match function {
+				ParseState::AstNode(ast_node) => ast_node,
+				_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, function.get_span())),
+			};
+			// Construct function call node
+			let function_call_ast_node = AstNode {
+				start: argument.start,
+				end: function.end,
+				variant: AstNodeVariant::FunctionCall(Box::new(function), [argument].into()),
+			};
+			// Insert back into list
+			items_being_parsed.insert(index - 1, ParseState::AstNode(function_call_ast_node));
+			continue;
 		}
+		index += 1;
 	}
-	// TODO: Parse ternary operators
+	// Parse ternary operators, there is no if/else so these are searched for from right to left
+	// so that a chain such as `a ? b : c ? d : e` nests the next ternary in the else operand like an else if chain would
 	let mut index = items_being_parsed.len().saturating_sub(2);
 	while index > 0 {
 		if let ParseState::Token(Token {
-			variant: TokenVariant::Operator(operator_symbol, operator_type, false, _), start, end: _
+			variant: TokenVariant::Operator(OperatorToken { base: operator_symbol, operator_type, is_assignment: false, .. }), start, end
 		}) = &items_being_parsed[index] { 'a: {
 			// Get the AST operator
 			let operator = match operator_symbol {
 				Some(OperatorSymbol::TernaryFirst) => match operator_type {
 					OperatorType::UnsignedLogicalShortCircuit => Operation::ShortCircuitTernary,
 					OperatorType::SignedLogicalNotShortCircuit => Operation::NotShortCircuitTernary,
-					OperatorType::FloatingPointBitwise => return Err((Error::InvalidTernaryOperator, *start)),
+					OperatorType::FloatingPointBitwise => return Err((Error::InvalidTernaryOperator, (*start, *end))),
 				},
 				_ => break 'a,
 			};
@@ -471,12 +600,12 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 			let second_operator_index = items_being_parsed.iter()
 				.skip(index)
 				.position(|item| matches!(item, ParseState::Token(Token {
-					variant: TokenVariant::Operator(Some(OperatorSymbol::TernarySecond), OperatorType::UnsignedLogicalShortCircuit, false, _),
+					variant: TokenVariant::Operator(operator @ OperatorToken { operator_type: OperatorType::UnsignedLogicalShortCircuit, .. }),
 					start: _,
 					end: _,
 				}
-				)))
-				.ok_or_else(|| (Error::UnmatchedTernary, *start))?;
+				) if operator.is_plain_symbol(OperatorSymbol::TernarySecond)))
+				.ok_or_else(|| (Error::UnmatchedTernary, (*start, *end)))?;
 			// Remove operators and operands
 			let left_operand = items_being_parsed.remove(index - 1);
 			items_being_parsed.remove(index - 1);
@@ -484,15 +613,15 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 			items_being_parsed.remove(index + second_operator_index - 2);
 			let center_operand = items_being_parsed.drain(index - 1..index + second_operator_index - 2).collect();
 			// Parse expression between the "?" and ":" operators
-			let center_operand = parse_expression(center_operand)?;
+			let center_operand = parse_expression(main_data, center_operand)?;
 			// Get left and right operands
 			let left_operand = match left_operand {
 				ParseState::AstNode(ast_node) => ast_node,
-				_ => return Err((Error::TernaryOperatorNotUsedOnExpressions, left_operand.get_start())),
+				_ => return Err((Error::TernaryOperatorNotUsedOnExpressions, left_operand.get_span())),
 			};
 			let right_operand = match right_operand {
 				ParseState::AstNode(ast_node) => ast_node,
-				_ => return Err((Error::TernaryOperatorNotUsedOnExpressions, right_operand.get_start())),
+				_ => return Err((Error::TernaryOperatorNotUsedOnExpressions, right_operand.get_span())),
 			};
 			// Construct operator node
 			let operator_ast_node = AstNode {
@@ -515,12 +644,12 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 		};
 		// Get function body
 		if index == items_being_parsed.len().saturating_sub(1) {
-			return Err((Error::FunctionParametersWithoutBody, parameters_end));
+			return Err((Error::FunctionParametersWithoutBody, (parameters_end, parameters_end)));
 		}
 		let function_body = items_being_parsed.remove(index + 1);
 		let function_body_ast_node = match function_body {
 			ParseState::AstNode(ast_node) => ast_node,
-			_ => return Err((Error::FunctionParametersWithoutBody, function_body.get_start())),
+			_ => return Err((Error::FunctionParametersWithoutBody, function_body.get_span())),
 		};
 		// Get function parameters
 		let function_parameters = match &mut items_being_parsed[index] {
@@ -543,22 +672,25 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 			ParseState::AstNode(AstNode { variant: AstNodeVariant::Keyword(keyword, arguments, child), start, end: keyword_end }) => {
 					match keyword {
 						// Keywords without arguments
-						Keyword::EntryPoint | Keyword::Loop | Keyword::Break | Keyword::Continue | Keyword::Export => {},
+						Keyword::EntryPoint | Keyword::Loop | Keyword::Break | Keyword::Continue | Keyword::Export | Keyword::Const => {},
 						// Keywords with arguments
-						Keyword::Write | Keyword::Stack | Keyword::Import | Keyword::Link | Keyword::SystemConstant => continue,
+						Keyword::Write | Keyword::Stack | Keyword::Import | Keyword::Link | Keyword::SystemConstant | Keyword::Assert | Keyword::RuntimeAssert |
+						Keyword::SizeOf => continue,
+						// Keywords with both arguments and a child node
+						Keyword::Section | Keyword::Label | Keyword::DoWhile | Keyword::For => {},
 					};
 					(*keyword, take(arguments), take(child), *start, *keyword_end)
 				}
 			_ => continue,
 		};
 		if child.is_some() {
-			return Err((Error::KeywordWithTwoChildren, start));
+			return Err((Error::KeywordWithTwoChildren, (start, keyword_end)));
 		}
 		// Take child node
 		let child_node = match (index + 1) < items_being_parsed.len() {
 			true => Some(match items_being_parsed.remove(index + 1) {
 				ParseState::AstNode(ast_node) => ast_node,
-				_ => return Err((Error::MetadataItemWithoutChildNode, start)),
+				_ => return Err((Error::MetadataItemWithoutChildNode, (start, keyword_end))),
 			}),
 			false => None,
 		};
@@ -577,11 +709,41 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 		// Insert back into list
 		items_being_parsed[index] = ParseState::AstNode(metadata_ast_node);
 	}
+	// Parse ranges such as `0..5`, two periods between two expressions
+	let mut index = 1;
+	while index + 2 < items_being_parsed.len() {
+		if !matches!(&items_being_parsed[index], ParseState::Token(Token { variant: TokenVariant::Separator(Separator::Period), .. })) ||
+			!matches!(&items_being_parsed[index + 1], ParseState::Token(Token { variant: TokenVariant::Separator(Separator::Period), .. })) {
+			index += 1;
+			continue;
+		}
+		// Get the start and end of the range
+		let range_start = items_being_parsed.remove(index - 1);
+		items_being_parsed.remove(index - 1);
+		items_being_parsed.remove(index - 1);
+		let range_end = items_being_parsed.remove(index - 1);
+		let range_start = match range_start {
+			ParseState::AstNode(ast_node) => ast_node,
+			_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, range_start.get_span())),
+		};
+		let range_end = match range_end {
+			ParseState::AstNode(ast_node) => ast_node,
+			_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, range_end.get_span())),
+		};
+		// Construct range node
+		let range_ast_node = AstNode {
+			start: range_start.start,
+			end: range_end.end,
+			variant: AstNodeVariant::Range(Box::new(range_start), Box::new(range_end)),
+		};
+		// Insert back into list
+		items_being_parsed.insert(index - 1, ParseState::AstNode(range_ast_node));
+	}
 	// Parse augmented binary operators
 	let mut index = items_being_parsed.len().saturating_sub(2);
 	while index > 0 {
 		if let ParseState::Token(Token {
-			variant: TokenVariant::Operator(operator_symbol, operator_type, true, is_l_value_assignment), start, end: _
+			variant: TokenVariant::Operator(OperatorToken { base: operator_symbol, operator_type, is_assignment: true, is_l_value_assignment }), start, end
 		})
 		= &items_being_parsed[index] {
 			// If we find one
@@ -590,7 +752,7 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 				false => match operator_symbol {
 					Some(operator_symbol) => Operator::Augmented(match binary_operator_from_symbol(*operator_symbol, *operator_type) {
 						Some(operator) => operator,
-						None => return Err((Error::BinaryOperatorNotUsedOnExpressions, *start)),
+						None => return Err((Error::BinaryOperatorNotUsedOnExpressions, (*start, *end))),
 					}),
 					None => Operator::Assignment,
 				}
@@ -602,11 +764,11 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 			let right_operand = items_being_parsed.remove(index - 1);
 			let left_operand = match left_operand {
 				ParseState::AstNode(ast_node) => ast_node,
-				_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, left_operand.get_start())),
+				_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, left_operand.get_span())),
 			};
 			let right_operand = match right_operand {
 				ParseState::AstNode(ast_node) => ast_node,
-				_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, right_operand.get_start())),
+				_ => return Err((Error::BinaryOperatorNotUsedOnExpressions, right_operand.get_span())),
 			};
 			// Construct operator node
 			let operator_ast_node = AstNode {
@@ -629,20 +791,34 @@ fn parse_expression(mut items_being_parsed: Vec<ParseState>) -> Result<AstNode,
 		}
 	}
 	for item in items_being_parsed.iter() {
-		match item {
-			ParseState::Token(Token { variant: TokenVariant::Operator(..), start, .. }) =>
-				return Err((Error::OperatorUsedOnNothing, *start)),
-			ParseState::Token(Token { variant: TokenVariant::Separator(..), start, .. }) =>
-				return Err((Error::OperatorUsedOnNothing, *start)),
-			_ => {},
+		if let ParseState::Token(token @ Token { variant: TokenVariant::Operator(..) | TokenVariant::Separator(..), .. }) = item {
+			return Err((Error::OperatorUsedOnNothing, token.span()));
 		}
 	}
-	let start = items_being_parsed.first().unwrap().get_start();
-	return Err((Error::FeatureNotYetImplemented("Feature".into()), start));
+	let span = items_being_parsed.first().unwrap().get_span();
+	return Err((Error::FeatureNotYetImplemented("Feature".into()), span));
 }
 
+/// How deep parentheses and AST nodes can be nested, the parser and each pass over the AST are recursive so deeper nesting could overflow the stack.
+const MAX_EXPRESSION_DEPTH: usize = 256;
+
 /// Takes in the tokens from tokenizing a file and parses each semi-colon separated global expression into a returned AST node.
-pub fn parse_tokens(tokens: Vec<Token>) -> Result<Box<[AstNode]>, (Error, (NonZeroUsize, NonZeroUsize))> {
+pub fn parse_tokens(main_data: &MainData, tokens: Vec<Token>) -> Result<Box<[AstNode]>, (Error, Span)> {
+	// Make sure parentheses are not nested too deep to parse
+	let mut parenthesis_depth = 0usize;
+	for token in tokens.iter() {
+		if let TokenVariant::Separator(separator) = &token.variant {
+			if separator.is_open_parenthesis() {
+				parenthesis_depth += 1;
+				if parenthesis_depth > MAX_EXPRESSION_DEPTH {
+					return Err((Error::ExpressionTooDeep(MAX_EXPRESSION_DEPTH), token.span()));
+				}
+			}
+			if separator.is_close_parenthesis() {
+				parenthesis_depth = parenthesis_depth.saturating_sub(1);
+			}
+		}
+	}
 	// Wrap all the tokens in a parse state object
 	let items_being_parsed: Vec<ParseState> = tokens.into_iter()
 		.map(|token| match token {
@@ -669,5 +845,196 @@ pub fn parse_tokens(tokens: Vec<Token>) -> Result<Box<[AstNode]>, (Error, (NonZe
 		})
 		.collect();
 	// Parse semi-colon separated expressions
-	Ok(parse_separated_expressions(items_being_parsed, false)?.0)
-}
\ No newline at end of file
+	let ast_nodes = parse_separated_expressions(main_data, items_being_parsed, false)?.0;
+	// Operator chains such as `1 + 1 + 1` nest without parentheses so the depth of the parsed nodes is also checked, without recursing
+	let mut nodes_to_check: Vec<(&AstNode, usize)> = ast_nodes.iter().map(|ast_node| (ast_node, 1)).collect();
+	while let Some((ast_node, depth)) = nodes_to_check.pop() {
+		if depth > MAX_EXPRESSION_DEPTH {
+			return Err((Error::ExpressionTooDeep(MAX_EXPRESSION_DEPTH), ast_node.span()));
+		}
+		nodes_to_check.extend(ast_node.children().into_iter().map(|child| (child, depth + 1)));
+	}
+	Ok(ast_nodes)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::num::NonZeroUsize;
+
+	use crate::{ast_node::{AstNode, AstNodeVariant, Operation, Operator}, error::Error, position::{Position, Span}, token::{Separator, Tokenizer}, with_main_data};
+
+	use super::parse_tokens;
+
+	/// Tokenizes and parses `source`, panicking if it has an error.
+	fn parse_source(source: &str) -> Box<[AstNode]> {
+		with_main_data(&["--check"], |main_data| {
+			let tokens = Tokenizer::new(source).tokenize(main_data).unwrap_or_else(|(error, _)| panic!("{error}"));
+			parse_tokens(main_data, tokens).unwrap_or_else(|(error, _)| panic!("{error}"))
+		})
+	}
+
+	/// Tokenizes and parses `source`, panicking if it does not have a parse error.
+	fn parse_error(source: &str) -> (Error, Span) {
+		with_main_data(&["--check"], |main_data| {
+			let tokens = Tokenizer::new(source).tokenize(main_data).unwrap_or_else(|(error, _)| panic!("{error}"));
+			match parse_tokens(main_data, tokens) {
+				Ok(ast_nodes) => panic!("{ast_nodes:?} parsed without an error"),
+				Err(error) => error,
+			}
+		})
+	}
+
+	/// Get the value being assigned by the assignment `node`.
+	fn assigned_value(node: &AstNode) -> &AstNode {
+		match &node.variant {
+			AstNodeVariant::Operator(_, operands) => &operands[1],
+			_ => panic!("{node:?} is not an assignment"),
+		}
+	}
+
+	/// Writes an expression of constants, binary operators and ternaries with brackets around each operator, such as `(2 + (3 * 4))`.
+	fn bracketed(node: &AstNode) -> String {
+		match &node.variant {
+			AstNodeVariant::Constant(value) => value.to_string(),
+			AstNodeVariant::Operator(Operator::Normal(Operation::ShortCircuitTernary), operands) =>
+				format!("({} ? {} : {})", bracketed(&operands[0]), bracketed(&operands[1]), bracketed(&operands[2])),
+			AstNodeVariant::Operator(Operator::Normal(operation), operands) => {
+				let symbol = match operation {
+					Operation::IntegerAdd => "+",
+					Operation::IntegerSubtract => "-",
+					Operation::IntegerMultiply => "*",
+					Operation::UnsignedDivide => "/",
+					Operation::UnsignedModulo => "%",
+					Operation::LogicalShortCircuitOr => "||",
+					_ => panic!("{operation:?} is not an arithmetic operation"),
+				};
+				format!("({} {symbol} {})", bracketed(&operands[0]), bracketed(&operands[1]))
+			}
+			_ => panic!("{node:?} is not a constant or operator"),
+		}
+	}
+
+	fn position(line: usize, column: usize) -> Position {
+		(NonZeroUsize::new(line).unwrap(), NonZeroUsize::new(column).unwrap())
+	}
+
+	#[test]
+	fn member_accesses_nest_from_left_to_right() {
+		let ast_nodes = parse_source("x = a.b.c;");
+		let outer_access = assigned_value(&ast_nodes[0]);
+		let AstNodeVariant::MemberAccess(inner_access, member_name) = &outer_access.variant else {
+			panic!("{outer_access:?} is not a member access");
+		};
+		assert_eq!(&**member_name, "c");
+		assert_eq!(outer_access.span(), (position(1, 5), position(1, 10)));
+		let AstNodeVariant::MemberAccess(accessed, member_name) = &inner_access.variant else {
+			panic!("{inner_access:?} is not a member access");
+		};
+		assert_eq!(&**member_name, "b");
+		assert_eq!(inner_access.span(), (position(1, 5), position(1, 8)));
+		assert!(matches!(&accessed.variant, AstNodeVariant::Identifier(name) if &**name == "a"));
+		assert_eq!(accessed.span(), (position(1, 5), position(1, 6)));
+	}
+
+	#[test]
+	fn too_many_open_parentheses_position() {
+		let (error, span) = parse_error("x = (1 +\n\t(2);");
+		assert!(matches!(error, Error::TooManyOpenParentheses), "{error}");
+		assert_eq!(span, (position(1, 5), position(1, 6)));
+	}
+
+	#[test]
+	fn too_many_close_parentheses_position() {
+		let (error, span) = parse_error("x = (1 +\n\t2));");
+		assert!(matches!(error, Error::TooManyCloseParentheses), "{error}");
+		assert_eq!(span, (position(2, 4), position(2, 5)));
+	}
+
+	#[test]
+	fn parenthesis_mismatch_positions() {
+		let (error, span) = parse_error("x = (1 +\n\t[2));");
+		assert!(matches!(
+			error, Error::ParenthesisMismatch(Separator::OpenSquareParenthesis, open_position, Separator::CloseParenthesis) if open_position == position(2, 2)
+		), "{error}");
+		assert_eq!(span, (position(2, 4), position(2, 5)));
+	}
+
+	#[test]
+	fn pipelines_nest_from_left_to_right() {
+		let ast_nodes = parse_source("y = x |> f |> g;");
+		let outer_call = assigned_value(&ast_nodes[0]);
+		let AstNodeVariant::FunctionCall(function, arguments) = &outer_call.variant else {
+			panic!("{outer_call:?} is not a function call");
+		};
+		assert!(matches!(&function.variant, AstNodeVariant::Identifier(name) if &**name == "g"));
+		let [inner_call] = &**arguments else {
+			panic!("{arguments:?} is not one argument");
+		};
+		let AstNodeVariant::FunctionCall(function, arguments) = &inner_call.variant else {
+			panic!("{inner_call:?} is not a function call");
+		};
+		assert!(matches!(&function.variant, AstNodeVariant::Identifier(name) if &**name == "f"));
+		assert!(matches!(&**arguments, [AstNode { variant: AstNodeVariant::Identifier(name), .. }] if &**name == "x"));
+	}
+
+	#[test]
+	fn shift_assignments_are_augmented() {
+		let ast_nodes = parse_source("x >>= 1;\nx <<= 1;");
+		assert!(matches!(&ast_nodes[0].variant, AstNodeVariant::Operator(Operator::Augmented(Operation::LogicalRightBitShift), _)), "{:?}", ast_nodes[0]);
+		assert!(matches!(&ast_nodes[1].variant, AstNodeVariant::Operator(Operator::Augmented(Operation::LogicalLeftBitShift), _)), "{:?}", ast_nodes[1]);
+	}
+
+	#[test]
+	fn arithmetic_precedence_and_associativity() {
+		for (source, expected) in [
+			("x = 2 + 3 * 4;", "(2 + (3 * 4))"),
+			("x = 2 * 3 + 4;", "((2 * 3) + 4)"),
+			("x = 10 - 2 - 3;", "((10 - 2) - 3)"),
+			("x = 8 / 4 / 2;", "((8 / 4) / 2)"),
+			("x = 1 + 7 % 4 - 2;", "((1 + (7 % 4)) - 2)"),
+		] {
+			assert_eq!(bracketed(assigned_value(&parse_source(source)[0])), expected, "{source}");
+		}
+	}
+
+	#[test]
+	fn zero_coalescing_mixed_with_ternaries() {
+		// "?" is always the first operator of a ternary and "??" parses to the same operation as "||" at a lower precedence
+		for (source, expected) in [
+			("x = 1 ? 2 : 3 ?? 4;", "(1 ? 2 : (3 || 4))"),
+			("x = 1 ?? 2 ? 3 : 4;", "((1 || 2) ? 3 : 4)"),
+			("x = 1 ? 2 ?? 3 : 4;", "(1 ? (2 || 3) : 4)"),
+			("x = 1 ? 2 : 3 ? 4 : 5;", "(1 ? 2 : (3 ? 4 : 5))"),
+			("x = 1 ?? 2 || 3;", "(1 || (2 || 3))"),
+		] {
+			assert_eq!(bracketed(assigned_value(&parse_source(source)[0])), expected, "{source}");
+		}
+		let (error, _) = parse_error("x = 1 ? 2;");
+		assert!(matches!(error, Error::UnmatchedTernary), "{error}");
+	}
+
+	#[test]
+	fn grouping_parentheses_and_calls() {
+		assert_eq!(bracketed(assigned_value(&parse_source("x = (1 + 2) * 3;")[0])), "((1 + 2) * 3)");
+		// The result of f(1) is called
+		let ast_nodes = parse_source("x = f(1)(2);");
+		let outer_call = assigned_value(&ast_nodes[0]);
+		let AstNodeVariant::FunctionCall(function, arguments) = &outer_call.variant else {
+			panic!("{outer_call:?} is not a function call");
+		};
+		assert!(matches!(&**arguments, [AstNode { variant: AstNodeVariant::Constant(2), .. }]));
+		let AstNodeVariant::FunctionCall(function, arguments) = &function.variant else {
+			panic!("{function:?} is not a function call");
+		};
+		assert!(matches!(&function.variant, AstNodeVariant::Identifier(name) if &**name == "f"));
+		assert!(matches!(&**arguments, [AstNode { variant: AstNodeVariant::Constant(1), .. }]));
+		// A grouped function is called
+		let ast_nodes = parse_source("x = (f)(1);");
+		let call = assigned_value(&ast_nodes[0]);
+		let AstNodeVariant::FunctionCall(function, arguments) = &call.variant else {
+			panic!("{call:?} is not a function call");
+		};
+		assert!(matches!(&function.variant, AstNodeVariant::Identifier(name) if &**name == "f"));
+		assert!(matches!(&**arguments, [AstNode { variant: AstNodeVariant::Constant(1), .. }]));
+	}
+}
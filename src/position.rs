@@ -0,0 +1,16 @@
+use std::num::NonZeroUsize;
+
+/// A line and column in a source file.
+pub type Position = (NonZeroUsize, NonZeroUsize);
+
+/// The start of a piece of source code and the position of the char after its last char.
+pub type Span = (Position, Position);
+
+/// The position of nodes that are generated by the compiler rather than read from a source file,
+/// it is a position that can't be in a real file so that generated nodes are not mistaken for being at 1:1.
+pub const GENERATED_POSITION: Position = (NonZeroUsize::MAX, NonZeroUsize::MAX);
+
+/// Returns if a line number is the line of `GENERATED_POSITION`.
+pub const fn is_generated_line(line: NonZeroUsize) -> bool {
+	line.get() == GENERATED_POSITION.0.get()
+}
@@ -0,0 +1,52 @@
+use std::time::{Duration, Instant};
+
+use crate::json_string;
+
+/// A span of time spent in a phase of compilation, written to the `--time-trace` file as a Chrome tracing complete event.
+pub struct TimeTraceEvent {
+	/// The name of the phase, such as `Parse`.
+	name: &'static str,
+	/// What the phase was working on, such as the file or global variable being built.
+	detail: String,
+	/// When the phase started, relative to the start of compilation.
+	start: Duration,
+	/// How long the phase took.
+	duration: Duration,
+}
+
+/// The phases of compilation that have been timed for `--time-trace`.
+pub struct TimeTrace {
+	/// When compilation started, event times are relative to this.
+	start: Instant,
+	events: Vec<TimeTraceEvent>,
+}
+
+impl TimeTrace {
+	pub fn new() -> Self {
+		Self {
+			start: Instant::now(),
+			events: Vec::new(),
+		}
+	}
+
+	/// Record that the phase `name` ran from `started_at` until now.
+	pub fn record(&mut self, name: &'static str, detail: String, started_at: Instant) {
+		self.events.push(TimeTraceEvent {
+			name,
+			detail,
+			start: started_at.saturating_duration_since(self.start),
+			duration: started_at.elapsed(),
+		});
+	}
+
+	/// Get the events as a Chrome tracing JSON object that can be loaded in `chrome://tracing`, times are in microseconds.
+	pub fn to_json(&self) -> String {
+		let events: Vec<String> = self.events.iter()
+			.map(|event| format!(
+				"{{\"name\":{},\"cat\":\"bcz\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":1,\"args\":{{\"detail\":{}}}}}",
+				json_string(event.name), event.start.as_micros(), event.duration.as_micros(), json_string(&event.detail),
+			))
+			.collect();
+		format!("{{\"traceEvents\":[\n{}\n]}}\n", events.join(",\n"))
+	}
+}
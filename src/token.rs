@@ -3,7 +3,7 @@ use std::{collections::{HashMap, HashSet}, num::NonZeroUsize};
 
 use strum_macros::{EnumDiscriminants, EnumIter};
 
-use crate::{error::Error, MainData};
+use crate::{compiler_arguments::LanguageVersion, error::Error, parse::prefix_operator_from_symbol, position::{Position, Span}, MainData};
 
 #[derive(EnumIter, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Separator {
@@ -60,6 +60,14 @@ pub enum Keyword {
 	Import,
 	Export,
 	SystemConstant,
+	Section,
+	Const,
+	Label,
+	DoWhile,
+	Assert,
+	RuntimeAssert,
+	SizeOf,
+	For,
 }
 
 impl Keyword {
@@ -75,6 +83,14 @@ impl Keyword {
 			Self::Import => "import",
 			Self::Export => "export",
 			Self::SystemConstant => "_system_constant",
+			Self::Section => "section",
+			Self::Const => "const",
+			Self::Label => "label",
+			Self::DoWhile => "do_while",
+			Self::Assert => "assert",
+			Self::RuntimeAssert => "runtime_assert",
+			Self::SizeOf => "sizeof",
+			Self::For => "for",
 		}
 	}
 
@@ -83,6 +99,11 @@ impl Keyword {
 			.map(|keyword| (keyword.get_symbol(), keyword))
 			.collect()
 	}
+
+	/// Is this keyword metadata that changes how the global it is applied to is built, rather than an expression that is evaluated.
+	pub const fn is_metadata(self) -> bool {
+		matches!(self, Self::EntryPoint | Self::Link | Self::Export | Self::Const | Self::Section)
+	}
 }
 
 #[derive(EnumIter, Clone, Copy, Debug, PartialEq, Eq)]
@@ -110,6 +131,10 @@ pub enum OperatorSymbol {
 	Decrement,
 	BitwiseLeftShift,
 	BitwiseRightShift,
+	Pipeline,
+	LogicalAnd,
+	LogicalOr,
+	ZeroCoalesce,
 }
 
 impl OperatorSymbol {
@@ -137,6 +162,10 @@ impl OperatorSymbol {
 			Self::Decrement => "--",
 			Self::BitwiseLeftShift => "<<",
 			Self::BitwiseRightShift => ">>",
+			Self::Pipeline => "|>",
+			Self::LogicalAnd => "&&",
+			Self::LogicalOr => "||",
+			Self::ZeroCoalesce => "??",
 		}
 	}
 
@@ -152,6 +181,12 @@ impl OperatorSymbol {
 }
 
 #[derive(EnumIter, Clone, Copy, Debug)]
+/// The type of an operator, selected by the char that prefixes the operator symbol.
+///
+/// A '~' that prefixes another operator symbol selects the floating point/bitwise version of that operator, such as `~+` for a float add.
+/// A '~' that is used on its own as a prefix operator, such as in `~x`, is a bitwise not, the same as `~!x`.
+/// The bitwise operators `~&`, `~|`, `~^` and `~!` work on the raw bit pattern of their operands, so a float can have its sign or exponent bits
+/// masked or flipped, such as `x ~& 0x7FFFFFFFFFFFFFFF` to get the absolute value of a 64-bit float.
 pub enum OperatorType {
 	UnsignedLogicalShortCircuit,
 	SignedLogicalNotShortCircuit,
@@ -176,6 +211,40 @@ impl OperatorType {
 	}
 }
 
+#[derive(Clone, Copy, Debug)]
+/// An operator token such as `+`, `$<=`, `~+=` or `@=`.
+pub struct OperatorToken {
+	/// The operator symbol, `None` for a plain assignment such as `=` or `$=`.
+	pub base: Option<OperatorSymbol>,
+	/// The type selected by the prefix char of the operator.
+	pub operator_type: OperatorType,
+	/// If the operator ends with a '=' and assigns its result, such as `=` or `+=`.
+	pub is_assignment: bool,
+	/// If the operator is the `@=` l-value assignment operator.
+	pub is_l_value_assignment: bool,
+}
+
+impl OperatorToken {
+	pub const fn new(base: Option<OperatorSymbol>, operator_type: OperatorType, is_assignment: bool) -> Self {
+		Self { base, operator_type, is_assignment, is_l_value_assignment: false }
+	}
+
+	/// The `@=` l-value assignment operator.
+	pub const fn l_value_assignment() -> Self {
+		Self { base: None, operator_type: OperatorType::SignedLogicalNotShortCircuit, is_assignment: true, is_l_value_assignment: true }
+	}
+
+	/// If the operator is not any kind of assignment, such as `+` or `$<`.
+	pub const fn is_plain(&self) -> bool {
+		!self.is_assignment && !self.is_l_value_assignment
+	}
+
+	/// If the operator has the given symbol and is not an assignment.
+	pub fn is_plain_symbol(&self, symbol: OperatorSymbol) -> bool {
+		self.is_plain() && self.base == Some(symbol)
+	}
+}
+
 #[derive(EnumDiscriminants, Debug)]
 pub enum TokenVariant {
 	NumericalLiteral(u64),
@@ -183,7 +252,7 @@ pub enum TokenVariant {
 	Identifier(Box<str>),
 	Keyword(Keyword),
 	Separator(Separator),
-	Operator(Option<OperatorSymbol>, OperatorType, bool, bool),
+	Operator(OperatorToken),
 }
 
 #[derive(Debug)]
@@ -281,56 +350,257 @@ fn escaped_char_value(sequence: &str) -> Result<(char, usize), Error> {
 	Ok((first_char, first_char.len_utf8()))
 }
 
+/// Parses a numeric literal width suffix such as `u8` or `i32` into its bit width and if it is signed.
+fn numeric_suffix_width(suffix: &str) -> Result<(u32, bool), Error> {
+	let is_signed = match suffix.chars().next() {
+		Some('i') => true,
+		Some('u') => false,
+		_ => return Err(Error::InvalidNumericSuffix(suffix.into())),
+	};
+	match &suffix[1..] {
+		"8" => Ok((8, is_signed)),
+		"16" => Ok((16, is_signed)),
+		"32" => Ok((32, is_signed)),
+		"64" => Ok((64, is_signed)),
+		_ => Err(Error::InvalidNumericSuffix(suffix.into())),
+	}
+}
+
+/// Get the length in bytes of the run of operator chars at the start of `string`.
+///
+/// The run ends before any comment so that `a+//comment` and `a+/*comment*/b` are not read as containing the operators `+//` or `+/*`.
+fn operator_run_length(operator_character_set: &HashSet<char>, string: &str) -> usize {
+	string.char_indices()
+		.find(|(index, chr)| !operator_character_set.contains(chr) || string[*index..].starts_with("//") || string[*index..].starts_with("/*"))
+		.map_or(string.len(), |(index, _)| index)
+}
+
+/// Split an operator string such as `$<=` or `~+=` into its symbol, type and if it is an assignment.
+///
+/// Returns `None` if the string is not a single valid operator.
+fn parse_operator_string(main_data: &MainData, operator_string: &str) -> Option<OperatorToken> {
+	// Get operator type
+	let first_char = operator_string.chars().next()?;
+	let (operator_type, operator_string_without_type) = match main_data.char_to_operator_type_mapping.get(&first_char) {
+		Some(operator_type) => (*operator_type, &operator_string[1..]),
+		None => (OperatorType::UnsignedLogicalShortCircuit, operator_string),
+	};
+	// Get if the operator is an assignment
+	let (is_assignment, operator_base_string) =
+	match operator_string_without_type.ends_with('=') && !matches!(operator_string_without_type, "==" | "!=" | "<=" | ">=") {
+		true => (true, &operator_string_without_type[..operator_string_without_type.len() - 1]),
+		false => (false, operator_string_without_type),
+	};
+	// Get operator base
+	let operator_base = match operator_base_string.is_empty() {
+		true => None,
+		false => Some(*main_data.str_to_operator_mapping.get(operator_base_string)?),
+	};
+	Some(OperatorToken::new(operator_base, operator_type, is_assignment))
+}
+
+/// Returns if the longest valid operator at the start of `operator_string` is a prefix operator such as `-` or `~`.
+fn starts_with_prefix_operator(main_data: &MainData, operator_string: &str) -> bool {
+	let operator = (1..=operator_string.len()).rev().find_map(|length| parse_operator_string(main_data, &operator_string[..length]));
+	match operator {
+		Some(OperatorToken { base: None, operator_type: OperatorType::FloatingPointBitwise, is_assignment: false, .. }) => true,
+		Some(OperatorToken { base: Some(symbol), operator_type, is_assignment: false, .. }) => prefix_operator_from_symbol(symbol, operator_type).is_some(),
+		_ => false,
+	}
+}
+
+/// Get the valid operator that is closest to the invalid operator `operator_string` by edit distance, if one is close enough to be a likely typo.
+fn suggest_operator(main_data: &MainData, operator_string: &str) -> Option<String> {
+	let mut candidates: Vec<String> = main_data.str_to_operator_mapping.keys()
+		.flat_map(|base| ["", "$", "~"].into_iter().flat_map(move |type_symbol| ["", "="].into_iter()
+			.map(move |assignment_symbol| format!("{type_symbol}{base}{assignment_symbol}"))
+		))
+		.filter(|candidate| parse_operator_string(main_data, candidate).is_some())
+		.collect();
+	// Operators with the same chars in a different order come first so that swapped chars such as `=<` suggest `<=`
+	let sorted_chars = |string: &str| {
+		let mut chars: Vec<char> = string.chars().collect();
+		chars.sort_unstable();
+		chars
+	};
+	let operator_chars = sorted_chars(operator_string);
+	candidates.sort_by_cached_key(|candidate| (sorted_chars(candidate) != operator_chars, candidate.clone()));
+	closest_by_edit_distance(operator_string, candidates.iter().map(String::as_str)).map(Into::into)
+}
+
+/// Get the keyword that is closest to the invalid keyword name `keyword_name` by edit distance, with its '@' prefix, if one is close enough to be a likely typo.
+fn suggest_keyword(main_data: &MainData, keyword_name: &str) -> Option<String> {
+	let mut candidates: Vec<&str> = main_data.str_to_keyword_mapping.keys().copied().collect();
+	candidates.sort();
+	closest_by_edit_distance(keyword_name, candidates.into_iter()).map(|keyword_name| format!("@{keyword_name}"))
+}
+
+/// Get the candidate with the smallest edit distance to `string`, if it is at most 2 edits away and shorter than `string` is long.
+/// Ties are broken by the first candidate.
+fn closest_by_edit_distance<'a>(string: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+	let max_distance = 2.min(string.chars().count().saturating_sub(1));
+	candidates
+		.map(|candidate| (edit_distance(string, candidate), candidate))
+		.filter(|(distance, _)| *distance <= max_distance)
+		.min_by_key(|(distance, _)| *distance)
+		.map(|(_, candidate)| candidate)
+}
+
+/// Get the number of single char insertions, deletions, substitutions or swaps of adjacent chars needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().collect();
+	let b: Vec<char> = b.chars().collect();
+	// distances[i][j] is the distance between the first i chars of a and the first j chars of b
+	let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+	for (i, row) in distances.iter_mut().enumerate() {
+		row[0] = i;
+	}
+	for (j, distance) in distances[0].iter_mut().enumerate() {
+		*distance = j;
+	}
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let substitution_cost = (a[i - 1] != b[j - 1]) as usize;
+			distances[i][j] = (distances[i - 1][j] + 1).min(distances[i][j - 1] + 1).min(distances[i - 1][j - 1] + substitution_cost);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				distances[i][j] = distances[i][j].min(distances[i - 2][j - 2] + 1);
+			}
+		}
+	}
+	distances[a.len()][b.len()]
+}
+
+/// Get the length in bytes of the whitespace and block comments at the start of `string`.
+///
+/// Used to allow whitespace and block comments between the '@' and the name of a keyword, such as in `@ entry_point` or `@/* comment */entry_point`.
+fn whitespace_and_block_comments_length(string: &str) -> Result<usize, Error> {
+	let mut length = 0;
+	loop {
+		let remaining = &string[length..];
+		if let Some(comment) = remaining.strip_prefix("/*") {
+			length += comment.find("*/").ok_or(Error::UnterminatedBlockComment)? + 4;
+			continue;
+		}
+		match remaining.find(|chr: char| !chr.is_ascii_whitespace()) {
+			Some(0) => return Ok(length),
+			Some(whitespace_length) => length += whitespace_length,
+			None => return Ok(string.len()),
+		}
+	}
+}
+
 impl Token {
-	/// Takes in a string slice `line_content` and tokenizes the first token in the string.
+	/// Create a token that is `char_length` chars long starting at `line` and `column`, the end is the column of the char after the last char.
+	pub const fn new(variant: TokenVariant, line: NonZeroUsize, column: NonZeroUsize, char_length: usize) -> Self {
+		Self {
+			variant,
+			start: (line, column),
+			end: (line, column.saturating_add(char_length)),
+		}
+	}
+
+	/// Get the position of the first char of the token and the position of the char after its last char.
+	pub const fn span(&self) -> Span {
+		(self.start, self.end)
+	}
+
+	/// Print the token with the meaning of its fields decoded, such as the operator symbol and type that `~+=` was tokenized into.
+	pub fn print(&self) {
+		print!("{{ {}:{} to {}:{} {:?}", self.start.0, self.start.1, self.end.0, self.end.1, TokenVariantDiscriminants::from(&self.variant));
+		match &self.variant {
+			TokenVariant::NumericalLiteral(value) => print!(", value: {value}"),
+			TokenVariant::StringLiteral(string_value) => print!(", string_value: {string_value:?}"),
+			TokenVariant::Identifier(name) => print!(", name: {name}"),
+			TokenVariant::Keyword(keyword) => print!(", keyword: {keyword:?} @{}", keyword.get_symbol()),
+			TokenVariant::Separator(separator) => print!(", separator: {separator:?} {}", separator.get_symbol()),
+			TokenVariant::Operator(OperatorToken { base, operator_type, is_assignment, is_l_value_assignment }) => {
+				match base {
+					Some(operator_symbol) => print!(", symbol: {operator_symbol:?} {}", operator_symbol.get_symbol()),
+					None => print!(", symbol: None"),
+				}
+				print!(", type: {operator_type:?}, is_assignment: {is_assignment}, is_l_value_assignment: {is_l_value_assignment}");
+			}
+		}
+		println!(" }}");
+	}
+
+	/// Takes in a string slice `source` of the rest of a source file and tokenizes the first token in the string, only block comments can span lines.
 	/// Returns the tokenized token and the input string slice with the tokenized chars removed.
-	pub fn tokenize_from_line<'a>(main_data: &mut MainData, line_content: &'a str, line_number: NonZeroUsize, column_number: NonZeroUsize, starts_with_block_comment: bool)
+	fn tokenize_from_source<'a>(main_data: &mut MainData, source: &'a str, line_number: NonZeroUsize, column_number: NonZeroUsize, starts_with_block_comment: bool)
 	-> Result<(Option<Self>, &'a str, bool), Error> {
 		// If we are in a block comment, try find the end
 		if starts_with_block_comment {
-			return Ok(match line_content.find("*/") {
+			return Ok(match source.find("*/") {
 				// Skip comment if we do
-				Some(index) => (None, &line_content[index + 2..], false),
-				// Skip entire line if we don't and continue to look for end
+				Some(index) => (None, &source[index + 2..], false),
+				// Skip the rest of the source if we don't, the comment is unterminated
 				None => (None, "", true),
 			});
 		}
+		// Char and string literals are searched for their closing quote in the rest of the line, this is only found for tokens that need it so that long lines tokenize quickly
+		let line_content = || &source[..source.find(['\r', '\n']).unwrap_or(source.len())];
 		// Get the token varient descriminant and length in bytes
-		let (token_varient_descriminant, length_in_bytes) = match line_content.chars().next()
+		let (token_varient_descriminant, length_in_bytes) = match source.chars().next()
 			.expect("Function input should not be empty") {
-			_ if line_content.starts_with("//") => return Ok((None, "", false)),
-			_ if line_content.starts_with("/*") => return Ok((None, &line_content[2..], true)),
+			_ if source.starts_with("//") => return Ok((None, &source[line_content().len()..], false)),
+			_ if source.starts_with("/*") => return Ok((None, &source[2..], true)),
 			first_char if first_char.is_ascii_alphabetic() || first_char == '_' => (
 				TokenVariantDiscriminants::Identifier,
-				line_content.find(|chr: char| !(chr.is_ascii_alphanumeric() || chr == '_')).unwrap_or_else(|| line_content.len()),
+				source.find(|chr: char| !(chr.is_ascii_alphanumeric() || chr == '_')).unwrap_or_else(|| source.len()),
 			),
 			first_char if first_char.is_ascii_digit() => (
 				TokenVariantDiscriminants::NumericalLiteral,
-				line_content.find(|chr: char| !(chr.is_ascii_alphanumeric() || chr == '_' || chr == '.')).unwrap_or_else(|| line_content.len()),
+				{
+					// Literals with an arbitrary base such as `0r36:z` have a colon between the base and the digits
+					let is_arbitrary_base = source.starts_with("0r");
+					// A literal ends before a `..` so that ranges such as `0..5` are not a single literal
+					source.char_indices()
+						.find(|(index, chr)| {
+							!(chr.is_ascii_alphanumeric() || *chr == '_' || *chr == '.' || (is_arbitrary_base && *chr == ':')) || source[*index..].starts_with("..")
+						})
+						.map_or_else(|| source.len(), |(index, _)| index)
+				},
 			),
 			first_char if main_data.char_to_separator_mapping.contains_key(&first_char) => (TokenVariantDiscriminants::Separator, 1),
 			first_char if main_data.operator_character_set.contains(&first_char) => (
 				TokenVariantDiscriminants::Operator,
-				line_content.find(|chr: char| !main_data.operator_character_set.contains(&chr)).unwrap_or_else(|| line_content.len()),
+				{
+					// A run of operator chars such as `=-` in `a=-1` may be several operators, so we take the longest valid operator at the start of the run
+					let run_length = operator_run_length(&main_data.operator_character_set, source);
+					match (1..=run_length).rev().find(|length| parse_operator_string(main_data, &source[..*length]).is_some()) {
+						// The rest of the run must start with a prefix operator such as the `-` in `a=-1`, otherwise the run is a typo such as `=<`
+						Some(length) if length < run_length && !starts_with_prefix_operator(main_data, &source[length..run_length]) =>
+							return Err(Error::InvalidOperator(source[..run_length].into(), suggest_operator(main_data, &source[..run_length]))),
+						Some(length) => length,
+						None => return Err(Error::InvalidOperator(source[..run_length].into(), suggest_operator(main_data, &source[..run_length]))),
+					}
+				},
 			),
-			_ if line_content.starts_with("@=") => match line_content.chars().nth(2) {
+			_ if source.starts_with("@=") => match source.chars().nth(2) {
 				Some(chr) if !main_data.operator_character_set.contains(&chr) => (TokenVariantDiscriminants::Operator, 2),
-				_ => return Err(Error::InvalidOperator(line_content.split_at(
-					line_content.find(|chr| !main_data.operator_character_set.contains(&chr)).unwrap_or_else(|| line_content.len())
-				).0.into())),
+				_ => {
+					let operator_string = source.split_at(
+						source.find(|chr| !main_data.operator_character_set.contains(&chr)).unwrap_or_else(|| source.len())
+					).0;
+					return Err(Error::InvalidOperator(operator_string.into(), suggest_operator(main_data, operator_string)));
+				}
 			}
 			'@' => (
 				TokenVariantDiscriminants::Keyword,
-				&line_content[1..].find(|chr: char| !(chr.is_ascii_alphanumeric() || chr == '_')).unwrap_or_else(|| line_content.len()) + 1,
+				{
+					let name_start = 1 + whitespace_and_block_comments_length(&source[1..])?;
+					let name = &source[name_start..];
+					name_start + name.find(|chr: char| !(chr.is_ascii_alphanumeric() || chr == '_')).unwrap_or_else(|| name.len())
+				},
 			),
 			'\'' => (
 				TokenVariantDiscriminants::NumericalLiteral,
 				{
-					if line_content.starts_with("'''") || line_content.starts_with("'\\'") {
+					if source.starts_with("'''") || source.starts_with("'\\'") {
 						3
 					}
 					else {
-						match (&line_content[1..]).find('\'') {
+						match line_content()[1..].find('\'') {
 							Some(length_in_bytes) => length_in_bytes + 2,
 							None => return Err(Error::UnterminatedCharLiteral),
 						}
@@ -341,7 +611,7 @@ impl Token {
 				TokenVariantDiscriminants::StringLiteral,
 				'length_found: {
 					let mut is_escaped = false;
-					for (index, chr) in line_content.chars().skip(1).enumerate() {
+					for (index, chr) in line_content().chars().skip(1).enumerate() {
 						if chr == '"' && !is_escaped {
 							break 'length_found index + 2;
 						}
@@ -353,7 +623,7 @@ impl Token {
 			invalid_char => return Err(Error::InvalidTokenStartChar(invalid_char)),
 		};
 		// Split the input string into the token and the remaining string
-		let (token_string, string_without_token) = line_content.split_at(length_in_bytes);
+		let (token_string, string_without_token) = source.split_at(length_in_bytes);
 		// Parse the input string to a token varient
 		let first_char = token_string.chars().next().expect("Length should be at least 1");
 		let token_varient = match token_varient_descriminant {
@@ -376,34 +646,56 @@ impl Token {
 						}
 					};
 					if value > main_data.int_max_value {
-						return Err(Error::NumericalLiteralTooLarge);
+						return Err(Error::NumericalLiteralTooLarge(main_data.int_bit_width));
 					}
 					value
 				}
 				// If we have a numerical literal
 				else {
-					let (has_prefix, base, is_float) = if first_char == '0' {
+					let (prefix_length, base, is_float) = if first_char == '0' {
 						match token_string.chars().nth(1) {
-							None => (false, 10, false),
-							Some(second_char) if second_char.is_ascii_digit() => (false, 10, false),
-							Some('x') => (true, 16, false),
-							Some('o') => (true, 8, false),
-							Some('b') => (true, 2, false),
-							Some('f') => (true, 10, true),
+							None => (0, 10, false),
+							Some(second_char) if second_char.is_ascii_digit() => (0, 10, false),
+							Some('i' | 'u') => (0, 10, false),
+							Some('x') => (2, 16, false),
+							Some('o') => (2, 8, false),
+							Some('b') => (2, 2, false),
+							Some('f') => (2, 10, true),
+							// An arbitrary base written in decimal between `0r` and `:`
+							Some('r') => {
+								let base_length = match token_string[2..].find(':') {
+									Some(base_length) => base_length,
+									None => return Err(Error::InvalidNumericalLiteralBase('r')),
+								};
+								let base_string = &token_string[2..2 + base_length];
+								match base_string.parse::<u32>() {
+									Ok(base) if (2..=36).contains(&base) => (base_length + 3, base, false),
+									_ => return Err(Error::NumericalLiteralBaseOutOfRange(base_string.into())),
+								}
+							}
 							Some(invalid_char) => return Err(Error::InvalidNumericalLiteralBase(invalid_char)),
 						}
 					}
 					else {
-						(false, 10, false)
+						(0, 10, false)
 					};
 					// Remove the prefix if it has one
-					let string_without_prefix = match has_prefix {
-						true => &token_string[2..],
-						false => token_string,
+					let string_without_prefix = &token_string[prefix_length..];
+					// Split off the width suffix if there is one, bases above 18 use `i` as a digit so they can't have a suffix
+					let (string_without_prefix, suffix) = match string_without_prefix.find(['i', 'u']) {
+						Some(index) if !is_float && base <= 18 => (&string_without_prefix[..index], Some(numeric_suffix_width(&string_without_prefix[index..])?)),
+						_ => (string_without_prefix, None),
 					};
 					// Parse number
 					if is_float {
-						return Err(Error::FeatureNotYetImplemented("Float literals".into()));
+						let float_value: f64 = match string_without_prefix.replace('_', "").parse() {
+							Ok(float_value) => float_value,
+							Err(_) => return Err(Error::InvalidFloatLiteral(token_string.into())),
+						};
+						match main_data.float_to_value(float_value) {
+							Some(value) => value,
+							None => return Err(Error::FeatureNotYetImplemented("Float literals for this int width".into())),
+						}
 					}
 					else {
 						// Parse number char by char
@@ -416,52 +708,48 @@ impl Token {
 							// Parse digit
 							match chr.to_digit(base) {
 								Some(digit) => out = match out.checked_mul(base as u64).map(|value| value.checked_add(digit as u64)).flatten() {
-									Some(value) if value > main_data.int_max_value => return Err(Error::NumericalLiteralTooLarge),
+									Some(value) if value > main_data.int_max_value => return Err(Error::NumericalLiteralTooLarge(main_data.int_bit_width)),
 									Some(value) => value,
-									None => return Err(Error::NumericalLiteralTooLarge),
+									None => return Err(Error::NumericalLiteralTooLarge(main_data.int_bit_width)),
 								},
 								None => return Err(Error::InvalidDigitForBase(chr, base as u8)),
 							}
 						}
+						// The value must fit in the width given by the suffix, signed values are sign extended to the int width
+						if let Some((bit_width, is_signed)) = suffix {
+							if bit_width < 64 && out >> bit_width != 0 {
+								return Err(Error::NumericalLiteralTooLarge(bit_width as u8));
+							}
+							if is_signed && bit_width < 64 && (out >> (bit_width - 1)) & 1 == 1 {
+								out = (out | (u64::MAX << bit_width)) & main_data.int_max_value;
+							}
+						}
 						out
 					}
 				}
 			}),
-			TokenVariantDiscriminants::Keyword => TokenVariant::Keyword(match main_data.str_to_keyword_mapping.get(&token_string[1..]) {
-				Some(keyword) => *keyword,
-				None => return Err(Error::InvalidKeyword(token_string.to_string()))
-			}),
+			TokenVariantDiscriminants::Keyword => {
+				let keyword_name = &token_string[1 + whitespace_and_block_comments_length(&token_string[1..])?..];
+				TokenVariant::Keyword(match main_data.str_to_keyword_mapping.get(keyword_name) {
+					Some(keyword) => *keyword,
+					None => return Err(Error::InvalidKeyword(format!("@{keyword_name}"), suggest_keyword(main_data, keyword_name))),
+				})
+			}
 			TokenVariantDiscriminants::Operator => {
 				// Parse the l-value assignment operator
 				if token_string == "@=" {
-					return Ok((Some(Self {
-						variant: TokenVariant::Operator(None, OperatorType::SignedLogicalNotShortCircuit, true, true),
-						start: (line_number, column_number),
-						end: (line_number, column_number.saturating_add(2)),
-					}), string_without_token, false));
+					return Ok((Some(Self::new(TokenVariant::Operator(OperatorToken::l_value_assignment()), line_number, column_number, 2)), string_without_token, false));
 				}
-				// Get operator type
-				let operator_type = main_data.char_to_operator_type_mapping.get(&first_char);
-				let (operator_type, operator_string_without_type) = match operator_type {
-					Some(operator_type) => (*operator_type, &token_string[1..]),
-					None => (OperatorType::UnsignedLogicalShortCircuit, token_string),
-				};
-				// Get if the operator is an assignment
-				let (is_assignment, operator_base_string) =
-				match operator_string_without_type.chars().last() == Some('=') && !matches!(operator_string_without_type, "==" | "!=" | "<=" | ">=") {
-					true => (true, &operator_string_without_type[..operator_string_without_type.len() - 1]),
-					false => (false, operator_string_without_type),
+				// Get operator base, type and if it is an assignment
+				let operator = match parse_operator_string(main_data, token_string) {
+					Some(operator) => operator,
+					None => return Err(Error::InvalidOperator(token_string.into(), suggest_operator(main_data, token_string))),
 				};
-				// Get operator base
-				let operator_base = match operator_base_string.is_empty() {
-					true => None,
-					false => Some(match main_data.str_to_operator_mapping.get(operator_base_string) {
-						Some(operator_base) => *operator_base,
-						None => return Err(Error::InvalidOperator(token_string.into())),
-					}),
-				};
-				// Create operator token varient
-				TokenVariant::Operator(operator_base, operator_type, is_assignment, false)
+				// Make sure the operator is in the language version being compiled
+				if operator.base == Some(OperatorSymbol::Pipeline) && main_data.language_version < LanguageVersion::V2 {
+					return Err(Error::FeatureNotInLanguageVersion("Pipeline operators", LanguageVersion::V2));
+				}
+				TokenVariant::Operator(operator)
 			}
 			TokenVariantDiscriminants::StringLiteral => {
 				let mut string_quote_content = &token_string[1..token_string.len() - 1];
@@ -475,11 +763,294 @@ impl Token {
 			}
 		};
 		// Return
-		let token = Self {
-			variant: token_varient,
-			start: (line_number, column_number),
-			end: (line_number, column_number.saturating_add(token_string.chars().count())),
-		};
+		let token = Self::new(token_varient, line_number, column_number, token_string.chars().count());
 		Ok((Some(token), string_without_token, false))
 	}
-}
\ No newline at end of file
+}
+
+/// Splits the whole content of a source file into tokens, keeping track of the line and column that each token starts and ends at.
+pub struct Tokenizer<'a> {
+	/// The source that has not been tokenized yet.
+	remaining_source: &'a str,
+	/// The line and column of the first char of the remaining source.
+	position: Position,
+	/// Was the last char that was skipped over a '\r', so that a '\n' after it is part of the same line ending.
+	after_carriage_return: bool,
+	/// Where the block comment that the remaining source starts inside of starts, `None` if it does not start inside a block comment.
+	block_comment_start: Option<Position>,
+}
+
+impl<'a> Tokenizer<'a> {
+	pub const fn new(source: &'a str) -> Self {
+		Self {
+			remaining_source: source,
+			position: (NonZeroUsize::MIN, NonZeroUsize::MIN),
+			after_carriage_return: false,
+			block_comment_start: None,
+		}
+	}
+
+	/// Skip over the first `length_in_bytes` bytes of the remaining source, a "\r\n", '\r' or '\n' moves to the start of the next line.
+	fn advance(&mut self, length_in_bytes: usize) {
+		let (line, column) = &mut self.position;
+		for chr in self.remaining_source[..length_in_bytes].chars() {
+			match (chr, self.after_carriage_return) {
+				('\n', true) => {}
+				('\r' | '\n', _) => {
+					*line = line.saturating_add(1);
+					*column = NonZeroUsize::MIN;
+				}
+				_ => *column = column.saturating_add(1),
+			}
+			self.after_carriage_return = chr == '\r';
+		}
+		self.remaining_source = &self.remaining_source[length_in_bytes..];
+	}
+
+	/// Tokenize the whole source, returns the error and the position of the token that caused it if the source could not be tokenized.
+	pub fn tokenize(mut self, main_data: &mut MainData) -> Result<Vec<Token>, (Error, Position)> {
+		let mut tokens = Vec::new();
+		// Skip whitespace including line endings, chars and bytes are the same size since we are only looking for ASCII whitespace chars
+		while let Some(start_whitespace_length) = self.remaining_source.find(|chr: char| !chr.is_ascii_whitespace()) {
+			self.advance(start_whitespace_length);
+			// Tokenize a token from the source
+			let token_start = self.position;
+			let (line, column) = token_start;
+			let (token, new_remaining_source, in_block_comment) =
+				Token::tokenize_from_source(main_data, self.remaining_source, line, column, self.block_comment_start.is_some())
+				.map_err(|error| (error, token_start))?;
+			self.block_comment_start = match in_block_comment {
+				true => self.block_comment_start.or(Some(token_start)),
+				false => None,
+			};
+			// Skip over the chars that where consumed by the tokenization, the end of the token is where the tokenizer is after skipping it
+			self.advance(self.remaining_source.len() - new_remaining_source.len());
+			if let Some(mut token) = token {
+				token.end = self.position;
+				tokens.push(token);
+			}
+		}
+		if let Some(block_comment_start) = self.block_comment_start {
+			return Err((Error::UnterminatedBlockComment, block_comment_start));
+		}
+		Ok(tokens)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::num::NonZeroUsize;
+
+	use crate::{error::Error, with_main_data};
+
+	use super::{parse_operator_string, Keyword, OperatorSymbol, OperatorToken, OperatorType, Token, TokenVariant, Tokenizer};
+
+	fn tokenize(source: &str) -> Result<Vec<Token>, Error> {
+		with_main_data(&["--check"], |main_data| Tokenizer::new(source).tokenize(main_data)).map_err(|(error, _)| error)
+	}
+
+	/// Tokenizes `source` that should be a single keyword and returns the keyword.
+	fn tokenize_keyword(source: &str) -> Keyword {
+		let tokens = tokenize(source).unwrap_or_else(|error| panic!("{error}"));
+		match &tokens[..] {
+			[Token { variant: TokenVariant::Keyword(keyword), .. }] => *keyword,
+			_ => panic!("{tokens:?} is not a single keyword"),
+		}
+	}
+
+	/// Tokenizes `source` that should only have identifiers and non-assignment operators, returns the name or symbol of each token.
+	fn tokenize_identifiers_and_operators(source: &str) -> Vec<String> {
+		let tokens = tokenize(source).unwrap_or_else(|error| panic!("{error}"));
+		tokens.iter().map(|token| match &token.variant {
+			TokenVariant::Identifier(name) => name.to_string(),
+			TokenVariant::Operator(operator) if operator.is_plain() => operator.base.unwrap().get_symbol().to_string(),
+			_ => panic!("{token:?} is not an identifier or operator"),
+		}).collect()
+	}
+
+	/// Tokenizes `source` that should be a single numeric literal and returns its value.
+	fn tokenize_number(source: &str) -> u64 {
+		let tokens = tokenize(source).unwrap_or_else(|error| panic!("{error}"));
+		match &tokens[..] {
+			[Token { variant: TokenVariant::NumericalLiteral(value), .. }] => *value,
+			_ => panic!("{tokens:?} is not a single numeric literal"),
+		}
+	}
+
+	#[test]
+	fn keyword() {
+		assert_eq!(tokenize_keyword("@entry_point"), Keyword::EntryPoint);
+	}
+
+	#[test]
+	fn unknown_keyword() {
+		assert!(matches!(tokenize("@entry_pint"), Err(Error::InvalidKeyword(keyword, _)) if keyword == "@entry_pint"));
+	}
+
+	#[test]
+	fn unknown_keyword_suggestions() {
+		for (misspelled, suggestion) in [("@entrypoint", "@entry_point"), ("@brake", "@break"), ("@imprt", "@import")] {
+			assert!(matches!(tokenize(misspelled), Err(Error::InvalidKeyword(keyword, Some(suggested))) if keyword == misspelled && suggested == suggestion));
+		}
+		assert_eq!(
+			Error::InvalidKeyword("@entrypoint".into(), Some("@entry_point".into())).to_string(), "Invalid keyword \"@entrypoint\", did you mean \"@entry_point\"?"
+		);
+		// Nothing is suggested for a name that is not close to any keyword
+		assert!(matches!(tokenize("@xyzzy"), Err(Error::InvalidKeyword(_, None))));
+	}
+
+	#[test]
+	fn whitespace_and_comments_after_at() {
+		assert_eq!(tokenize_keyword("@ entry_point"), Keyword::EntryPoint);
+		assert_eq!(tokenize_keyword("@/* comment */entry_point"), Keyword::EntryPoint);
+	}
+
+	#[test]
+	fn numeric_suffixes() {
+		assert_eq!(tokenize_number("10u32"), 10);
+		assert_eq!(tokenize_number("255u8"), 255);
+		assert_eq!(tokenize_number("5i8"), 5);
+		// Negative signed values are sign extended
+		assert_eq!(tokenize_number("0xFFi8"), u64::MAX);
+		assert_eq!(tokenize_number("0x80i16"), 0x80);
+	}
+
+	#[test]
+	fn numeric_suffix_too_small() {
+		assert!(matches!(tokenize("256u8"), Err(Error::NumericalLiteralTooLarge(_))));
+	}
+
+	#[test]
+	fn invalid_numeric_suffix() {
+		assert!(matches!(tokenize("5u7"), Err(Error::InvalidNumericSuffix(suffix)) if suffix == "u7"));
+	}
+
+	#[test]
+	fn comments_end_operators() {
+		assert_eq!(tokenize_identifiers_and_operators("a//c"), ["a"]);
+		assert_eq!(tokenize_identifiers_and_operators("a/ /b"), ["a", "/", "/", "b"]);
+		assert_eq!(tokenize_identifiers_and_operators("a/*x*/b"), ["a", "b"]);
+		assert_eq!(tokenize_identifiers_and_operators("a+//c"), ["a", "+"]);
+	}
+
+	#[test]
+	fn numeric_literal_too_large_message() {
+		let error = tokenize("18446744073709551616").unwrap_err();
+		assert_eq!(error.to_string(), "Numerical literal too large, exceeds the 64-bit maximum");
+		let error = tokenize("256u8").unwrap_err();
+		assert_eq!(error.to_string(), "Numerical literal too large, exceeds the 8-bit maximum");
+	}
+
+	#[test]
+	fn arbitrary_base_literals() {
+		assert_eq!(tokenize_number("0r36:z"), 35);
+		assert_eq!(tokenize_number("0r36:10"), 36);
+		assert_eq!(tokenize_number("0r3:12"), 5);
+	}
+
+	#[test]
+	fn arbitrary_base_out_of_range() {
+		assert!(matches!(tokenize("0r37:1"), Err(Error::NumericalLiteralBaseOutOfRange(base)) if base == "37"));
+		assert!(matches!(tokenize("0r1:0"), Err(Error::NumericalLiteralBaseOutOfRange(base)) if base == "1"));
+	}
+
+	#[test]
+	fn compound_operators() {
+		for (symbol, expected_base, expected_is_assignment) in [
+			(">>=", OperatorSymbol::BitwiseRightShift, true),
+			("<<=", OperatorSymbol::BitwiseLeftShift, true),
+			("&&", OperatorSymbol::LogicalAnd, false),
+			("||", OperatorSymbol::LogicalOr, false),
+			("??", OperatorSymbol::ZeroCoalesce, false),
+			("==", OperatorSymbol::EqualTo, false),
+			("!=", OperatorSymbol::NotEqualTo, false),
+			("<=", OperatorSymbol::LessThanOrEqualTo, false),
+			(">=", OperatorSymbol::GreaterThanOrEqualTo, false),
+		] {
+			let tokens = tokenize(&format!("a{symbol}b")).unwrap_or_else(|error| panic!("{error}"));
+			match &tokens[..] {
+				[_, Token { variant: TokenVariant::Operator(operator), .. }, _] => {
+					assert_eq!(operator.base, Some(expected_base), "{symbol}");
+					assert_eq!(operator.is_assignment, expected_is_assignment, "{symbol}");
+				}
+				_ => panic!("{symbol} was not tokenized as a single operator: {tokens:?}"),
+			}
+		}
+	}
+
+	#[test]
+	fn operator_token_fields() {
+		with_main_data(&["--check"], |main_data| {
+			let plus = parse_operator_string(main_data, "+").unwrap();
+			assert_eq!(plus.base, Some(OperatorSymbol::AddRead));
+			assert!(matches!(plus.operator_type, OperatorType::UnsignedLogicalShortCircuit));
+			assert!(plus.is_plain() && plus.is_plain_symbol(OperatorSymbol::AddRead) && !plus.is_plain_symbol(OperatorSymbol::SubtractNegate));
+			let signed_less_than_or_equal_to = parse_operator_string(main_data, "$<=").unwrap();
+			assert_eq!(signed_less_than_or_equal_to.base, Some(OperatorSymbol::LessThanOrEqualTo));
+			assert!(matches!(signed_less_than_or_equal_to.operator_type, OperatorType::SignedLogicalNotShortCircuit));
+			assert!(signed_less_than_or_equal_to.is_plain());
+			let float_add_assignment = parse_operator_string(main_data, "~+=").unwrap();
+			assert_eq!(float_add_assignment.base, Some(OperatorSymbol::AddRead));
+			assert!(matches!(float_add_assignment.operator_type, OperatorType::FloatingPointBitwise));
+			assert!(float_add_assignment.is_assignment && !float_add_assignment.is_plain_symbol(OperatorSymbol::AddRead));
+			let assignment = parse_operator_string(main_data, "=").unwrap();
+			assert_eq!(assignment.base, None);
+			assert!(assignment.is_assignment && !assignment.is_l_value_assignment);
+			assert!(parse_operator_string(main_data, "+-").is_none());
+		});
+		let l_value_assignment = OperatorToken::l_value_assignment();
+		assert!(l_value_assignment.is_l_value_assignment && !l_value_assignment.is_plain());
+		let constructed = OperatorToken::new(Some(OperatorSymbol::Xor), OperatorType::FloatingPointBitwise, false);
+		assert!(constructed.is_plain_symbol(OperatorSymbol::Xor) && !constructed.is_l_value_assignment);
+	}
+
+	#[test]
+	fn token_spans() {
+		let position = |line: usize, column: usize| (NonZeroUsize::new(line).unwrap(), NonZeroUsize::new(column).unwrap());
+		// Single char tokens end at the column after them
+		let token = Token::new(TokenVariant::Identifier("x".into()), NonZeroUsize::new(2).unwrap(), NonZeroUsize::new(5).unwrap(), 1);
+		assert_eq!(token.span(), (position(2, 5), position(2, 6)));
+		let token = Token::new(TokenVariant::Identifier("count".into()), NonZeroUsize::new(1).unwrap(), NonZeroUsize::new(1).unwrap(), 5);
+		assert_eq!(token.span(), (position(1, 1), position(1, 6)));
+		// Tokenized tokens have the same spans
+		let tokens = tokenize("x = count;\n  @entry_point").unwrap_or_else(|error| panic!("{error}"));
+		let spans: Vec<_> = tokens.iter().map(Token::span).collect();
+		assert_eq!(spans, [
+			(position(1, 1), position(1, 2)),
+			(position(1, 3), position(1, 4)),
+			(position(1, 5), position(1, 10)),
+			(position(1, 10), position(1, 11)),
+			(position(2, 3), position(2, 15)),
+		]);
+	}
+
+	#[test]
+	fn invalid_operator_suggestions() {
+		assert!(matches!(tokenize("a =< b"), Err(Error::InvalidOperator(operator, Some(suggestion))) if operator == "=<" && suggestion == "<="));
+		assert!(matches!(tokenize("a=>b"), Err(Error::InvalidOperator(operator, Some(suggestion))) if operator == "=>" && suggestion == ">="));
+		assert_eq!(Error::InvalidOperator("=<".into(), Some("<=".into())).to_string(), "Invalid operator \"=<\", did you mean \"<=\"?");
+		// An operator followed by a prefix operator is not a typo
+		assert_eq!(tokenize_identifiers_and_operators("a&&-b"), ["a", "&&", "-", "b"]);
+		assert!(tokenize("a=-b").is_ok());
+	}
+
+	#[test]
+	fn tokens_at_and_across_line_boundaries() {
+		// Tokens end at a line break, so operators on different lines are not joined
+		assert_eq!(tokenize_identifiers_and_operators("count\ntotal"), ["count", "total"]);
+		assert_eq!(tokenize_identifiers_and_operators("a +\n+ b"), ["a", "+", "+", "b"]);
+		assert_eq!(tokenize_identifiers_and_operators("a <\r\n< b"), ["a", "<", "<", "b"]);
+		assert_eq!(tokenize_identifiers_and_operators("a /* x\n */+ b"), ["a", "+", "b"]);
+		// A token at the end of the source with no line break after it
+		assert_eq!(tokenize_identifiers_and_operators("a <<"), ["a", "<<"]);
+		let position = |line: usize, column: usize| (NonZeroUsize::new(line).unwrap(), NonZeroUsize::new(column).unwrap());
+		let tokens = tokenize("first\r\nsecond /* a\nb */ +\nthird").unwrap_or_else(|error| panic!("{error}"));
+		let spans: Vec<_> = tokens.iter().map(Token::span).collect();
+		assert_eq!(spans, [
+			(position(1, 1), position(1, 6)),
+			(position(2, 1), position(2, 7)),
+			(position(3, 6), position(3, 7)),
+			(position(4, 1), position(4, 6)),
+		]);
+	}
+}
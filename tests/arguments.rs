@@ -0,0 +1,105 @@
+//! Tests for how compiler arguments are read.
+mod common;
+
+use std::{env::consts::EXE_SUFFIX, fs::create_dir, process::Command};
+
+use common::{assert_failure, assert_success, compiler_command, stdout, TestDirectory};
+
+#[test]
+fn response_file_with_two_source_files() {
+	let directory = TestDirectory::new("response_file_with_two_source_files");
+	let main_filepath = directory.write("main.bcz", "main = @entry_point () {\n\t0\n};\n");
+	let other_filepath = directory.write("other.bcz", "f = (a) { a + 1 };\n");
+	let response_filepath = directory.write("args.txt", &format!("-c\n{}\n{}\n", main_filepath.display(), other_filepath.display()));
+	let output = directory.compile(&[&format!("@{}", response_filepath.display())]);
+	assert_success(&output);
+	let compiled_filepaths: Vec<String> = stdout(&output).lines().map(str::to_string).collect();
+	assert_eq!(compiled_filepaths, [main_filepath.display().to_string(), other_filepath.display().to_string()]);
+}
+
+#[test]
+fn missing_response_file() {
+	let directory = TestDirectory::new("missing_response_file");
+	let output = directory.compile(&[&format!("@{}", directory.path().join("missing.txt").display())]);
+	assert_failure(&output, "Could not open file");
+}
+
+#[test]
+fn manifest_with_two_source_files() {
+	let directory = TestDirectory::new("manifest_with_two_source_files");
+	let main_filepath = directory.write("main.bcz", "main = @entry_point () {\n\t0\n};\n");
+	let other_filepath = directory.write("other.bcz", "f = (a) { a + 1 };\n");
+	let manifest_filepath = directory.write(
+		"project.bcz.toml", &format!("# Test project\nfiles = [\"{}\", \"{}\"]\nno-link = true\n", main_filepath.display(), other_filepath.display())
+	);
+	let output = directory.compile(&[manifest_filepath.to_str().unwrap()]);
+	assert_success(&output);
+	let compiled_filepaths: Vec<String> = stdout(&output).lines().map(str::to_string).collect();
+	assert_eq!(compiled_filepaths, [main_filepath.display().to_string(), other_filepath.display().to_string()]);
+}
+
+#[test]
+fn manifest_files_are_relative_to_the_manifest() {
+	let directory = TestDirectory::new("manifest_files_are_relative_to_the_manifest");
+	directory.write("main.bcz", "main = @entry_point () {\n\t0\n};\n");
+	create_dir(directory.path().join("src")).unwrap();
+	directory.write("src/other.bcz", "f = (a) { a + 1 };\n");
+	let manifest_filepath = directory.write("project.bcz.toml", "files = [\"main.bcz\", \"src/other.bcz\"]\nno-link = true\n");
+	// The compiler runs from the root of the repository so the files are not found relative to the working directory
+	let output = directory.compile(&[manifest_filepath.to_str().unwrap()]);
+	assert_success(&output);
+	let compiled_filepaths: Vec<String> = stdout(&output).lines().map(str::to_string).collect();
+	assert_eq!(compiled_filepaths, [directory.path().join("main.bcz").display().to_string(), directory.path().join("src/other.bcz").display().to_string()]);
+}
+
+#[test]
+fn invalid_manifest() {
+	let directory = TestDirectory::new("invalid_manifest");
+	let manifest_filepath = directory.write("project.bcz.toml", "files = [\"main.bcz\"\n");
+	let output = directory.compile(&[manifest_filepath.to_str().unwrap()]);
+	assert_failure(&output, "Invalid manifest");
+}
+
+#[test]
+fn manifest_with_unknown_key() {
+	let directory = TestDirectory::new("manifest_with_unknown_key");
+	let manifest_filepath = directory.write("project.bcz.toml", "fils = [\"main.bcz\"]\n");
+	let output = directory.compile(&[manifest_filepath.to_str().unwrap()]);
+	assert_failure(&output, "Unknown manifest key \"fils\"");
+}
+
+#[test]
+fn option_value_ending_in_toml_is_not_a_manifest() {
+	let directory = TestDirectory::new("option_value_ending_in_toml_is_not_a_manifest");
+	assert_success(&directory.compile_source("main = @entry_point () {\n\t0\n};\n", &["-o", "program.toml"]));
+	assert!(directory.path().join("program.toml").exists());
+}
+
+#[test]
+fn define_from_env_defines_constants() {
+	let directory = TestDirectory::new("define_from_env_defines_constants");
+	let main_filepath = directory.write("main.bcz", "main = @entry_point () {\n\tBCZ_TEST_ANSWER + BCZ_TEST_OFFSET\n};\n");
+	let executable_filename = format!("program{EXE_SUFFIX}");
+	let output = compiler_command()
+		.env("BCZ_TEST_ANSWER", "40")
+		.env("BCZ_TEST_OFFSET", "0x2")
+		.arg("-b").arg(directory.path())
+		.args(["--define-from-env", "BCZ_TEST_", "-o", &executable_filename])
+		.arg(&main_filepath)
+		.output().unwrap();
+	assert_success(&output);
+	assert_eq!(Command::new(directory.path().join(executable_filename)).status().unwrap().code(), Some(42));
+}
+
+#[test]
+fn define_from_env_with_non_integer_value() {
+	let directory = TestDirectory::new("define_from_env_with_non_integer_value");
+	let main_filepath = directory.write("main.bcz", "main = @entry_point () {\n\t0\n};\n");
+	let output = compiler_command()
+		.env("BCZ_TEST_NAME", "forty")
+		.arg("-b").arg(directory.path())
+		.args(["--define-from-env", "BCZ_TEST_", "-c"])
+		.arg(&main_filepath)
+		.output().unwrap();
+	assert_failure(&output, "Value \"forty\" of defined constant BCZ_TEST_NAME is not an integer");
+}
@@ -0,0 +1,252 @@
+//! Tests that check the LLVM IR built for programs.
+mod common;
+
+use common::{assert_success, TestDirectory};
+
+/// Get the names of the functions defined in `llvm_ir`.
+fn defined_functions(llvm_ir: &str) -> Vec<&str> {
+	llvm_ir.lines()
+		.filter(|line| line.starts_with("define "))
+		.filter_map(|line| line.split('@').nth(1)?.split('(').next())
+		.collect()
+}
+
+/// Get the IR of the body of the function `name` defined in `llvm_ir`.
+fn function_body<'a>(llvm_ir: &'a str, name: &str) -> &'a str {
+	let define_line = llvm_ir.lines().find(|line| line.starts_with("define ") && line.contains(&format!(" @{name}("))).unwrap();
+	let body = &llvm_ir[llvm_ir.find(define_line).unwrap()..];
+	&body[..body.find("\n}").unwrap()]
+}
+
+#[test]
+fn unnamed_functions_get_different_names() {
+	let directory = TestDirectory::new("unnamed_functions_get_different_names");
+	let llvm_ir = directory.llvm_ir("main = @entry_point () {\n\tf = (a) { a + 1 };\n\tg = (a) { a + 2 };\n\tf(1) + g(2)\n};\n", &[]);
+	let mut unnamed_functions: Vec<&str> = defined_functions(&llvm_ir).into_iter().filter(|name| name.starts_with("__bcz__unnamedFunction")).collect();
+	unnamed_functions.sort();
+	assert_eq!(unnamed_functions, ["__bcz__unnamedFunction__0", "__bcz__unnamedFunction__1"]);
+}
+
+#[test]
+fn tilde_before_operator_selects_float_operator() {
+	let directory = TestDirectory::new("tilde_before_operator_selects_float_operator");
+	let llvm_ir = directory.llvm_ir("add = (a, b) { a ~+ b };\n", &[]);
+	assert!(llvm_ir.contains("fadd"), "{llvm_ir}");
+	assert!(!llvm_ir.contains("xor"), "{llvm_ir}");
+}
+
+#[test]
+fn imported_constant_is_not_loaded() {
+	let directory = TestDirectory::new("imported_constant_is_not_loaded");
+	directory.write("constants.bcz", "@export A = 60;\n");
+	let llvm_ir = directory.llvm_ir("A = @import(\"constants.bcz\", \"A\");\nmain = @entry_point () {\n\tA + 1\n};\n", &[]);
+	assert!(!llvm_ir.contains(" = load "), "{llvm_ir}");
+	assert!(llvm_ir.contains("61"), "{llvm_ir}");
+}
+
+#[test]
+fn section_is_set_on_globals_and_functions() {
+	let directory = TestDirectory::new("section_is_set_on_globals_and_functions");
+	let llvm_ir = directory.llvm_ir("g = @section(\".fastdata\") 5;\nf = @section(\".fastcode\") (a) { a + g };\n", &[]);
+	let global = llvm_ir.lines().find(|line| line.starts_with("@g = ")).unwrap();
+	assert!(global.contains("section \".fastdata\""), "{llvm_ir}");
+	let function = llvm_ir.lines().find(|line| line.starts_with("define ") && line.contains("@f(")).unwrap();
+	assert!(function.contains("section \".fastcode\""), "{llvm_ir}");
+}
+
+#[test]
+fn call_in_tail_position_is_tail_call() {
+	let directory = TestDirectory::new("call_in_tail_position_is_tail_call");
+	let llvm_ir = directory.llvm_ir("count_up = (n) {\n\tcount_up(n + 1)\n};\nnot_tail = (n) {\n\tcount_up(n) + 1\n};\n", &[]);
+	assert!(function_body(&llvm_ir, "count_up").contains(" = tail call "), "{llvm_ir}");
+	let not_tail_body = function_body(&llvm_ir, "not_tail");
+	assert!(not_tail_body.contains(" = call ") && !not_tail_body.contains(" tail call "), "{llvm_ir}");
+}
+
+#[test]
+fn trap_mode_selects_runtime_failure() {
+	let source = "divide = (a, b) { a / b };\n";
+	let directory = TestDirectory::new("trap_mode_selects_runtime_failure");
+	let trap_llvm_ir = directory.llvm_ir(source, &["--checked-div", "--trap-mode", "trap"]);
+	assert!(trap_llvm_ir.contains("call void @llvm.trap()"), "{trap_llvm_ir}");
+	assert!(!trap_llvm_ir.contains("__bcz_panic"), "{trap_llvm_ir}");
+	let abort_llvm_ir = directory.llvm_ir(source, &["--checked-div", "--trap-mode", "abort"]);
+	assert!(abort_llvm_ir.contains("call void @__bcz_panic()"), "{abort_llvm_ir}");
+	assert!(!abort_llvm_ir.contains("llvm.trap"), "{abort_llvm_ir}");
+}
+
+#[test]
+fn crlf_line_endings_build_the_same_as_lf() {
+	let source = "// Comment\nf = (a) {\n\ta + 1\n};\ng = 'x';\n";
+	let directory = TestDirectory::new("crlf_line_endings_build_the_same_as_lf");
+	let lf_llvm_ir = directory.llvm_ir(source, &[]);
+	let crlf_llvm_ir = directory.llvm_ir(&source.replace('\n', "\r\n"), &[]);
+	let cr_llvm_ir = directory.llvm_ir(&source.replace('\n', "\r"), &[]);
+	assert_eq!(lf_llvm_ir, crlf_llvm_ir);
+	assert_eq!(lf_llvm_ir, cr_llvm_ir);
+}
+
+#[test]
+fn linked_function_call_has_calling_convention() {
+	let directory = TestDirectory::new("linked_function_call_has_calling_convention");
+	let llvm_ir = directory.llvm_ir("puts = @link(\"puts\", \"libc\", 4, 8);\nmain = @entry_point () {\n\tputs(0)\n};\n", &[]);
+	assert!(function_body(&llvm_ir, "__bcz__link__puts").contains(" = call win64cc i32 @puts("), "{llvm_ir}");
+}
+
+#[test]
+fn freestanding_entry_point_is_start() {
+	let directory = TestDirectory::new("freestanding_entry_point_is_start");
+	let llvm_ir = directory.llvm_ir(
+		"puts = @link(\"puts\", \"libc\", 4, 8);\nmain = @entry_point () {\n\tputs(0)\n};\n", &["--freestanding", "--target-triplet", "x86_64-pc-windows-msvc"]
+	);
+	let defined_functions = defined_functions(&llvm_ir);
+	assert!(defined_functions.contains(&"_start"), "{llvm_ir}");
+	assert!(!defined_functions.contains(&"WinMain"), "{llvm_ir}");
+	// Linked functions use the C calling convention and are not imported from a DLL
+	assert!(llvm_ir.contains("declare i32 @puts("), "{llvm_ir}");
+	assert!(!llvm_ir.contains("dllimport"), "{llvm_ir}");
+}
+
+#[test]
+fn fast_math_sets_flags_on_float_operations() {
+	let source = "add = (a, b) { a ~+ b };\n";
+	let directory = TestDirectory::new("fast_math_sets_flags_on_float_operations");
+	let fast_llvm_ir = directory.llvm_ir(source, &["--ffast-math"]);
+	assert!(fast_llvm_ir.contains(" = fadd fast double "), "{fast_llvm_ir}");
+	let llvm_ir = directory.llvm_ir(source, &[]);
+	assert!(llvm_ir.contains(" = fadd double "), "{llvm_ir}");
+}
+
+#[test]
+fn ir_comments_add_source_positions_to_names() {
+	let source = "f = (a, b) {\n\ta + b\n};\n";
+	let directory = TestDirectory::new("ir_comments_add_source_positions_to_names");
+	let llvm_ir = directory.llvm_ir(source, &["--ir-comments"]);
+	assert!(llvm_ir.contains("%add_temp_L2C2 = add i64 "), "{llvm_ir}");
+	let llvm_ir = directory.llvm_ir(source, &[]);
+	assert!(llvm_ir.contains("%add_temp = add i64 "), "{llvm_ir}");
+}
+
+#[test]
+fn named_function_is_called_directly() {
+	let source = "double = (a) {\n\ta * 2\n};\napply = (function, a) {\n\tfunction(a)\n};\nmain = @entry_point () {\n\tdouble(3) + apply(double, 4)\n};\n";
+	let llvm_ir = TestDirectory::new("named_function_is_called_directly").llvm_ir(source, &[]);
+	let main_body = function_body(&llvm_ir, "main");
+	assert!(main_body.contains(" call i64 @double(i64 3)"), "{main_body}");
+	assert!(!main_body.contains("inttoptr"), "{main_body}");
+	// A function pointer that is not a global function is still called through a pointer
+	let apply_body = function_body(&llvm_ir, "apply");
+	assert!(apply_body.contains("inttoptr"), "{apply_body}");
+}
+
+#[test]
+fn unchecked_division_has_no_zero_check() {
+	let llvm_ir = TestDirectory::new("unchecked_division_has_no_zero_check").llvm_ir("divide = (a, b) { a / b };\nmodulo = (a, b) { a % b };\n", &[]);
+	for function_name in ["divide", "modulo"] {
+		let body = function_body(&llvm_ir, function_name);
+		assert!(!body.contains("icmp") && !body.contains("llvm.trap") && !body.contains("br i1"), "{body}");
+	}
+}
+
+#[test]
+fn modules_are_identified_by_compiler_version() {
+	let llvm_ir = TestDirectory::new("modules_are_identified_by_compiler_version").llvm_ir("main = @entry_point () {\n\t0\n};\n", &[]);
+	assert!(llvm_ir.contains("!llvm.ident = !{!0}"), "{llvm_ir}");
+	assert!(llvm_ir.contains(&format!("!0 = !{{!\"BCZ compiler version {}\"}}", env!("CARGO_PKG_VERSION"))), "{llvm_ir}");
+}
+
+#[test]
+fn operator_overloading_calls_overload_functions() {
+	let source = "__add = (a, b) {\n\ta - b\n};\nf = (a, b) {\n\ta + b - 1\n};\n";
+	let directory = TestDirectory::new("operator_overloading_calls_overload_functions");
+	let llvm_ir = directory.llvm_ir(source, &["--operator-overloading"]);
+	let body = function_body(&llvm_ir, "f");
+	assert!(body.contains(" = call i64 @__add(i64 "), "{body}");
+	// Operators without an overload function and the operators inside the overload function use the built in int operators
+	assert!(body.contains(" = sub i64 ") && !body.contains(" = add i64 "), "{body}");
+	let body = function_body(&llvm_ir, "__add");
+	assert!(body.contains(" = sub i64 ") && !body.contains("call"), "{body}");
+	// Operators are not overloaded without the option
+	let llvm_ir = directory.llvm_ir(source, &[]);
+	let body = function_body(&llvm_ir, "f");
+	assert!(body.contains(" = add i64 ") && !body.contains("call"), "{body}");
+}
+
+#[test]
+fn narrow_globals_use_the_narrowest_int_type() {
+	let source = "small = 200;\nnegative = 0 - 3;\nlarge = 70000;\nreferenced = 1;\nmain = @entry_point () {\n\tsmall + negative + large + *(&referenced)\n};\n";
+	let directory = TestDirectory::new("narrow_globals_use_the_narrowest_int_type");
+	let llvm_ir = directory.llvm_ir(source, &["--narrow-globals"]);
+	for expected_global in [
+		"@small = internal constant i8 -56", "@negative = internal constant i8 -3", "@large = internal constant i32 70000",
+		// Globals that have their address taken are read with the full int width
+		"@referenced = internal constant i64 1",
+	] {
+		assert!(llvm_ir.contains(expected_global), "{expected_global} not in:\n{llvm_ir}");
+	}
+	let llvm_ir = directory.llvm_ir(source, &[]);
+	assert!(llvm_ir.contains("@small = internal constant i64 200"), "{llvm_ir}");
+}
+
+#[test]
+fn builtins_are_lowered_and_other_names_resolve_to_globals() {
+	let source = "__builtin_double = (a) {\n\ta * 2\n};\nf = (a, b) {\n\t__builtin_memcpy(a, b, 8);\n\t__builtin_trap();\n\t__builtin_double(a)\n};\n\
+		g = (__builtin_trap) {\n\t__builtin_trap()\n};\n";
+	let llvm_ir = TestDirectory::new("builtins_are_lowered_and_other_names_resolve_to_globals").llvm_ir(source, &[]);
+	let body = function_body(&llvm_ir, "f");
+	assert!(body.contains("call void @llvm.memcpy.p0i8.p0i8.i64(") && body.contains("call void @llvm.trap()"), "{body}");
+	// A name that is not registered as a built-in is called as the user global function of that name
+	assert!(body.contains(" = tail call i64 @__builtin_double(i64 "), "{body}");
+	assert!(!llvm_ir.contains("@__builtin_trap") && !llvm_ir.contains("@__builtin_memcpy"), "{llvm_ir}");
+	// A local variable shadows a built-in
+	let body = function_body(&llvm_ir, "g");
+	assert!(body.contains("inttoptr") && !body.contains("@llvm.trap"), "{body}");
+}
+
+#[test]
+fn global_initialized_to_address_of_global() {
+	let llvm_ir = TestDirectory::new("global_initialized_to_address_of_global").llvm_ir("other = 42;\ng = &other;\n", &[]);
+	assert!(llvm_ir.contains("@g = internal constant i64 ptrtoint (i64* @other to i64)"), "{llvm_ir}");
+}
+
+#[test]
+fn entry_point_return_value_is_coerced_to_exit_code() {
+	let llvm_ir = TestDirectory::new("entry_point_return_value_is_coerced_to_exit_code").llvm_ir("f = () {\n\t0x100000000 + 42\n};\nmain = @entry_point () {\n\tf()\n};\n", &[]);
+	let entry_point = function_body(&llvm_ir, "main");
+	assert!(entry_point.contains("trunc i64 %function_call_temp to i32"), "{llvm_ir}");
+	assert!(entry_point.contains("sext i32 %exit_code_temp to i64"), "{llvm_ir}");
+	assert!(!function_body(&llvm_ir, "f").contains("trunc"), "{llvm_ir}");
+}
+
+#[test]
+fn linux_entry_stub_uses_exit_syscall_of_target_cpu() {
+	let directory = TestDirectory::new("linux_entry_stub_uses_exit_syscall_of_target_cpu");
+	let source = "main = @entry_point () {\n\t3\n};\n";
+	assert_success(&directory.compile_source(source, &["-c", "--target-triplet", "x86_64-pc-linux-gnu"]));
+	let entry_stub = directory.read("entry.s");
+	assert!(entry_stub.contains("movl $60, %eax\n\tsyscall"), "{entry_stub}");
+	assert_success(&directory.compile_source(source, &["-c", "--target-triplet", "i686-pc-linux-gnu"]));
+	let entry_stub = directory.read("entry.s");
+	assert!(entry_stub.contains("movl $1, %eax\n\tint $0x80"), "{entry_stub}");
+	assert!(!entry_stub.contains("syscall"), "{entry_stub}");
+}
+
+#[test]
+fn array_assignment_is_memcpy() {
+	let llvm_ir = TestDirectory::new("array_assignment_is_memcpy").llvm_ir("f = () {\n\ta = @stack(4, 2);\n\tb = @stack(4, 2);\n\tb = a;\n\t0\n};\n", &[]);
+	assert!(function_body(&llvm_ir, "f").contains("call void @llvm.memcpy.p0i8.p0i8.i64(i8* align 1 %0, i8* align 1 %1, i64 8, i1 false)"), "{llvm_ir}");
+}
+
+#[test]
+fn bitwise_operation_on_float_is_bitcast_to_int_and_back() {
+	let directory = TestDirectory::new("bitwise_operation_on_float_is_bitcast_to_int_and_back");
+	let source = "absolute_sum = (a, b) {\n\t(a ~+ b) ~& 0x7FFFFFFFFFFFFFFF\n};\nmain = @entry_point () {\n\tabsolute_sum(0f1, 0f0 ~- 0f2.5) == 0f1.5\n};\n";
+	let llvm_ir = directory.llvm_ir(source, &[]);
+	let body = function_body(&llvm_ir, "absolute_sum");
+	// The float sum is masked as an int then cast back to a float, which is only cast to an int again when it is returned
+	assert!(body.contains("%float_to_int_temp = bitcast double %fadd_temp to i64"), "{llvm_ir}");
+	assert!(body.contains("%band_temp = and i64 %float_to_int_temp, 9223372036854775807"), "{llvm_ir}");
+	assert!(body.contains("%int_to_float_temp3 = bitcast i64 %band_temp to double"), "{llvm_ir}");
+	assert!(body.contains("bitcast double %int_to_float_temp3 to i64"), "{llvm_ir}");
+	assert_eq!(directory.exit_code(source), 1);
+}
@@ -0,0 +1,99 @@
+//! Helpers for tests that run the compiler on source files written to a temporary directory.
+#![allow(dead_code)]
+
+use std::{env::{consts::EXE_SUFFIX, temp_dir}, fs::{create_dir_all, read_to_string, remove_dir_all, write}, path::{Path, PathBuf}, process::{Command, ExitStatus, Output}};
+
+/// A directory that the source files of a test are written to and that the compiler writes its output files to, deleted when dropped.
+pub struct TestDirectory {
+	path: PathBuf,
+}
+
+impl TestDirectory {
+	/// Creates an empty directory for the test `name`, each test should use a different name since tests run at the same time.
+	pub fn new(name: &str) -> Self {
+		let path = temp_dir().join(format!("bcz_test_{}_{name}", std::process::id()));
+		let _ = remove_dir_all(&path);
+		create_dir_all(&path).unwrap();
+		Self { path }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Writes a file to the directory and returns its path.
+	pub fn write(&self, filename: &str, content: &str) -> PathBuf {
+		let filepath = self.path.join(filename);
+		if let Some(parent) = filepath.parent() {
+			create_dir_all(parent).unwrap();
+		}
+		write(&filepath, content).unwrap();
+		filepath
+	}
+
+	/// Reads a file that was written to the directory.
+	pub fn read(&self, filename: &str) -> String {
+		read_to_string(self.path.join(filename)).unwrap()
+	}
+
+	/// Runs the compiler with `arguments` and the directory as the binary home directory.
+	pub fn compile(&self, arguments: &[&str]) -> Output {
+		compiler_command().arg("-b").arg(&self.path).args(arguments).output().unwrap()
+	}
+
+	/// Writes `source` to `main.bcz` and compiles it with `arguments`.
+	pub fn compile_source(&self, source: &str, arguments: &[&str]) -> Output {
+		let filepath = self.write("main.bcz", source);
+		self.compile(&[arguments, &[filepath.to_str().unwrap()]].concat())
+	}
+
+	/// Compiles `source` without linking and returns the LLVM IR of the module, panicking if it does not compile.
+	pub fn llvm_ir(&self, source: &str, arguments: &[&str]) -> String {
+		let output = self.compile_source(source, &[arguments, &["-c", "--dump-llvm-module"]].concat());
+		assert_success(&output);
+		// LLVM dumps modules to stderr
+		String::from_utf8_lossy(&output.stderr).into_owned()
+	}
+
+	/// Compiles and links `source` into an executable and runs it, panicking if it does not compile.
+	pub fn run(&self, source: &str, arguments: &[&str]) -> ExitStatus {
+		let executable_filename = format!("program{EXE_SUFFIX}");
+		let output = self.compile_source(source, &[arguments, &["-o", &executable_filename]].concat());
+		assert_success(&output);
+		Command::new(self.path.join(executable_filename)).status().unwrap()
+	}
+
+	/// Runs `source` and returns the exit code of the program, the low 8 bits of the value returned by the entry point on Linux.
+	pub fn exit_code(&self, source: &str) -> i32 {
+		self.run(source, &[]).code().expect("program was terminated by a signal")
+	}
+}
+
+impl Drop for TestDirectory {
+	fn drop(&mut self) {
+		let _ = remove_dir_all(&self.path);
+	}
+}
+
+/// Get a command that runs the compiler from the root of the repository so that the standard library can be found.
+pub fn compiler_command() -> Command {
+	let mut command = Command::new(env!("CARGO_BIN_EXE_bcz_programming_language"));
+	command.current_dir(env!("CARGO_MANIFEST_DIR"));
+	command
+}
+
+pub fn stdout(output: &Output) -> String {
+	String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// Panics with the output of the compiler if it failed.
+pub fn assert_success(output: &Output) {
+	assert!(output.status.success(), "compiler failed:\n{}{}", stdout(output), String::from_utf8_lossy(&output.stderr));
+}
+
+/// Panics with the output of the compiler if it succeeded or if its output does not contain `expected`.
+pub fn assert_failure(output: &Output, expected: &str) {
+	let stdout = stdout(output);
+	assert!(!output.status.success(), "compiler succeeded:\n{stdout}");
+	assert!(stdout.contains(expected), "expected {expected:?} in:\n{stdout}");
+}
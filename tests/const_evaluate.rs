@@ -0,0 +1,120 @@
+//! Tests that check how globals are folded by const evaluation.
+mod common;
+
+use common::{assert_failure, assert_success, stdout, TestDirectory};
+
+/// Const evaluates `source` and returns the printed trees of the const evaluated globals.
+fn const_evaluated_globals(test_name: &str, source: &str) -> String {
+	let directory = TestDirectory::new(test_name);
+	let output = directory.compile_source(source, &["--check", "--print-after-const-evaluate"]);
+	assert_success(&output);
+	stdout(&output)
+}
+
+#[test]
+fn augmented_assignment_operand_is_folded() {
+	let globals = const_evaluated_globals("augmented_assignment_operand_is_folded", "f = (a) {\n\tx = a;\n\tx += 2 + 3;\n\tx\n};\n");
+	assert!(globals.contains("--{ 3:2 to 3:12 Operator, operator: Augmented(IntegerAdd) }"), "{globals}");
+	assert!(globals.contains("---{ 3:7 to 3:12 Constant, value: 5 }"), "{globals}");
+}
+
+#[test]
+fn const_expression_is_folded() {
+	let globals = const_evaluated_globals("const_expression_is_folded", "x = @const 2 + 3;\n");
+	assert!(globals.contains("x -> {} = { 1:5 to 1:17 Constant, value: 5 }"), "{globals}");
+}
+
+#[test]
+fn const_function_call_is_an_error() {
+	let directory = TestDirectory::new("const_function_call_is_an_error");
+	let output = directory.compile_source("f = (a) { a };\nx = @const f(1);\n", &["--check"]);
+	assert_failure(&output, "main.bcz:2:12: Expression marked @const can not be evaluated at compile time.");
+}
+
+#[test]
+fn passing_static_assertion() {
+	let globals = const_evaluated_globals("passing_static_assertion", "A = 4;\ncheck = @assert(A == 4);\n");
+	assert!(globals.contains("check -> {\"A\"} = { 2:9 to 2:24 Constant, value: 0 }"), "{globals}");
+}
+
+#[test]
+fn failing_static_assertion() {
+	let directory = TestDirectory::new("failing_static_assertion");
+	let output = directory.compile_source("A = 4;\ncheck = @assert(A == 5);\n", &["--check"]);
+	assert_failure(&output, "main.bcz:2:9: Static assertion failed.");
+}
+
+#[test]
+fn non_constant_static_assertion() {
+	let directory = TestDirectory::new("non_constant_static_assertion");
+	let output = directory.compile_source("f = (a) { a };\ncheck = @assert(f(1));\n", &["--check"]);
+	assert_failure(&output, "main.bcz:2:17: Static assertion condition can not be evaluated at compile time.");
+}
+
+#[test]
+fn sizeof_is_folded() {
+	let globals = const_evaluated_globals("sizeof_is_folded", "four = @sizeof(4);\none = @sizeof(2 - 1);\n");
+	assert!(globals.contains("four -> {} = { 1:8 to 1:18 Constant, value: 4 }"), "{globals}");
+	assert!(globals.contains("one -> {} = { 2:7 to 2:21 Constant, value: 1 }"), "{globals}");
+}
+
+#[test]
+fn float_operations_are_folded() {
+	let globals = const_evaluated_globals("float_operations_are_folded", "sum = 0f1.5 ~+ 0f2.0;\nproduct = 0f1.5 ~* 0f2.0;\n");
+	assert!(globals.contains(&format!("sum -> {{}} = {{ 1:7 to 1:21 Constant, value: {} }}", 3.5f64.to_bits())), "{globals}");
+	assert!(globals.contains(&format!("product -> {{}} = {{ 2:11 to 2:25 Constant, value: {} }}", 3f64.to_bits())), "{globals}");
+}
+
+#[test]
+fn mixed_int_and_float_operations_are_not_folded_as_floats() {
+	let source = "bits = 0f1.0 + 1;\nadd = (a) { a ~+ 0f2.0 };\nnan = 0f0.0 ~/ 0f0.0;\n";
+	let globals = const_evaluated_globals("mixed_int_and_float_operations_are_not_folded_as_floats", source);
+	// An int add adds to the bits of the float instead of adding 1.0
+	assert!(globals.contains(&format!("bits -> {{}} = {{ 1:8 to 1:17 Constant, value: {} }}", 1f64.to_bits() + 1)), "{globals}");
+	assert!(globals.contains("--{ 2:13 to 2:23 Operator, operator: Normal(FloatAdd) }"), "{globals}");
+	assert!(globals.contains("nan -> {} = { 3:7 to 3:21 Operator, operator: Normal(FloatDivide) }"), "{globals}");
+}
+
+#[test]
+fn overflowing_constant_arithmetic_is_an_error_when_checked() {
+	let directory = TestDirectory::new("overflowing_constant_arithmetic_is_an_error_when_checked");
+	for expression in ["int_max * 2", "int_max + 1", "int_min - 1", "0 - int_min", "int_min $/ (0 - 1)"] {
+		let source = format!("int_max = 0x7FFFFFFFFFFFFFFF;\nint_min = 0 - int_max - 1;\nx = {expression};\n");
+		assert_failure(&directory.compile_source(&source, &["--check", "--overflow-check-const"]), "main.bcz:3:5: Constant arithmetic overflows a signed int");
+		// Without the option the result wraps
+		assert_success(&directory.compile_source(&source, &["--check"]));
+	}
+	let output = directory.compile_source(
+		"int_max = 0x7FFFFFFFFFFFFFFF;\nx = int_max - 1 + 1;\n", &["--check", "--overflow-check-const", "--print-after-const-evaluate"]
+	);
+	assert_success(&output);
+	let globals = stdout(&output);
+	assert!(globals.contains("x -> {\"int_max\"} = { 2:5 to 2:20 Constant, value: 9223372036854775807 }"), "{globals}");
+}
+
+#[test]
+fn signed_and_unsigned_comparisons_near_int_max_are_folded() {
+	let globals = const_evaluated_globals(
+		"signed_and_unsigned_comparisons_near_int_max_are_folded",
+		"int_max = 0x7FFFFFFFFFFFFFFF;\nx = (int_max < int_max + 1) + (int_max $< int_max + 1) * 2 + (int_max + 1 $< int_max) * 4 + (int_max + 1 < int_max) * 8;\n",
+	);
+	assert!(globals.contains("x -> {\"int_max\"} = { 2:6 to 2:120 Constant, value: 5 }"), "{globals}");
+}
+
+#[test]
+fn unsigned_wraparound_is_not_a_constant_overflow() {
+	let directory = TestDirectory::new("unsigned_wraparound_is_not_a_constant_overflow");
+	// Negative constants are stored as unsigned ints, so adding to them wraps past the int width without overflowing as signed
+	let output = directory.compile_source(
+		"minus_one = 0xFFFFFFFFFFFFFFFF;\nx = minus_one + 1;\ny = minus_one * minus_one;\n", &["--check", "--overflow-check-const", "--print-after-const-evaluate"]
+	);
+	assert_success(&output);
+	let globals = stdout(&output);
+	assert!(globals.contains("x -> {\"minus_one\"} = { 2:5 to 2:18 Constant, value: 0 }"), "{globals}");
+	assert!(globals.contains("y -> {\"minus_one\"} = { 3:5 to 3:26 Constant, value: 1 }"), "{globals}");
+	// Overflow is checked against the int width of the target
+	let output = directory.compile_source(
+		"x = 0x7FFFFFFF * 2;\n", &["--check", "--overflow-check-const", "--target-triplet", "i686-pc-linux-gnu"]
+	);
+	assert_failure(&output, "main.bcz:1:5: Constant arithmetic overflows a signed int");
+}
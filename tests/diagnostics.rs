@@ -0,0 +1,209 @@
+//! Tests that check the errors and warnings printed by the compiler.
+mod common;
+
+use common::{assert_failure, assert_success, stdout, TestDirectory};
+
+/// A program with an invalid keyword.
+const INVALID_PROGRAM: &str = "main = @entry_pint () {\n\t0\n};\n";
+
+#[test]
+fn color_never_prints_no_escape_codes() {
+	let directory = TestDirectory::new("color_never_prints_no_escape_codes");
+	let output = directory.compile_source(INVALID_PROGRAM, &["--check", "--color", "never"]);
+	assert_failure(&output, "@entry_pint");
+	assert!(!stdout(&output).contains('\x1b'));
+}
+
+#[test]
+fn color_always_prints_escape_codes() {
+	let directory = TestDirectory::new("color_always_prints_escape_codes");
+	let output = directory.compile_source(INVALID_PROGRAM, &["--check", "--color", "always"]);
+	assert_failure(&output, "@entry_pint");
+	assert!(stdout(&output).contains("\x1b["));
+}
+
+#[test]
+fn strict_globals_reports_misspelled_global() {
+	let directory = TestDirectory::new("strict_globals_reports_misspelled_global");
+	let output = directory.compile_source("count = 1;\nmain = @entry_point () {\n\tcoutn + 1\n};\n", &["--check", "--strict-globals"]);
+	assert_failure(&output, "main.bcz:3:2: Use of undefined global variable coutn.");
+}
+
+#[test]
+fn json_error_format() {
+	let directory = TestDirectory::new("json_error_format");
+	let output = directory.compile_source("main = @entry_point () {\n\t0 +\n};\n", &["--check", "--error-format", "json"]);
+	assert_failure(&output, "\"message\":\"Operator used on nothing\"");
+	let stdout = stdout(&output);
+	let json_error = stdout.lines().find(|line| line.starts_with('{')).unwrap();
+	assert!(json_error.starts_with("{\"severity\":\"error\",\"code\":\"E0020\","), "{json_error}");
+	assert!(json_error.ends_with("main.bcz\",\"line\":2,\"column\":4,\"end_column\":5}"), "{json_error}");
+}
+
+#[test]
+fn crlf_line_endings_keep_error_positions() {
+	let directory = TestDirectory::new("crlf_line_endings_keep_error_positions");
+	let output = directory.compile_source(&INVALID_PROGRAM.replace('\n', "\r\n"), &["--check"]);
+	assert_failure(&output, "main.bcz:1:8: ");
+	assert!(!stdout(&output).contains('\r'));
+}
+
+#[test]
+fn underline_covers_error_span() {
+	let directory = TestDirectory::new("underline_covers_error_span");
+	let output = directory.compile_source("f = (a) {\n\ta + 1 = 3\n};\n", &["-c", "--color", "never"]);
+	assert_failure(&output, "main.bcz:2:2: ");
+	assert!(stdout(&output).ends_with("2 | \ta + 1 = 3\n  | \t^^^^^\n"), "{}", stdout(&output));
+	let output = directory.compile_source("f = (a) {\n\ta <=\n};\n", &["--check", "--color", "never"]);
+	assert_failure(&output, "main.bcz:2:4: ");
+	assert!(stdout(&output).ends_with("2 | \ta <=\n  | \t  ^^\n"), "{}", stdout(&output));
+}
+
+#[test]
+fn break_outside_loop() {
+	let directory = TestDirectory::new("break_outside_loop");
+	let output = directory.compile_source("main = @entry_point () {\n\t@break;\n\t0\n};\n", &["-c"]);
+	assert_failure(&output, "main.bcz:2:2: Not used inside loop.");
+}
+
+#[test]
+fn array_assigned_array_of_other_length() {
+	let directory = TestDirectory::new("array_assigned_array_of_other_length");
+	let output = directory.compile_source("main = @entry_point () {\n\ta = @stack(2);\n\tb = @stack(3);\n\tb = a;\n\tb = 1;\n\t0\n};\n", &["-c"]);
+	assert_failure(&output, "main.bcz:4:2: An array can only be assigned an array with the same entry count and width");
+}
+
+#[test]
+fn default_parameter_errors() {
+	let directory = TestDirectory::new("default_parameter_errors");
+	let output = directory.compile_source("f = (x, y, z = 2) { x + y + z };\nmain = @entry_point () {\n\tf(1)\n};\n", &["-c"]);
+	assert_failure(&output, "main.bcz:3:2: Function with 3 parameters called with 1 arguments, only parameters with a default value can be omitted");
+	let output = directory.compile_source("g = () { 1 };\nf = (x, y = g()) { x + y };\n", &["-c"]);
+	assert_failure(&output, "main.bcz:2:13: Default parameter value can not be evaluated at compile time");
+	let output = directory.compile_source("main = @entry_point () {\n\tf = (x, y = 2) { x + y };\n\tf(1)\n};\n", &["-c"]);
+	assert_failure(&output, "main.bcz:2:14: Only global functions can have default parameter values");
+}
+
+#[test]
+fn void_block_used_as_operand() {
+	let directory = TestDirectory::new("void_block_used_as_operand");
+	let output = directory.compile_source("main = @entry_point () {\n\t{} + 1\n};\n", &["-c"]);
+	assert_failure(&output, "main.bcz:2:2: Value of expression is void and can not be used as an operand");
+}
+
+#[test]
+fn deeply_nested_expressions_are_rejected() {
+	let directory = TestDirectory::new("deeply_nested_expressions_are_rejected");
+	let output = directory.compile_source(&format!("x = {}1{};\n", "(".repeat(10_000), ")".repeat(10_000)), &["--check"]);
+	assert_failure(&output, "Expression is nested more than 256 levels deep");
+	let output = directory.compile_source(&format!("x = 1{};\n", " + 1".repeat(10_000)), &["--check"]);
+	assert_failure(&output, "Expression is nested more than 256 levels deep");
+}
+
+#[test]
+fn unresolved_import_lists_searched_directories() {
+	let directory = TestDirectory::new("unresolved_import_lists_searched_directories");
+	let include_directory = directory.path().join("shared");
+	let source = "A = @import(\"missing.bcz\", \"A\");\nmain = @entry_point () {\n\tA\n};\n";
+	let output = directory.compile_source(source, &["-c", "-I", include_directory.to_str().unwrap()]);
+	assert_failure(&output, &format!(
+		"Could not find imported file \"missing.bcz\", searched in \"{}\", \"{}\"", directory.path().display(), include_directory.display()
+	));
+}
+
+#[test]
+fn function_limits() {
+	let directory = TestDirectory::new("function_limits");
+	let five_parameters = "f = (a, b, c, d, e) {\n\ta\n};\nmain = @entry_point () {\n\tf(1, 2, 3, 4, 5)\n};\n";
+	assert_failure(&directory.compile_source(five_parameters, &["-c", "--max-function-params", "4"]), "main.bcz:1:5: Too many function parameters");
+	assert_failure(&directory.compile_source(five_parameters, &["-c", "--max-function-args", "4"]), "main.bcz:5:2: Too many function arguments");
+	assert_success(&directory.compile_source(five_parameters, &["-c", "--max-function-params", "5", "--max-function-args", "5"]));
+	// Default arguments that are filled in count towards the limit
+	let defaults = "f = (a, b = 1, c = 1, d = 1, e = 1) {\n\ta\n};\nmain = @entry_point () {\n\tf(1)\n};\n";
+	assert_failure(&directory.compile_source(defaults, &["-c", "--max-function-args", "4"]), "main.bcz:5:2: Too many function arguments");
+	assert_failure(&directory.compile_source(five_parameters, &["-c", "--max-function-args", "lots"]), "Invalid function limit \"lots\", expected a number from 0 to 65535");
+}
+
+#[test]
+fn assignment_to_constant_global_in_function() {
+	let directory = TestDirectory::new("assignment_to_constant_global_in_function");
+	let source = "limit = 10;\nf = (a) {\n\tlimit = a;\n\ta\n};\nmain = @entry_point () {\n\tf(1)\n};\n";
+	assert_failure(&directory.compile_source(source, &["-c"]), "main.bcz:3:2: Can not assign to the constant global \"limit\".");
+	// Reading the global and taking its address are not assignments
+	let source = "limit = 10;\nf = (a) {\n\tx = &limit;\n\ta + limit\n};\nmain = @entry_point () {\n\tf(1)\n};\n";
+	assert_success(&directory.compile_source(source, &["-c"]));
+}
+
+#[test]
+fn features_gated_by_language_version() {
+	let directory = TestDirectory::new("features_gated_by_language_version");
+	let pipeline = "f = (a) { a + 1 };\nmain = @entry_point () {\n\t1 |> f\n};\n";
+	assert_failure(&directory.compile_source(pipeline, &["--check", "--std", "1"]), "Pipeline operators require language version 2 or later, use --std 2");
+	assert_success(&directory.compile_source(pipeline, &["--check", "--std", "2"]));
+	let comparison_chain = "f = (a) {\n\t1 < a < 3\n};\n";
+	assert_failure(&directory.compile_source(comparison_chain, &["-c", "--std", "1"]), "main.bcz:2:2: Comparison chains require language version 2 or later");
+	assert_success(&directory.compile_source(comparison_chain, &["-c", "--std", "2"]));
+}
+
+#[test]
+fn missing_return_on_one_branch() {
+	let directory = TestDirectory::new("missing_return_on_one_branch");
+	let source = "f = (a) {\n\ta ? {\n\t\t5\n\t} : {}\n};\nmain = @entry_point () {\n\tf(1)\n};\n";
+	assert_failure(&directory.compile_source(source, &["-c"]), "main.bcz:4:6: Function yields a value on some paths but not on this one.");
+	// Functions that yield a value on every path or on none are fine
+	let source = "f = (a) {\n\ta ? {\n\t\t5\n\t} : 6\n};\ng = (a) {\n\ta ? {} : {};\n};\nmain = @entry_point () {\n\tg(1);\n\tf(1)\n};\n";
+	assert_success(&directory.compile_source(source, &["-c"]));
+}
+
+#[test]
+fn abi_check_rejects_wide_link_types() {
+	let directory = TestDirectory::new("abi_check_rejects_wide_link_types");
+	let source = "f = @link(\"f\", \"libc\", 4, 16);\nmain = @entry_point () {\n\tf(0)\n};\n";
+	let output = directory.compile_source(source, &["-c", "--abi-check", "--target-triplet", "i686-pc-linux-gnu"]);
+	assert_failure(&output, "main.bcz:1:27: A 128 bit integer cannot be passed to or returned from a linked function in the C ABI of the target.");
+	// 128 bit integers are in the C ABI of 64-bit targets, and are not checked without --abi-check
+	assert_success(&directory.compile_source(source, &["-c", "--abi-check", "--target-triplet", "x86_64-pc-linux-gnu"]));
+	assert_success(&directory.compile_source(source, &["-c", "--target-triplet", "i686-pc-linux-gnu"]));
+	let source = "f = @link(\"f\", \"libc\", 8, 8);\nmain = @entry_point () {\n\tf(0)\n};\n";
+	assert_success(&directory.compile_source(source, &["-c", "--abi-check", "--target-triplet", "i686-pc-linux-gnu"]));
+}
+
+#[test]
+fn returning_address_of_local() {
+	let directory = TestDirectory::new("returning_address_of_local");
+	let source = "f = (a) {\n\tlocal = a;\n\t&local\n};\ng = (a) {\n\tb = &a;\n\tb\n};\nh = (a) {\n\tlocal = a;\n\tp = &local;\n\t*p\n};\n";
+	let output = directory.compile_source(source, &["-c"]);
+	assert_success(&output);
+	let warnings = stdout(&output);
+	assert!(warnings.contains("main.bcz:3:2: The address of a local variable is returned from its function"), "{warnings}");
+	// The address is still tracked after it is assigned to another variable, and reading through it is fine
+	assert!(warnings.contains("main.bcz:7:2: The address of a local variable is returned from its function"), "{warnings}");
+	assert_eq!(warnings.matches("Warning[E0103]").count(), 2, "{warnings}");
+	assert_failure(&directory.compile_source(source, &["-c", "--strict"]), "Error[E0103]");
+}
+
+#[test]
+fn compilation_past_timeout_is_stopped() {
+	let directory = TestDirectory::new("compilation_past_timeout_is_stopped");
+	// With no time at all the deadline has passed when the first expression is parsed
+	let output = directory.compile_source("x = 1;\ny = 2;\n", &["--check", "--timeout", "0"]);
+	assert_failure(&output, "main.bcz:1:1: Compilation took longer than the time limit set with --timeout.");
+	assert_failure(&directory.compile_source("x = 1;\n", &["--check", "--timeout", "soon"]), "Invalid timeout \"soon\", expected a number of milliseconds");
+	assert_success(&directory.compile_source("x = 1;\n", &["--check", "--timeout", "60000"]));
+}
+
+#[test]
+fn suppressed_diagnostics_are_hidden() {
+	let directory = TestDirectory::new("suppressed_diagnostics_are_hidden");
+	let source = "f = (a) {\n\tlocal = a;\n\t&local\n};\n";
+	let output = directory.compile_source(source, &["-c"]);
+	assert_success(&output);
+	assert!(stdout(&output).contains("Warning[E0103]"), "{}", stdout(&output));
+	let output = directory.compile_source(source, &["-c", "--suppress", "E0103"]);
+	assert_success(&output);
+	assert!(!stdout(&output).contains("E0103"), "{}", stdout(&output));
+	// Suppressed errors are not printed but still fail the build
+	let output = directory.compile_source(INVALID_PROGRAM, &["--check", "--suppress", "E0011"]);
+	assert!(!output.status.success());
+	assert!(!stdout(&output).contains("E0011"), "{}", stdout(&output));
+}
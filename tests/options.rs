@@ -0,0 +1,312 @@
+//! Tests for compiler options that change how a program is built.
+mod common;
+
+use std::{fs::{read, read_dir}, path::{Path, PathBuf}};
+
+use common::{assert_failure, assert_success, stdout, TestDirectory};
+
+/// Get the paths of the object files in `directory` and its subdirectories.
+fn object_files(directory: &Path) -> Vec<PathBuf> {
+	let mut paths = Vec::new();
+	for entry in read_dir(directory).unwrap() {
+		let path = entry.unwrap().path();
+		if path.is_dir() {
+			paths.extend(object_files(&path));
+		}
+		else if path.extension().is_some_and(|extension| extension == "o") {
+			paths.push(path);
+		}
+	}
+	paths
+}
+
+/// Compiles `source` without linking and returns the bytes of the object file.
+fn compile_object(test_name: &str, source: &str, arguments: &[&str]) -> Vec<u8> {
+	let directory = TestDirectory::new(test_name);
+	assert_success(&directory.compile_source(source, &[arguments, &["-c"]].concat()));
+	let object_files = object_files(directory.path());
+	assert_eq!(object_files.len(), 1);
+	read(&object_files[0]).unwrap()
+}
+
+/// A program that takes the address of a global, which is built differently with position independent code.
+const GLOBAL_ADDRESS_PROGRAM: &str = "g = 5;\nmain = @entry_point () {\n\t&g\n};\n";
+
+#[test]
+fn pic_changes_object_file() {
+	let pic_object = compile_object("pic_changes_object_file_pic", GLOBAL_ADDRESS_PROGRAM, &["--pic"]);
+	let static_object = compile_object("pic_changes_object_file_static", GLOBAL_ADDRESS_PROGRAM, &["--no-pic"]);
+	assert_ne!(pic_object, static_object);
+}
+
+#[test]
+fn code_model_changes_object_file() {
+	let small_object = compile_object("code_model_changes_object_file_small", GLOBAL_ADDRESS_PROGRAM, &["--no-pic", "--code-model", "small"]);
+	let large_object = compile_object("code_model_changes_object_file_large", GLOBAL_ADDRESS_PROGRAM, &["--no-pic", "--code-model", "large"]);
+	assert_ne!(small_object, large_object);
+}
+
+#[test]
+fn invalid_code_model() {
+	let directory = TestDirectory::new("invalid_code_model");
+	let output = directory.compile_source(GLOBAL_ADDRESS_PROGRAM, &["-c", "--code-model", "huge"]);
+	assert_failure(&output, "Invalid code model \"huge\"");
+}
+
+#[test]
+fn check_writes_no_files() {
+	let directory = TestDirectory::new("check_writes_no_files");
+	assert_success(&directory.compile_source("main = @entry_point () {\n\t0\n};\n", &["--check"]));
+	let filenames: Vec<_> = read_dir(directory.path()).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+	assert_eq!(filenames, ["main.bcz"]);
+}
+
+#[test]
+fn check_reports_errors() {
+	let directory = TestDirectory::new("check_reports_errors");
+	let output = directory.compile_source("main = @entry_point () {\n\t0 +\n};\n", &["--check"]);
+	assert_failure(&output, "main.bcz:2:4: Operator used on nothing.");
+}
+
+#[test]
+fn dump_symbols_prints_linkage() {
+	let directory = TestDirectory::new("dump_symbols_prints_linkage");
+	let output = directory.compile_source("puts = @link(\"puts\", \"libc\", 4, 8);\nmain = @entry_point () {\n\tputs(0)\n};\n", &["-c", "--dump-symbols"]);
+	assert_success(&output);
+	let symbols = stdout(&output);
+	let symbol_lines: Vec<&str> = symbols.lines().collect();
+	assert!(symbol_lines.contains(&"function main External"), "{symbols}");
+	assert!(symbol_lines.contains(&"function puts External"), "{symbols}");
+}
+
+#[test]
+fn dump_target_data_layout_prints_layout() {
+	let directory = TestDirectory::new("dump_target_data_layout_prints_layout");
+	let output = directory.compile_source("main = @entry_point () {\n\t0\n};\n", &["--check", "--dump-target-data-layout"]);
+	assert_success(&output);
+	let stdout = stdout(&output);
+	let data_layout = stdout.lines().find_map(|line| line.strip_prefix("Target data layout: ")).expect(&stdout);
+	assert!(!data_layout.is_empty());
+	// Pointer sizes are given by specifications starting with "p" such as "p:64:64" or "p270:32:32"
+	assert!(data_layout.split('-').any(|specification| specification.starts_with('p')), "{data_layout}");
+}
+
+#[test]
+fn empty_files_compile_without_linking() {
+	for (test_name, source) in [("empty_file_compiles", ""), ("comments_only_file_compiles", "// Nothing here yet\n\n/* Or here */\n")] {
+		let directory = TestDirectory::new(test_name);
+		assert_success(&directory.compile_source(source, &["-c"]));
+		assert_eq!(object_files(directory.path()).len(), 1);
+	}
+}
+
+#[test]
+fn empty_files_have_no_entry_point_to_link() {
+	for (test_name, source) in [("empty_file_links", ""), ("comments_only_file_links", "// Nothing here yet\n\n/* Or here */\n")] {
+		let directory = TestDirectory::new(test_name);
+		assert_failure(&directory.compile_source(source, &[]), "No entry point, a function marked with @entry_point is required to link an executable");
+	}
+}
+
+#[test]
+fn no_entry_compiles_libraries() {
+	let directory = TestDirectory::new("no_entry_compiles_libraries");
+	let library = "@export add = (a, b) {\n\ta + b\n};\n";
+	assert_success(&directory.compile_source(library, &["--no-entry"]));
+	assert_eq!(object_files(directory.path()).len(), 1);
+	assert_failure(&directory.compile_source(library, &[]), "No entry point, a function marked with @entry_point is required to link an executable");
+	// An entry point in a library is not wrapped in a start function
+	let entry_point = "main = @entry_point () {\n\t0\n};\n";
+	let output = directory.compile_source(entry_point, &["--no-entry", "--freestanding", "--dump-llvm-module"]);
+	assert_success(&output);
+	assert!(!String::from_utf8_lossy(&output.stderr).contains("@_start("));
+	assert!(directory.llvm_ir(entry_point, &["--freestanding"]).contains("define void @_start("));
+}
+
+#[test]
+#[cfg(unix)]
+fn library_options_are_passed_to_linker() {
+	use std::{fs::{set_permissions, Permissions}, os::unix::fs::PermissionsExt};
+
+	let directory = TestDirectory::new("library_options_are_passed_to_linker");
+	// A linker that records its arguments instead of linking
+	let linker_arguments_filepath = directory.path().join("linker_arguments.txt");
+	let linker_filepath = directory.write("linker.sh", &format!("#!/bin/sh\nprintf '%s\\n' \"$@\" > '{}'\n", linker_arguments_filepath.display()));
+	set_permissions(&linker_filepath, Permissions::from_mode(0o755)).unwrap();
+	let output = directory.compile_source("main = @entry_point () {\n\t0\n};\n", &[
+		"--link-command", linker_filepath.to_str().unwrap(), "--sysroot", "/opt/sysroot", "-L", "/opt/libs", "--library-path", "/opt/more_libs", "--library", "m",
+	]);
+	assert_success(&output);
+	let linker_arguments = directory.read("linker_arguments.txt");
+	let linker_arguments: Vec<&str> = linker_arguments.lines().collect();
+	for expected_argument in ["--sysroot=/opt/sysroot", "-L/opt/libs", "-L/opt/more_libs", "-lm"] {
+		assert!(linker_arguments.contains(&expected_argument), "{expected_argument} not in {linker_arguments:?}");
+	}
+}
+
+/// Compiles `main.bcz` in a directory for the test `test_name` with the directory as the source directory and returns the object file.
+fn compile_object_in_source_directory(test_name: &str, arguments: &[&str]) -> Vec<u8> {
+	let directory = TestDirectory::new(test_name);
+	directory.write("main.bcz", "main = @entry_point () {\n\t0\n};\n");
+	assert_success(&directory.compile(&[arguments, &["-s", directory.path().to_str().unwrap(), "-c", "main.bcz"]].concat()));
+	let object_files = object_files(directory.path());
+	assert_eq!(object_files.len(), 1);
+	read(&object_files[0]).unwrap()
+}
+
+#[test]
+fn deterministic_builds_do_not_depend_on_directory() {
+	assert_eq!(
+		compile_object_in_source_directory("deterministic_build_a", &["--deterministic"]),
+		compile_object_in_source_directory("deterministic_build_b", &["--deterministic"]),
+	);
+}
+
+#[test]
+fn print_tokens_decodes_operators() {
+	let directory = TestDirectory::new("print_tokens_decodes_operators");
+	let output = directory.compile_source("x = 1 ~+ 2 $& 3;\n", &["--check", "--print-tokens"]);
+	assert_success(&output);
+	let tokens = stdout(&output);
+	let token_lines: Vec<&str> = tokens.lines().collect();
+	for expected_line in [
+		"{ 1:3 to 1:4 Operator, symbol: None, type: UnsignedLogicalShortCircuit, is_assignment: true, is_l_value_assignment: false }",
+		"{ 1:7 to 1:9 Operator, symbol: AddRead +, type: FloatingPointBitwise, is_assignment: false, is_l_value_assignment: false }",
+		"{ 1:12 to 1:14 Operator, symbol: AndTakeRefrence &, type: SignedLogicalNotShortCircuit, is_assignment: false, is_l_value_assignment: false }",
+	] {
+		assert!(token_lines.contains(&expected_line), "{expected_line} not in:\n{tokens}");
+	}
+}
+
+#[test]
+fn keep_going_compiles_files_after_a_failure() {
+	let directory = TestDirectory::new("keep_going_compiles_files_after_a_failure");
+	let bad_filepath = directory.write("bad.bcz", "main = @entry_point () {\n\t0 +\n};\n");
+	let good_filepath = directory.write("good.bcz", "f = (a) { a + 1 };\n");
+	let output = directory.compile(&["-c", "--keep-going", bad_filepath.to_str().unwrap(), good_filepath.to_str().unwrap()]);
+	assert_failure(&output, "1 file failed to compile");
+	assert_failure(&output, "bad.bcz:2:4: Operator used on nothing.");
+	let object_filenames: Vec<_> = object_files(directory.path()).iter().map(|path| path.file_stem().unwrap().to_owned()).collect();
+	assert_eq!(object_filenames, ["good"]);
+	// Without the flag compiling stops at the bad file
+	let directory = TestDirectory::new("stop_after_a_failure");
+	let bad_filepath = directory.write("bad.bcz", "main = @entry_point () {\n\t0 +\n};\n");
+	let good_filepath = directory.write("good.bcz", "f = (a) { a + 1 };\n");
+	assert_failure(&directory.compile(&["-c", bad_filepath.to_str().unwrap(), good_filepath.to_str().unwrap()]), "Operator used on nothing.");
+	assert!(object_files(directory.path()).is_empty());
+}
+
+#[test]
+fn dep_file_lists_imported_sources() {
+	let directory = TestDirectory::new("dep_file_lists_imported_sources");
+	let imported_filepath = directory.write("constants.bcz", "@export A = 60;\n");
+	let main_filepath = directory.write("main.bcz", "A = @import(\"constants.bcz\", \"A\");\nmain = @entry_point () {\n\tA\n};\n");
+	let dep_filepath = directory.path().join("main.d");
+	assert_success(&directory.compile(&["-c", "--emit-dep-file", dep_filepath.to_str().unwrap(), main_filepath.to_str().unwrap()]));
+	let dep_file = directory.read("main.d");
+	let (targets, dependencies) = dep_file.split_once(": ").unwrap();
+	assert!(targets.ends_with(".o"), "{dep_file}");
+	assert!(dependencies.contains(main_filepath.to_str().unwrap()), "{dep_file}");
+	assert!(dependencies.contains(imported_filepath.to_str().unwrap()), "{dep_file}");
+}
+
+#[test]
+fn time_trace_writes_phase_events() {
+	let directory = TestDirectory::new("time_trace_writes_phase_events");
+	let time_trace_filepath = directory.path().join("trace.json");
+	let output = directory.compile_source("main = @entry_point () {\n\t0\n};\n", &["-c", "--time-trace", time_trace_filepath.to_str().unwrap()]);
+	assert_success(&output);
+	let time_trace = directory.read("trace.json");
+	assert!(time_trace.starts_with("{\"traceEvents\":["), "{time_trace}");
+	for phase_name in ["Tokenize", "Parse", "ConstEvaluate", "BuildGlobal"] {
+		assert!(time_trace.contains(&format!("{{\"name\":\"{phase_name}\",\"cat\":\"bcz\",\"ph\":\"X\"")), "{phase_name} not in {time_trace}");
+	}
+}
+
+#[test]
+fn dump_scopes_traces_nested_blocks() {
+	let directory = TestDirectory::new("dump_scopes_traces_nested_blocks");
+	let source = "f = (a) {\n\tb = a + 1;\n\t{\n\t\tc = b;\n\t\t{\n\t\t\td = c;\n\t\t};\n\t};\n\tb\n};\n";
+	let output = directory.compile_source(source, &["-c", "--dump-scopes"]);
+	assert_success(&output);
+	let scopes = stdout(&output);
+	assert!(scopes.ends_with(
+		"Enter function f: [a]\n\
+		\tEnter block: [a] []\n\
+		\tCreate variable b: [a] [b]\n\
+		\t\tEnter block: [a] [b] []\n\
+		\t\tCreate variable c: [a] [b] [c]\n\
+		\t\t\tEnter block: [a] [b] [c] []\n\
+		\t\t\tCreate variable d: [a] [b] [c] [d]\n\
+		\t\t\tExit block: [a] [b] [c] [d]\n\
+		\t\tExit block: [a] [b] [c]\n\
+		\tExit block: [a] [b]\n"
+	), "{scopes}");
+	// Scopes are not printed without the option
+	assert!(!stdout(&directory.compile_source(source, &["-c"])).contains("Enter block"));
+}
+
+#[test]
+fn print_phases_skips_codegen_when_checking() {
+	let directory = TestDirectory::new("print_phases_skips_codegen_when_checking");
+	let output = directory.compile_source("main = @entry_point () {\n\t0\n};\n", &["--check", "--print-phases"]);
+	assert_success(&output);
+	let phases: Vec<String> = stdout(&output).lines()
+		.filter(|line| line.starts_with('['))
+		.map(|line| line.replace(&*directory.path().join("main.bcz").to_string_lossy(), "main.bcz"))
+		.collect();
+	assert_eq!(phases, [
+		"[\u{2713}] Tokenize main.bcz",
+		"[\u{2713}] Parse main.bcz",
+		"[\u{2713}] SeparateGlobals main.bcz",
+		"[\u{2713}] Dependencies main.bcz",
+		"[\u{2713}] ConstEvaluate main.bcz",
+		"[ ] Codegen main.bcz (skipped)",
+		"[ ] Emit main.bcz (skipped)",
+		"[ ] Link (skipped)",
+	]);
+}
+
+#[test]
+fn dump_ir_after_codegen_prints_unoptimized_module() {
+	let directory = TestDirectory::new("dump_ir_after_codegen_prints_unoptimized_module");
+	let source = "main = @entry_point () {\n\tx = 2;\n\tx + 3\n};\n";
+	let output = directory.compile_source(source, &["-c", "--dump-ir-after", "codegen"]);
+	assert_success(&output);
+	let llvm_ir = stdout(&output);
+	assert!(llvm_ir.contains("main.bcz after codegen:\n"), "{llvm_ir}");
+	// The IR is as built, the local variable has not been optimized out of the entry function
+	assert!(llvm_ir.contains("define i64 @main() {\nentry:\n  %x = alloca i64, align 8\n"), "{llvm_ir}");
+	assert!(!llvm_ir.contains("after opt:"), "{llvm_ir}");
+	let output = directory.compile_source(source, &["-c", "--dump-ir-after", "opt"]);
+	assert_success(&output);
+	assert!(stdout(&output).contains("main.bcz after opt:\n"), "{}", stdout(&output));
+	assert_failure(&directory.compile_source(source, &["-c", "--dump-ir-after", "link"]), "Invalid IR phase \"link\", expected codegen or opt");
+}
+
+#[test]
+fn dump_metadata_lists_metadata_keywords() {
+	let directory = TestDirectory::new("dump_metadata_lists_metadata_keywords");
+	let output = directory.compile_source("puts = @link(\"puts\", \"libc\", 4, 8);\nmain = @entry_point () {\n\tputs(0)\n};\n", &["--check", "--dump-metadata"]);
+	assert_success(&output);
+	let metadata = stdout(&output);
+	assert!(metadata.ends_with("main.bcz:\n@link at 1:8 -> 4 arguments\n@entry_point at 2:8 -> FunctionDefinition\n"), "{metadata}");
+}
+
+#[test]
+fn metadata_json_describes_globals() {
+	let directory = TestDirectory::new("metadata_json_describes_globals");
+	let metadata_filepath = directory.path().join("metadata.json");
+	let source = "puts = @link(\"puts\", \"libc\", 4, 8);\n@export answer = 42;\nmain = @entry_point () {\n\tputs(0)\n};\n";
+	assert_success(&directory.compile_source(source, &["-c", "--emit-metadata-json", metadata_filepath.to_str().unwrap()]));
+	let metadata = directory.read("metadata.json");
+	assert!(metadata.starts_with("{\"globals\":[\n"), "{metadata}");
+	let file = directory.path().join("main.bcz");
+	for expected_global in [
+		"\"name\":\"main\",\"function\":true,\"parameter_count\":0,\"link\":false,\"export\":false,\"entry_point\":true}",
+		"\"name\":\"puts\",\"function\":true,\"parameter_count\":1,\"link\":true,\"export\":false,\"entry_point\":false}",
+		"\"name\":\"answer\",\"function\":false,\"parameter_count\":null,\"link\":false,\"export\":true,\"entry_point\":false}",
+	] {
+		assert!(metadata.contains(&format!("{{\"file\":\"{}\",{expected_global}", file.display())), "{expected_global} not in {metadata}");
+	}
+}
@@ -0,0 +1,204 @@
+//! Tests that compile programs into executables and check what they do when run.
+mod common;
+
+use common::TestDirectory;
+
+/// Runs a program where the entry point returns `expression` and returns the exit code.
+fn evaluate(test_name: &str, expression: &str) -> i32 {
+	TestDirectory::new(test_name).exit_code(&format!("main = @entry_point () {{\n\t{expression}\n}};\n"))
+}
+
+#[test]
+fn logical_not() {
+	assert_eq!(evaluate("logical_not_zero", "!0"), 1);
+	assert_eq!(evaluate("logical_not_five", "!5"), 0);
+	assert_eq!(evaluate("logical_not_twice", "!!3"), 1);
+}
+
+#[test]
+fn bitwise_not() {
+	// Exit codes are the low 8 bits of the returned value
+	assert_eq!(evaluate("bitwise_not_zero", "~0"), 0xFF);
+	assert_eq!(evaluate("bitwise_not_five", "~5"), 0xFA);
+}
+
+#[test]
+fn ternary_chain_takes_middle_branch() {
+	let source = "choose = (x) {\n\tx == 1 ? 10 : x == 2 ? 20 : 30\n};\nmain = @entry_point () {\n\tchoose(2)\n};\n";
+	assert_eq!(TestDirectory::new("ternary_chain_takes_middle_branch").exit_code(source), 20);
+}
+
+#[test]
+fn entry_point_return_value_is_exit_code() {
+	assert_eq!(evaluate("entry_point_return_value_is_exit_code", "42"), 42);
+}
+
+#[test]
+fn omitted_argument_uses_default() {
+	let source = "add = (x, y = 5) {\n\tx + y\n};\nmain = @entry_point () {\n\tadd(1) * 10 + add(1, 2)\n};\n";
+	assert_eq!(TestDirectory::new("omitted_argument_uses_default").exit_code(source), 63);
+}
+
+#[test]
+fn omitted_argument_of_imported_function_uses_default() {
+	let directory = TestDirectory::new("omitted_argument_of_imported_function_uses_default");
+	directory.write("math.bcz", "@export multiply = (x, y = 2) {\n\tx * y\n};\n");
+	let source = "multiply = @import(\"math.bcz\", \"multiply\");\nmain = @entry_point () {\n\tmultiply(20) + multiply(1, 2)\n};\n";
+	assert_eq!(directory.exit_code(source), 42);
+}
+
+#[test]
+fn break_out_of_labeled_blocks() {
+	// A label applies to the whole expression after it so the inner labeled block is in parentheses
+	let break_inner = "@label(outer) {\n\t\t(@label(inner) {\n\t\t\t@break(inner, 3);\n\t\t\t1\n\t\t}) + 100\n\t}";
+	assert_eq!(evaluate("break_out_of_inner_labeled_block", break_inner), 103);
+	let break_outer = "@label(outer) {\n\t\t(@label(inner) {\n\t\t\t@break(outer, 7);\n\t\t\t1\n\t\t}) + 100\n\t}";
+	assert_eq!(evaluate("break_out_of_outer_labeled_block", break_outer), 7);
+}
+
+#[test]
+fn break_and_continue_in_loop() {
+	let source = "main = @entry_point () {\n\ti = 0;\n\ttotal = 0;\n\t@loop {\n\t\ti = i + 1;\n\t\ti > 9 ? @break : {};\n\t\ti ~& 1 ? {} : @continue;\n\t\ttotal = total + i;\n\t};\n\ttotal\n};\n";
+	// The sum of the odd numbers from 1 to 9
+	assert_eq!(TestDirectory::new("break_and_continue_in_loop").exit_code(source), 25);
+}
+
+#[test]
+fn break_and_continue_in_nested_loops() {
+	let source = "main = @entry_point () {\n\tcount = 0;\n\ti = 0;\n\t@loop {\n\t\ti == 3 ? @break : {};\n\t\ti = i + 1;\n\t\tj = 0;\n\t\t@loop {\n\t\t\tj = j + 1;\n\t\t\tj == 2 ? @continue : {};\n\t\t\tj > 3 ? @break : {};\n\t\t\tcount = count + 1;\n\t\t};\n\t};\n\tcount\n};\n";
+	// The inner loop counts 1 and 3 each of the 3 times the outer loop runs
+	assert_eq!(TestDirectory::new("break_and_continue_in_nested_loops").exit_code(source), 6);
+}
+
+#[test]
+fn for_loops_over_ranges() {
+	let source = "main = @entry_point () {\n\ttotal = 0;\n\t@for(i, 0..5) {\n\t\ttotal = total + i;\n\t};\n\ttotal\n};\n";
+	assert_eq!(TestDirectory::new("for_loop_sums_range").exit_code(source), 10);
+	// The end of the range can be an expression, @continue and @break work in the body and an empty range does not run the body
+	let source = "main = @entry_point () {\n\ttotal = 0;\n\tend = 3;\n\t@for(i, 1..end + 2) {\n\t\ti == 2 ? @continue : {};\n\t\ti == 4 ? @break : {};\n\t\t\
+		total = total + i * 10;\n\t};\n\t@for(i, 5..5) {\n\t\ttotal = total + 100;\n\t};\n\ttotal\n};\n";
+	assert_eq!(TestDirectory::new("for_loop_continue_and_break").exit_code(source), 40);
+}
+
+#[test]
+fn do_while_runs_body_at_least_once() {
+	let source = "main = @entry_point () {\n\tcount = 0;\n\t@do_while(0) {\n\t\tcount = count + 1;\n\t};\n\tcount\n};\n";
+	assert_eq!(TestDirectory::new("do_while_runs_body_at_least_once").exit_code(source), 1);
+	let source = "main = @entry_point () {\n\tcount = 0;\n\t@do_while(count < 5) {\n\t\tcount = count + 1;\n\t};\n\tcount\n};\n";
+	assert_eq!(TestDirectory::new("do_while_runs_until_condition_is_false").exit_code(source), 5);
+}
+
+#[test]
+fn failed_runtime_assertion_traps() {
+	let source = "main = @entry_point () {\n\tzero = 0;\n\t@runtime_assert(zero);\n\t5\n};\n";
+	let status = TestDirectory::new("failed_runtime_assertion_traps").run(source, &["--trap-mode", "trap"]);
+	assert!(!status.success() && status.code() != Some(5), "{status}");
+}
+
+#[test]
+fn passing_runtime_assertion() {
+	let source = "main = @entry_point () {\n\tone = 1;\n\t@runtime_assert(one);\n\t5\n};\n";
+	assert_eq!(TestDirectory::new("passing_runtime_assertion").exit_code(source), 5);
+}
+
+#[test]
+fn no_asserts_removes_runtime_assertions() {
+	let source = "main = @entry_point () {\n\tzero = 0;\n\t@runtime_assert(zero);\n\t5\n};\n";
+	let status = TestDirectory::new("no_asserts_removes_runtime_assertions").run(source, &["--no-asserts"]);
+	assert_eq!(status.code(), Some(5));
+}
+
+#[test]
+fn void_ternary_branch() {
+	let source = "main = @entry_point () {\n\tx = 0;\n\t@loop {\n\t\tx == 3 ? @break : {};\n\t\tx = x + 1;\n\t};\n\tx\n};\n";
+	assert_eq!(TestDirectory::new("void_ternary_branch").exit_code(source), 3);
+}
+
+#[test]
+fn compound_operators() {
+	assert_eq!(evaluate("compound_operators_logical", "(3 && 0) + (0 || 5) * 2"), 10);
+	assert_eq!(evaluate("compound_operators_comparisons", "(1 == 1) + (1 != 1) * 2 + (2 <= 1) * 4 + (2 >= 1) * 8"), 9);
+}
+
+#[test]
+fn arithmetic_precedence() {
+	assert_eq!(evaluate("arithmetic_precedence_multiply", "2 + 3 * 4"), 14);
+	assert_eq!(evaluate("arithmetic_precedence_subtract", "10 - 2 - 3"), 5);
+	assert_eq!(evaluate("arithmetic_precedence_divide", "8 / 4 / 2"), 1);
+}
+
+#[test]
+fn chained_comparisons() {
+	assert_eq!(evaluate("chained_comparison_true", "1 < 2 < 3"), 1);
+	assert_eq!(evaluate("chained_comparison_false", "3 < 2 < 1"), 0);
+	// The middle operand increments x once
+	assert_eq!(evaluate("chained_comparison_middle_once", "x = 0;\n\tresult = 0 < {\n\t\tx = x + 1;\n\t\tx\n\t} < 3;\n\tresult + x * 2"), 3);
+}
+
+#[test]
+fn import_from_include_directory() {
+	let directory = TestDirectory::new("import_from_include_directory");
+	let include_directory = directory.path().join("shared");
+	directory.write("shared/constants.bcz", "@export A = 60;\n");
+	let source = "A = @import(\"constants.bcz\", \"A\");\nmain = @entry_point () {\n\tA + 2\n};\n";
+	let status = directory.run(source, &["-I", include_directory.to_str().unwrap()]);
+	assert_eq!(status.code(), Some(62));
+}
+
+#[test]
+fn checked_division_by_zero_traps() {
+	for (test_name, operator) in [("checked_division_by_zero_traps", "/"), ("checked_modulo_by_zero_traps", "%"), ("checked_signed_division_by_zero_traps", "$/")] {
+		let source = format!("divide = (a, b) {{\n\ta {operator} b\n}};\nmain = @entry_point () {{\n\tdivide(10, 0);\n\t5\n}};\n");
+		let status = TestDirectory::new(test_name).run(&source, &["--checked-div", "--trap-mode", "trap"]);
+		assert!(!status.success() && status.code() != Some(5), "{operator}: {status}");
+		// The trap is an illegal instruction rather than the divide error the CPU raises for an unchecked division
+		#[cfg(unix)]
+		{
+			use std::os::unix::process::ExitStatusExt;
+			assert_eq!(status.signal(), Some(4), "{operator}: {status}");
+		}
+	}
+}
+
+#[test]
+fn zero_coalescing() {
+	assert_eq!(evaluate("zero_coalescing_non_zero", "5 ?? 9"), 5);
+	assert_eq!(evaluate("zero_coalescing_zero", "0 ?? 9"), 9);
+	// The right operand is only evaluated when the left operand is zero
+	assert_eq!(evaluate("zero_coalescing_lazy", "x = 1;\n\ty = 5 ?? {\n\t\tx = 7;\n\t\t9\n\t};\n\ty + x * 10"), 15);
+	assert_eq!(evaluate("zero_coalescing_evaluated", "x = 1;\n\ty = 0 ?? {\n\t\tx = 7;\n\t\t9\n\t};\n\ty + x * 10"), 79);
+}
+
+#[test]
+fn call_function_through_its_address() {
+	let source = "double = (a) {\n\ta * 2\n};\nmain = @entry_point () {\n\taddress = &double;\n\taddress(21) + (address == double)\n};\n";
+	assert_eq!(TestDirectory::new("call_function_through_its_address").exit_code(source), 43);
+}
+
+#[test]
+fn bitwise_operators_on_float_bits() {
+	let source = "absolute = (x) {\n\tx ~& 0x7FFFFFFFFFFFFFFF\n};\nmain = @entry_point () {\n\tnegative = 0f0 ~- 0f1.5;\n\tsign_bit = 0x8000000000000000;\n\t\
+		(absolute(negative) == 0f1.5) + (absolute(0f1.5) == 0f1.5) * 2 + ((negative ~^ sign_bit) == 0f1.5) * 4 + ((0f1.5 ~| sign_bit) == negative) * 8\n};\n";
+	// Masking off the sign bit gives the absolute value, flipping it negates and setting it makes the float negative
+	assert_eq!(TestDirectory::new("bitwise_operators_on_float_bits").exit_code(source), 15);
+}
+
+#[test]
+fn signed_and_unsigned_comparisons_near_int_max() {
+	let source = "compare = (a, b) {\n\t(a < b) + (a $< b) * 2 + (a >= b) * 4 + (a $>= b) * 8\n};\nmain = @entry_point () {\n\tint_max = 0x7FFFFFFFFFFFFFFF;\n\t\
+		compare(int_max, int_max + 1) + compare(int_max + 1, int_max) * 16\n};\n";
+	// One more than int_max is greater when unsigned and the most negative int when signed
+	assert_eq!(TestDirectory::new("signed_and_unsigned_comparisons_near_int_max").exit_code(source), 9 + 6 * 16);
+}
+
+#[test]
+fn read_through_global_address_constant() {
+	let source = "other = 42;\ng = &other;\nmain = @entry_point () {\n\t*g + (g == &other)\n};\n";
+	assert_eq!(TestDirectory::new("read_through_global_address_constant").exit_code(source), 43);
+}
+
+#[test]
+fn assigning_array_copies_its_entries() {
+	let source = "main = @entry_point () {\n\ta = @stack(2);\n\tb = @stack(2);\n\t*a = 20;\n\t*(a + 8) = 22;\n\tb = a;\n\t*a = 0;\n\t*b + *(b + 8)\n};\n";
+	assert_eq!(TestDirectory::new("assigning_array_copies_its_entries").exit_code(source), 42);
+}